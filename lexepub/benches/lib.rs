@@ -38,6 +38,75 @@ fn bench_loading(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_archive_reads(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Archive");
+    let bytes = read_examples_testbook_bytes();
+    let names = futures::executor::block_on(async {
+        lexepub::core::extractor::EpubExtractor::from_bytes(bytes.clone())
+            .await
+            .unwrap()
+            .entry_names()
+            .await
+            .unwrap()
+    });
+
+    // Every read on a fresh extractor parses the central directory again
+    group.bench_function("read_all_entries (fresh extractor per read)", |b| {
+        b.iter(|| {
+            futures::executor::block_on(async {
+                for name in &names {
+                    let extractor =
+                        lexepub::core::extractor::EpubExtractor::from_bytes(bytes.clone())
+                            .await
+                            .unwrap();
+                    let _ = extractor.read_file(name).await;
+                }
+            })
+        })
+    });
+
+    group.bench_function("read_all_entries (shared extractor)", |b| {
+        b.iter(|| {
+            futures::executor::block_on(async {
+                let extractor = lexepub::core::extractor::EpubExtractor::from_bytes(bytes.clone())
+                    .await
+                    .unwrap();
+                for name in &names {
+                    let _ = extractor.read_file(name).await;
+                }
+            })
+        })
+    });
+
+    // File-backed reads: one file open per entry versus one for the batch
+    let path = Path::new("examples/epubs/test-book.epub").to_path_buf();
+    group.bench_function("read_all_entries (path, file open per read)", |b| {
+        b.iter(|| {
+            futures::executor::block_on(async {
+                let extractor = lexepub::core::extractor::EpubExtractor::open(path.clone())
+                    .await
+                    .unwrap();
+                for name in &names {
+                    let _ = extractor.read_file(name).await;
+                }
+            })
+        })
+    });
+
+    group.bench_function("read_all_entries (path, read_files)", |b| {
+        b.iter(|| {
+            futures::executor::block_on(async {
+                let extractor = lexepub::core::extractor::EpubExtractor::open(path.clone())
+                    .await
+                    .unwrap();
+                let _ = extractor.read_files(&names).await.unwrap();
+            })
+        })
+    });
+
+    group.finish();
+}
+
 fn bench_metadata(c: &mut Criterion) {
     let mut group = c.benchmark_group("Metadata");
     let bytes = read_examples_testbook_bytes();
@@ -82,6 +151,15 @@ fn bench_extraction(c: &mut Criterion) {
         )
     });
 
+    let path = Path::new("examples/epubs/test-book.epub");
+    group.bench_function("extract_text_only (open_path)", |b| {
+        b.iter_batched(
+            || futures::executor::block_on(lexepub::epub::LexEpub::open(path)).unwrap(),
+            |mut epub| futures::executor::block_on(epub.extract_text_only()).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+
     group.bench_function("extract_ast", |b| {
         b.iter_batched(
             || {
@@ -144,6 +222,7 @@ fn bench_analysis(c: &mut Criterion) {
 criterion_group!(
     benches,
     bench_loading,
+    bench_archive_reads,
     bench_metadata,
     bench_extraction,
     bench_analysis
@@ -1,4 +1,4 @@
-use crate::error::Result;
+use crate::error::{LexEpubError, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -21,6 +21,50 @@ impl Chapter {
             content,
         }
     }
+
+    /// Use the manifest's media type. An empty (missing) type keeps the
+    /// XHTML default.
+    pub fn with_media_type(mut self, media_type: &str) -> Self {
+        if !media_type.trim().is_empty() {
+            self.media_type = media_type.trim().to_string();
+        }
+        self
+    }
+
+    /// Whether the content should go through the HTML extractor
+    pub fn is_markup(&self) -> bool {
+        is_markup_media_type(&self.media_type)
+    }
+}
+
+/// Whether a manifest media type is HTML-ish markup. Spine items are often
+/// mislabelled (`text/html`, plain XML, or no type at all), so all of these
+/// are treated as markup rather than rejected.
+pub fn is_markup_media_type(media_type: &str) -> bool {
+    let essence = media_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    matches!(
+        essence.as_str(),
+        "" | "application/xhtml+xml"
+            | "text/html"
+            | "application/html"
+            | "application/xml"
+            | "text/xml"
+            | "image/svg+xml"
+    )
+}
+
+/// Whether an attribute name is an inline event handler: `on` followed by
+/// an event name made only of letters
+fn is_event_handler_attribute(name: &str) -> bool {
+    name.get(..2)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("on"))
+        && name.len() > 2
+        && name[2..].bytes().all(|b| b.is_ascii_alphabetic())
 }
 
 /// AST node representation for parsed HTML
@@ -41,6 +85,120 @@ pub enum AstNode {
     },
 }
 
+impl AstNode {
+    /// Strip executable content from the tree: `<script>` elements are
+    /// removed entirely and inline event-handler attributes (`onclick`,
+    /// `onload`...) are dropped. Other attributes that merely start with
+    /// `on`, such as `onix-id`, are kept.
+    pub fn sanitize(&mut self) {
+        if let AstNode::Element {
+            attrs, children, ..
+        } = self
+        {
+            attrs.retain(|key, _| !is_event_handler_attribute(key));
+            children.retain(|child| {
+                !matches!(child, AstNode::Element { tag, .. } if tag.eq_ignore_ascii_case("script"))
+            });
+            for child in children {
+                child.sanitize();
+            }
+        }
+    }
+
+    /// Serialize the tree back to HTML. Text and attribute values are
+    /// escaped (except the raw text of `<script>`/`<style>`), attributes are
+    /// written in sorted order so output is deterministic, and void elements
+    /// self-close (`<br />`). The synthetic `root` element produced by the
+    /// parser is transparent. Computed `styles` are not written; inline
+    /// `style` attributes are kept as they are.
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        self.write_html(&mut html, false);
+        html
+    }
+
+    fn write_html(&self, out: &mut String, raw_text: bool) {
+        match self {
+            AstNode::Element {
+                tag,
+                attrs,
+                children,
+                ..
+            } => {
+                if tag == "root" {
+                    for child in children {
+                        child.write_html(out, false);
+                    }
+                    return;
+                }
+                out.push('<');
+                out.push_str(tag);
+                let mut sorted: Vec<_> = attrs.iter().collect();
+                sorted.sort();
+                for (key, value) in sorted {
+                    out.push(' ');
+                    out.push_str(key);
+                    out.push_str("=\"");
+                    out.push_str(&html_escape::encode_double_quoted_attribute(value));
+                    out.push('"');
+                }
+                let name = tag.to_ascii_lowercase();
+                if VOID_ELEMENTS.contains(&name.as_str()) {
+                    out.push_str(" />");
+                    return;
+                }
+                out.push('>');
+                let raw_children = matches!(name.as_str(), "script" | "style");
+                for child in children {
+                    child.write_html(out, raw_children);
+                }
+                out.push_str("</");
+                out.push_str(tag);
+                out.push('>');
+            }
+            AstNode::Text { content } if raw_text => out.push_str(content),
+            AstNode::Text { content } => out.push_str(&html_escape::encode_text(content)),
+            AstNode::Comment { content } => {
+                if content.starts_with("<!--") {
+                    out.push_str(content);
+                } else {
+                    out.push_str("<!--");
+                    out.push_str(content);
+                    out.push_str("-->");
+                }
+            }
+        }
+    }
+}
+
+/// HTML elements that never have content or a closing tag
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Kind of a block-level run of text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextBlockKind {
+    /// `h1`..`h6`, carrying the heading level
+    Heading(u8),
+    Paragraph,
+    ListItem,
+    Quote,
+    Preformatted,
+}
+
+/// A block-level unit of chapter text (a paragraph, heading, list item...)
+/// with inline formatting flattened away.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextBlock {
+    pub kind: TextBlockKind,
+    pub text: String,
+    /// `id` attribute of the element the block came from, if any
+    #[serde(default)]
+    pub id: Option<String>,
+}
+
 /// A parsed EPUB chapter with content and metadata
 ///
 /// Contains the extracted text content, optional AST representation,
@@ -57,15 +215,79 @@ pub struct ParsedChapter {
     pub ast: Option<AstNode>,
     /// Word count in the content
     pub word_count: usize,
-    /// Character count in the content
+    /// Character count in the content, in Unicode scalar values. A combining
+    /// accent or each part of a ZWJ emoji sequence counts separately; see
+    /// [`ParsedChapter::grapheme_count`] for user-perceived characters.
     pub char_count: usize,
+    /// Whether the chapter carries JavaScript, either declared through the
+    /// manifest `properties="scripted"` flag or detected via a `<script>` tag
+    #[serde(default)]
+    pub is_scripted: bool,
+    /// First character of a drop cap, when the chapter styles one (a
+    /// `dropcap` class or `::first-letter` rule); see
+    /// [`crate::core::html_parser::detect_dropcap`]
+    #[serde(default)]
+    pub dropcap: Option<char>,
+}
+
+#[cfg(feature = "unicode-segmentation")]
+impl ParsedChapter {
+    /// User-perceived characters (extended grapheme clusters) in the content.
+    ///
+    /// Unlike `char_count`, `e` + combining acute or a family emoji joined
+    /// with ZWJs counts as one.
+    pub fn grapheme_count(&self) -> usize {
+        grapheme_count(&self.content)
+    }
+}
+
+/// Count extended grapheme clusters in `text`
+#[cfg(feature = "unicode-segmentation")]
+pub fn grapheme_count(text: &str) -> usize {
+    unicode_segmentation::UnicodeSegmentation::graphemes(text, true).count()
+}
+
+/// Fail with [`LexEpubError::ChapterError`] when the archive entry `path`
+/// is larger than `max` uncompressed bytes. Only the central directory is
+/// consulted, so an oversized chapter is never inflated.
+pub(crate) async fn ensure_chapter_size(
+    extractor: &crate::core::extractor::EpubExtractor,
+    path: &str,
+    max: Option<u64>,
+) -> Result<()> {
+    let Some(max) = max else {
+        return Ok(());
+    };
+    let size = extractor.entry_size(path).await?;
+    if size > max {
+        return Err(LexEpubError::ChapterError(format!(
+            "'{}' is {} bytes, over the {}-byte chapter limit",
+            path, size, max
+        )));
+    }
+    Ok(())
 }
 
 /// Chapter stream for async iteration
 pub struct ChapterStream {
     extractor: crate::core::extractor::EpubExtractor,
+    /// Archive path of each chapter; empty for a spine idref that has no
+    /// manifest item
     entries: Vec<String>,
+    /// `(idref, manifest media type)` of each entry, when known
+    items: Vec<(String, String)>,
     index: usize,
+    text_options: crate::core::html_parser::TextExtractOptions,
+    /// End the stream after the first failing chapter instead of carrying
+    /// on with the next one
+    strict: bool,
+    word_counter: Option<crate::core::html_parser::WordCounter>,
+    /// Chapters larger than this many uncompressed bytes fail unread
+    max_chapter_size: Option<u64>,
+    /// Idrefs whose manifest item carries the `scripted` property
+    scripted_items: std::collections::HashSet<String>,
+    /// Book stylesheet for AST chapters; `None` keeps the stream text-only
+    ast_stylesheet: Option<std::sync::Arc<crate::core::css::Stylesheet>>,
     /// in-flight future for the currently reading/parsing chapter
     inflight: Option<
         std::pin::Pin<Box<dyn std::future::Future<Output = Result<ParsedChapter>> + 'static>>,
@@ -82,10 +304,75 @@ impl ChapterStream {
         Self {
             extractor,
             entries,
+            items: Vec::new(),
             index: 0,
+            text_options: crate::core::html_parser::TextExtractOptions::default(),
+            strict: false,
+            word_counter: None,
+            max_chapter_size: None,
+            scripted_items: std::collections::HashSet::new(),
+            ast_stylesheet: None,
             inflight: None,
         }
     }
+
+    /// Report each chapter's spine idref and manifest media type; `items`
+    /// runs parallel to the entry paths. Without it chapters have an empty
+    /// id and the XHTML media type. An empty entry path marks a spine idref
+    /// with no manifest item, which fails as such.
+    pub fn with_manifest_items(mut self, items: Vec<(String, String)>) -> Self {
+        self.items = items;
+        self
+    }
+
+    /// Mark the chapters of these idrefs as scripted even without a
+    /// `<script>` tag, as the manifest `scripted` property declares
+    pub fn with_scripted_items(mut self, ids: std::collections::HashSet<String>) -> Self {
+        self.scripted_items = ids;
+        self
+    }
+
+    /// Set the word counter used for each chapter's `word_count`
+    pub fn with_word_counter(
+        mut self,
+        counter: Option<crate::core::html_parser::WordCounter>,
+    ) -> Self {
+        self.word_counter = counter;
+        self
+    }
+
+    /// Every chapter that fails to read or parse is yielded as
+    /// [`LexEpubError::ChapterFailed`] (an unresolved spine idref as the
+    /// `MissingFile` error naming it). In strict mode the stream ends after
+    /// the first failure; otherwise it carries on with the next chapter.
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Fail chapters whose uncompressed size exceeds `max` bytes. The size
+    /// comes from the central directory, so such chapters are never read.
+    pub fn with_max_chapter_size(mut self, max: Option<u64>) -> Self {
+        self.max_chapter_size = max;
+        self
+    }
+
+    /// Also parse each chapter's AST, sanitized, with links resolved to
+    /// archive paths and `stylesheet` applied, as [`crate::LexEpub::extract_ast`]
+    /// does. Streams are text-only unless this is set.
+    pub fn with_ast(mut self, stylesheet: crate::core::css::Stylesheet) -> Self {
+        self.ast_stylesheet = Some(std::sync::Arc::new(stylesheet));
+        self
+    }
+
+    /// Set the options used when extracting each chapter's plain text
+    pub fn with_text_options(
+        mut self,
+        options: crate::core::html_parser::TextExtractOptions,
+    ) -> Self {
+        self.text_options = options;
+        self
+    }
 }
 
 impl futures::Stream for ChapterStream {
@@ -95,65 +382,89 @@ impl futures::Stream for ChapterStream {
         mut self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Option<Self::Item>> {
-        // If no in-flight future, create one for the next chapter (if any)
-        if self.inflight.is_none() {
-            if self.index >= self.entries.len() {
-                return std::task::Poll::Ready(None);
-            }
-
-            let path = self.entries[self.index].clone();
-            let ex = self.extractor.clone();
+        loop {
+            // If no in-flight future, create one for the next chapter (if any)
+            if self.inflight.is_none() {
+                if self.index >= self.entries.len() {
+                    return std::task::Poll::Ready(None);
+                }
 
-            // create a future that reads & parses a single chapter
-            let fut = async move {
-                // read file bytes from the archive
-                let content = ex.read_file(&path).await?;
+                let path = self.entries[self.index].clone();
+                let (id, media_type) = self.items.get(self.index).cloned().unwrap_or_default();
+                if path.is_empty() {
+                    self.index += 1;
+                    if self.strict {
+                        self.index = self.entries.len();
+                    }
+                    return std::task::Poll::Ready(Some(Err(crate::epub::unresolved_spine_item(
+                        &id,
+                    ))));
+                }
+                let ex = self.extractor.clone();
+                let text_options = self.text_options.clone();
+                let word_counter = self.word_counter.clone();
+                let ast_stylesheet = self.ast_stylesheet.clone();
+                let max_chapter_size = self.max_chapter_size;
+                let scripted = self.scripted_items.contains(&id);
 
-                // parse html -> plain text
-                let html_content = String::from_utf8_lossy(&content);
-                let text_content = crate::core::html_parser::extract_text_content(&html_content)?;
+                // create a future that reads & parses a single chapter
+                let fut = async move {
+                    ensure_chapter_size(&ex, &path, max_chapter_size).await?;
+                    // read file bytes from the archive
+                    let content = ex.read_file(&path).await?;
 
-                let word_count = text_content.split_whitespace().count();
-                let char_count = text_content.chars().count();
+                    if let Some(stylesheet) = ast_stylesheet {
+                        let chapter =
+                            Chapter::new(path.clone(), id, content).with_media_type(&media_type);
+                        let mut parsed = crate::core::html_parser::ChapterParser::new()
+                            .with_both()
+                            .text_options(text_options)
+                            .word_counter(word_counter)
+                            .parse_chapter(chapter)?;
+                        parsed.is_scripted |= scripted;
+                        crate::epub::finish_full_chapter(&mut parsed, &path, &stylesheet);
+                        return Ok(parsed);
+                    }
 
-                let chapter = crate::core::chapter::Chapter {
-                    href: path.clone(),
-                    id: String::new(),
-                    media_type: "application/xhtml+xml".to_string(),
-                    content: content.clone(),
+                    // Text-only parse, as the eager text extraction does
+                    let chapter = Chapter::new(path, id, content).with_media_type(&media_type);
+                    let mut parsed = crate::core::html_parser::ChapterParser::new()
+                        .text_only()
+                        .text_options(text_options)
+                        .word_counter(word_counter)
+                        .parse_chapter(chapter)?;
+                    parsed.is_scripted |= scripted;
+                    Ok(parsed)
                 };
 
-                Ok(crate::core::chapter::ParsedChapter {
-                    chapter_info: chapter,
-                    title: None,
-                    content: text_content,
-                    ast: None,
-                    word_count,
-                    char_count,
-                })
-            };
-
-            self.inflight = Some(Box::pin(fut));
-        }
+                self.inflight = Some(Box::pin(fut));
+            }
 
-        // Poll the in-flight future
-        if let Some(fut) = self.inflight.as_mut() {
-            match fut.as_mut().poll(cx) {
-                std::task::Poll::Ready(Ok(parsed)) => {
-                    // consume the future and advance index
-                    self.inflight = None;
-                    self.index += 1;
-                    return std::task::Poll::Ready(Some(Ok(parsed)));
+            // Poll the in-flight future
+            if let Some(fut) = self.inflight.as_mut() {
+                match fut.as_mut().poll(cx) {
+                    std::task::Poll::Ready(Ok(parsed)) => {
+                        // consume the future and advance index
+                        self.inflight = None;
+                        self.index += 1;
+                        return std::task::Poll::Ready(Some(Ok(parsed)));
+                    }
+                    std::task::Poll::Ready(Err(e)) => {
+                        self.inflight = None;
+                        let href = self.entries[self.index].clone();
+                        self.index += 1;
+                        if self.strict {
+                            // Nothing more after the first failure
+                            self.index = self.entries.len();
+                        }
+                        return std::task::Poll::Ready(Some(Err(LexEpubError::ChapterFailed {
+                            href,
+                            source: Box::new(e),
+                        })));
+                    }
+                    std::task::Poll::Pending => return std::task::Poll::Pending,
                 }
-                std::task::Poll::Ready(Err(e)) => {
-                    self.inflight = None;
-                    self.index += 1; // skip this entry on error
-                    return std::task::Poll::Ready(Some(Err(e)));
-                }
-                std::task::Poll::Pending => return std::task::Poll::Pending,
             }
         }
-
-        std::task::Poll::Pending
     }
 }
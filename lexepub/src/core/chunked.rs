@@ -0,0 +1,127 @@
+use bytes::Bytes;
+use futures::io::{AsyncBufRead, AsyncRead, AsyncSeek, SeekFrom};
+use futures::Stream;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Seekable reader over bytes that arrive incrementally, e.g. chunks of a
+/// progressive download pushed through a channel.
+///
+/// Received chunks are buffered in memory. Seeks within (or past) the
+/// received range are immediate; reads beyond it wait for more chunks, and
+/// `SeekFrom::End` waits for the stream to finish since the total length is
+/// only known then. Reads past the end of a finished stream report EOF.
+pub struct ChunkedReader<S> {
+    stream: S,
+    buffer: Vec<u8>,
+    pos: u64,
+    finished: bool,
+}
+
+impl<S> ChunkedReader<S>
+where
+    S: Stream<Item = Bytes> + Unpin,
+{
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            buffer: Vec::new(),
+            pos: 0,
+            finished: false,
+        }
+    }
+
+    /// Number of bytes received so far
+    pub fn received(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Pull chunks until more than `want` bytes are buffered or the stream ends
+    fn poll_until(&mut self, cx: &mut Context<'_>, want: u64) -> Poll<()> {
+        while !self.finished && self.buffer.len() as u64 <= want {
+            match Pin::new(&mut self.stream).poll_next(cx) {
+                Poll::Ready(Some(chunk)) => self.buffer.extend_from_slice(&chunk),
+                Poll::Ready(None) => self.finished = true,
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        Poll::Ready(())
+    }
+}
+
+impl<S> AsyncBufRead for ChunkedReader<S>
+where
+    S: Stream<Item = Bytes> + Unpin,
+{
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        let pos = this.pos;
+        if this.poll_until(cx, pos).is_pending() {
+            return Poll::Pending;
+        }
+        let start = (pos as usize).min(this.buffer.len());
+        Poll::Ready(Ok(&this.buffer[start..]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.get_mut().pos += amt as u64;
+    }
+}
+
+impl<S> AsyncRead for ChunkedReader<S>
+where
+    S: Stream<Item = Bytes> + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let available = match self.as_mut().poll_fill_buf(cx) {
+            Poll::Ready(Ok(available)) => available,
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+            Poll::Pending => return Poll::Pending,
+        };
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl<S> AsyncSeek for ChunkedReader<S>
+where
+    S: Stream<Item = Bytes> + Unpin,
+{
+    fn poll_seek(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        pos: SeekFrom,
+    ) -> Poll<io::Result<u64>> {
+        let this = self.get_mut();
+        let (base, offset) = match pos {
+            SeekFrom::Start(n) => {
+                this.pos = n;
+                return Poll::Ready(Ok(n));
+            }
+            SeekFrom::Current(n) => (this.pos, n),
+            SeekFrom::End(n) => {
+                if this.poll_until(cx, u64::MAX).is_pending() {
+                    return Poll::Pending;
+                }
+                (this.buffer.len() as u64, n)
+            }
+        };
+        match base.checked_add_signed(offset) {
+            Some(new_pos) => {
+                this.pos = new_pos;
+                Poll::Ready(Ok(new_pos))
+            }
+            None => Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            ))),
+        }
+    }
+}
@@ -3,9 +3,31 @@ use quick_xml::events::Event;
 use quick_xml::reader::Reader;
 use std::io::Cursor;
 
+/// Media type of an EPUB package document (the OPF)
+pub const PACKAGE_MEDIA_TYPE: &str = "application/oebps-package+xml";
+
+/// One `<rootfile>` of container.xml, i.e. one rendition of the book
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RootFile {
+    pub full_path: String,
+    /// Declared `media-type`, empty when missing
+    pub media_type: String,
+}
+
+impl RootFile {
+    /// Whether this rootfile is an EPUB package document
+    pub fn is_package(&self) -> bool {
+        self.media_type.trim() == PACKAGE_MEDIA_TYPE
+    }
+}
+
 #[derive(Debug)]
 pub struct ContainerInfo {
+    /// The default package document: the first rootfile declaring
+    /// [`PACKAGE_MEDIA_TYPE`], else the first rootfile
     pub rootfile_path: String,
+    /// Every rootfile with a `full-path`, in document order
+    pub rootfiles: Vec<RootFile>,
 }
 
 pub struct ContainerParser {
@@ -20,38 +42,65 @@ impl ContainerParser {
         }
     }
 
-    /// Parse container.xml to find rootfile path
+    /// Parse container.xml to find the package document path. The rootfile
+    /// is chosen by its `application/oebps-package+xml` media type, not its
+    /// file extension, so `package.xml` or extensionless names work; the
+    /// first rootfile is used when none declares that media type. All
+    /// rootfiles are kept in [`ContainerInfo::rootfiles`].
     pub fn parse_container(&mut self, data: &[u8]) -> Result<ContainerInfo> {
         self.reader = Reader::from_reader(std::io::Cursor::new(data.to_vec()));
         self.reader.config_mut().trim_text(true);
 
-        let mut rootfile_path = None;
+        let mut rootfiles = Vec::new();
         let mut buf = Vec::new();
 
         loop {
             match self.reader.read_event_into(&mut buf) {
                 Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e)) => {
                     if e.name().as_ref() == b"rootfile" {
+                        let mut full_path = None;
+                        let mut media_type = None;
                         for attr in e.attributes().flatten() {
-                            if attr.key.as_ref() == b"full-path" {
-                                let value = String::from_utf8_lossy(&attr.value).to_string();
-                                rootfile_path = Some(value);
-                                break;
+                            let value = String::from_utf8_lossy(&attr.value).to_string();
+                            match attr.key.as_ref() {
+                                b"full-path" => full_path = Some(value),
+                                b"media-type" => media_type = Some(value),
+                                _ => {}
                             }
                         }
+                        if let Some(full_path) = full_path {
+                            rootfiles.push(RootFile {
+                                full_path,
+                                media_type: media_type.unwrap_or_default().trim().to_string(),
+                            });
+                        }
                     }
                 }
                 Ok(Event::Eof) => break,
-                Err(e) => return Err(LexEpubError::Xml(e)),
+                Err(e) => {
+                    return Err(LexEpubError::XmlInContext {
+                        file: "META-INF/container.xml".to_string(),
+                        position: self.reader.error_position(),
+                        source: e,
+                    })
+                }
                 _ => buf.clear(),
             }
         }
 
-        let rootfile_path = rootfile_path.ok_or_else(|| {
-            LexEpubError::InvalidFormat("No rootfile found in container.xml".to_string())
-        })?;
+        let rootfile_path = rootfiles
+            .iter()
+            .find(|rootfile| rootfile.is_package())
+            .or(rootfiles.first())
+            .map(|rootfile| rootfile.full_path.clone())
+            .ok_or_else(|| {
+                LexEpubError::InvalidFormat("No rootfile found in container.xml".to_string())
+            })?;
 
-        Ok(ContainerInfo { rootfile_path })
+        Ok(ContainerInfo {
+            rootfile_path,
+            rootfiles,
+        })
     }
 }
 
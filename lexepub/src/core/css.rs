@@ -220,6 +220,74 @@ impl Stylesheet {
     }
 }
 
+impl Stylesheet {
+    /// URLs referenced by the `src` descriptor of every `@font-face` rule,
+    /// including ones nested in `@media`/`@supports`, in source order.
+    pub fn font_face_urls(&self) -> Vec<String> {
+        let mut urls = Vec::new();
+        Self::collect_font_face_urls(&self.rules, &mut urls);
+        urls
+    }
+
+    /// Targets of the `@import` rules, in source order
+    pub fn import_urls(&self) -> Vec<String> {
+        self.rules
+            .iter()
+            .filter_map(|rule| match rule {
+                CssRule::Import(value) => css_urls(value)
+                    .into_iter()
+                    .next()
+                    .or_else(|| Some(unquote(value.split_whitespace().next()?).to_string())),
+                _ => None,
+            })
+            .filter(|url| !url.is_empty())
+            .collect()
+    }
+
+    fn collect_font_face_urls(rules: &[CssRule], urls: &mut Vec<String>) {
+        for rule in rules {
+            match rule {
+                CssRule::FontFace(declarations) => {
+                    if let Some(src) = declarations.get("src") {
+                        urls.extend(css_urls(src));
+                    }
+                }
+                CssRule::Media { rules, .. } | CssRule::Supports { rules, .. } => {
+                    Self::collect_font_face_urls(rules, urls);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Extract the targets of all `url(...)` tokens in a CSS value, unquoted
+pub fn css_urls(value: &str) -> Vec<String> {
+    let mut urls = Vec::new();
+    let lower = value.to_ascii_lowercase();
+    let mut pos = 0;
+    while let Some(start) = lower[pos..].find("url(") {
+        let open = pos + start + 4;
+        let Some(len) = value[open..].find(')') else {
+            break;
+        };
+        let url = unquote(value[open..open + len].trim());
+        if !url.is_empty() {
+            urls.push(url.to_string());
+        }
+        pos = open + len + 1;
+    }
+    urls
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value)
+}
+
 impl Stylesheet {
     /// Apply this stylesheet's rules directly to an AstNode tree (computes inline styles based on selectors).
     pub fn apply_to_ast(&self, ast: &mut crate::core::chapter::AstNode) {
@@ -0,0 +1,84 @@
+/// Best guess at how a chapter's bytes are encoded
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncodingGuess {
+    /// Encoding label, e.g. `UTF-8`, `UTF-16LE`, `WINDOWS-1252`
+    pub encoding: String,
+    /// 0.0-1.0; low values flag chapters that are likely mis-decoded
+    pub confidence: f32,
+}
+
+impl EncodingGuess {
+    fn new(encoding: &str, confidence: f32) -> Self {
+        Self {
+            encoding: encoding.to_string(),
+            confidence,
+        }
+    }
+}
+
+/// Guess the encoding of an (X)HTML document from its byte order mark, its
+/// declared encoding (`<?xml encoding>` or `<meta charset>`), and whether the
+/// bytes are actually valid UTF-8.
+pub fn detect_encoding(data: &[u8]) -> EncodingGuess {
+    if data.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        return EncodingGuess::new("UTF-8", 1.0);
+    }
+    if data.starts_with(&[0xFF, 0xFE]) {
+        return EncodingGuess::new("UTF-16LE", 1.0);
+    }
+    if data.starts_with(&[0xFE, 0xFF]) {
+        return EncodingGuess::new("UTF-16BE", 1.0);
+    }
+
+    let declared = declared_encoding(data);
+    let declared_utf8 = declared
+        .as_deref()
+        .is_none_or(|label| label == "UTF-8" || label == "UTF8");
+
+    if std::str::from_utf8(data).is_ok() {
+        let ascii_only = data.is_ascii();
+        return match declared {
+            Some(_) if declared_utf8 => EncodingGuess::new("UTF-8", 1.0),
+            None => EncodingGuess::new("UTF-8", 0.9),
+            // Pure ASCII reads the same under any ASCII-compatible label
+            Some(label) if ascii_only => EncodingGuess::new(&label, 0.9),
+            // Multi-byte UTF-8 sequences under a legacy label: the label is
+            // most likely wrong
+            Some(_) => EncodingGuess::new("UTF-8", 0.7),
+        };
+    }
+
+    match declared {
+        Some(label) if !declared_utf8 => EncodingGuess::new(&label, 0.8),
+        // Claims (or defaults to) UTF-8 but isn't; legacy Western text is the
+        // usual culprit
+        _ => EncodingGuess::new("WINDOWS-1252", 0.3),
+    }
+}
+
+/// Encoding named in the XML declaration or a `<meta>` charset, uppercased
+fn declared_encoding(data: &[u8]) -> Option<String> {
+    let head = &data[..data.len().min(1024)];
+    let head = String::from_utf8_lossy(head).to_ascii_lowercase();
+
+    let value_after = |key: &str| -> Option<String> {
+        let start = head.find(key)? + key.len();
+        let rest = head[start..].trim_start_matches(['=', ' ', '"', '\'']);
+        let end = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+            .unwrap_or(rest.len());
+        let value = &rest[..end];
+        (!value.is_empty()).then(|| value.to_ascii_uppercase())
+    };
+
+    let xml_decl = head
+        .starts_with("<?xml")
+        .then(|| head.find("?>").map(|end| &head[..end]))
+        .flatten();
+    if let Some(decl) = xml_decl {
+        if decl.contains("encoding") {
+            return value_after("encoding");
+        }
+    }
+    value_after("charset")
+}
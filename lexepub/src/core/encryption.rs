@@ -0,0 +1,156 @@
+use crate::error::{LexEpubError, Result};
+use quick_xml::events::Event;
+use quick_xml::reader::Reader;
+use sha1::{Digest, Sha1};
+use std::io::Cursor;
+
+/// IDPF font obfuscation algorithm (EPUB OCF 3, section 4)
+pub const IDPF_OBFUSCATION: &str = "http://www.idpf.org/2008/embedding";
+/// Legacy Adobe font obfuscation algorithm
+pub const ADOBE_OBFUSCATION: &str = "http://ns.adobe.com/pdf/enc#RC";
+
+/// A single `<EncryptedData>` entry from `META-INF/encryption.xml`
+#[derive(Debug, Clone)]
+pub struct EncryptedResource {
+    /// Archive path of the resource (the `CipherReference` URI)
+    pub uri: String,
+    /// The `EncryptionMethod` algorithm URI
+    pub algorithm: String,
+}
+
+impl EncryptedResource {
+    /// Whether this entry is plain font obfuscation rather than real encryption
+    pub fn is_font_obfuscation(&self) -> bool {
+        self.algorithm == IDPF_OBFUSCATION || self.algorithm == ADOBE_OBFUSCATION
+    }
+}
+
+/// Parsed contents of `META-INF/encryption.xml`
+#[derive(Debug, Clone, Default)]
+pub struct EncryptionInfo {
+    pub resources: Vec<EncryptedResource>,
+}
+
+impl EncryptionInfo {
+    /// Look up the encryption entry for an archive path
+    pub fn get(&self, path: &str) -> Option<&EncryptedResource> {
+        self.resources.iter().find(|r| r.uri == path)
+    }
+
+    /// Entries that are really encrypted (DRM), as opposed to obfuscated
+    /// fonts, which the crate can undo itself
+    pub fn encrypted(&self) -> impl Iterator<Item = &EncryptedResource> {
+        self.resources.iter().filter(|r| !r.is_font_obfuscation())
+    }
+}
+
+pub struct EncryptionParser {
+    reader: Reader<Cursor<Vec<u8>>>,
+}
+
+impl EncryptionParser {
+    /// Create a new encryption.xml parser
+    pub fn new() -> Self {
+        Self {
+            reader: Reader::from_reader(Cursor::new(Vec::new())),
+        }
+    }
+
+    /// Parse encryption.xml into the list of encrypted resources
+    pub fn parse_encryption(&mut self, data: &[u8]) -> Result<EncryptionInfo> {
+        self.reader = Reader::from_reader(std::io::Cursor::new(data.to_vec()));
+        self.reader.config_mut().trim_text(true);
+
+        let mut info = EncryptionInfo::default();
+        let mut algorithm = String::new();
+        let mut buf = Vec::new();
+
+        loop {
+            match self.reader.read_event_into(&mut buf) {
+                Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e)) => {
+                    match e.local_name().as_ref() {
+                        b"EncryptedData" => algorithm.clear(),
+                        b"EncryptionMethod" => {
+                            for attr in e.attributes().flatten() {
+                                if attr.key.as_ref() == b"Algorithm" {
+                                    algorithm = String::from_utf8_lossy(&attr.value).to_string();
+                                }
+                            }
+                        }
+                        b"CipherReference" => {
+                            for attr in e.attributes().flatten() {
+                                if attr.key.as_ref() == b"URI" {
+                                    let uri = String::from_utf8_lossy(&attr.value);
+                                    info.resources.push(EncryptedResource {
+                                        uri: uri.trim_start_matches('/').to_string(),
+                                        algorithm: algorithm.clone(),
+                                    });
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => {
+                    return Err(LexEpubError::XmlInContext {
+                        file: "META-INF/encryption.xml".to_string(),
+                        position: self.reader.error_position(),
+                        source: e,
+                    })
+                }
+                _ => buf.clear(),
+            }
+        }
+
+        Ok(info)
+    }
+}
+
+impl Default for EncryptionParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reverse font obfuscation in place. `unique_identifier` is the package's
+/// unique identifier, which both algorithms derive their key from.
+///
+/// Returns `false` (leaving `data` untouched) for unknown algorithms.
+pub fn deobfuscate_font(data: &mut [u8], algorithm: &str, unique_identifier: &str) -> bool {
+    let (key, header_len): (Vec<u8>, usize) = match algorithm {
+        IDPF_OBFUSCATION => {
+            let cleaned: String = unique_identifier
+                .chars()
+                .filter(|c| !matches!(c, ' ' | '\t' | '\r' | '\n'))
+                .collect();
+            (Sha1::digest(cleaned.as_bytes()).to_vec(), 1040)
+        }
+        ADOBE_OBFUSCATION => match adobe_key(unique_identifier) {
+            Some(key) => (key, 1024),
+            None => return false,
+        },
+        _ => return false,
+    };
+
+    for (i, byte) in data.iter_mut().take(header_len).enumerate() {
+        *byte ^= key[i % key.len()];
+    }
+    true
+}
+
+/// Adobe keys are the 16 raw bytes of the identifier's UUID
+fn adobe_key(unique_identifier: &str) -> Option<Vec<u8>> {
+    let hex: String = unique_identifier
+        .trim()
+        .trim_start_matches("urn:uuid:")
+        .chars()
+        .filter(|c| c.is_ascii_hexdigit())
+        .collect();
+    if hex.len() != 32 {
+        return None;
+    }
+    (0..16)
+        .map(|i| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok())
+        .collect()
+}
@@ -1,10 +1,12 @@
 use crate::error::{LexEpubError, Result};
 use async_zip::base::read::seek::ZipFileReader;
+use async_zip::ZipFile;
 use bytes::Bytes;
 use futures::io::{AllowStdIo, BufReader as FuturesBufReader, Cursor as FuturesCursor};
 use futures::lock::Mutex as AsyncMutex;
+use std::collections::HashMap;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 // Trait-object helper: combine AsyncBufRead + AsyncSeek + Unpin into one
 // object-safe trait so we can store boxed streaming readers.
@@ -17,6 +19,16 @@ impl<T: futures::AsyncBufRead + futures::AsyncSeek + Unpin> AsyncReadSeek for T
 #[derive(Clone)]
 pub struct EpubExtractor {
     data_source: EpubDataSource,
+    /// Central directory, parsed on the first read and shared by clones so
+    /// later reads seek straight to their entry
+    directory: Arc<OnceLock<CentralDirectory>>,
+}
+
+/// A parsed ZIP central directory with a filename lookup
+struct CentralDirectory {
+    file: ZipFile,
+    /// Filename -> entry index (the first entry wins for duplicate names)
+    index: HashMap<String, usize>,
 }
 
 #[derive(Clone)]
@@ -33,6 +45,7 @@ impl EpubExtractor {
     pub async fn open(path: std::path::PathBuf) -> Result<Self> {
         Ok(Self {
             data_source: EpubDataSource::FilePath(path),
+            directory: Arc::default(),
         })
     }
 
@@ -40,6 +53,7 @@ impl EpubExtractor {
     pub async fn from_bytes(data: Bytes) -> Result<Self> {
         Ok(Self {
             data_source: EpubDataSource::Bytes(data),
+            directory: Arc::default(),
         })
     }
 
@@ -52,6 +66,7 @@ impl EpubExtractor {
     {
         Ok(Self {
             data_source: EpubDataSource::Reader(Arc::new(AsyncMutex::new(Box::new(reader)))),
+            directory: Arc::default(),
         })
     }
 
@@ -66,6 +81,7 @@ impl EpubExtractor {
         let buf = FuturesBufReader::new(allow);
         Ok(Self {
             data_source: EpubDataSource::Reader(Arc::new(AsyncMutex::new(Box::new(buf)))),
+            directory: Arc::default(),
         })
     }
 
@@ -78,6 +94,133 @@ impl EpubExtractor {
         }
     }
 
+    /// Read several files through a single opened archive, in order. The
+    /// outer error means the archive itself could not be opened; each file
+    /// then succeeds or fails on its own.
+    pub async fn read_files(&self, paths: &[String]) -> Result<Vec<Result<Vec<u8>>>> {
+        let mut files = Vec::with_capacity(paths.len());
+        self.read_files_with(paths, |_, file| {
+            files.push(file);
+            Ok(())
+        })
+        .await?;
+        Ok(files)
+    }
+
+    /// Like [`Self::read_files`], but hands each file to `each` as
+    /// `(index into paths, bytes)` as soon as it is inflated, so only one
+    /// entry is held at a time. An error returned by `each` stops the read
+    /// and is passed through.
+    pub async fn read_files_with<F>(&self, paths: &[String], mut each: F) -> Result<()>
+    where
+        F: FnMut(usize, Result<Vec<u8>>) -> Result<()>,
+    {
+        match &self.data_source {
+            EpubDataSource::FilePath(file_path) => {
+                let file = std::fs::File::open(file_path).map_err(LexEpubError::Io)?;
+                let reader = FuturesBufReader::new(AllowStdIo::new(file));
+                let mut archive = self.archive(reader).await?;
+                self.extract_files_from_archive(&mut archive, paths, &mut each)
+                    .await
+            }
+            EpubDataSource::Bytes(bytes) => {
+                let reader = FuturesBufReader::new(FuturesCursor::new(bytes.as_ref()));
+                let mut archive = self.archive(reader).await?;
+                self.extract_files_from_archive(&mut archive, paths, &mut each)
+                    .await
+            }
+            EpubDataSource::Reader(m) => {
+                let mut guard = m.lock().await;
+                let reader_ref: &mut (dyn AsyncReadSeek + '_) = &mut *guard;
+                let mut archive = self.archive(reader_ref).await?;
+                self.extract_files_from_archive(&mut archive, paths, &mut each)
+                    .await
+            }
+        }
+    }
+
+    /// Names of all archive entries, in central directory order
+    pub async fn entry_names(&self) -> Result<Vec<String>> {
+        match &self.data_source {
+            EpubDataSource::FilePath(file_path) => {
+                let file = std::fs::File::open(file_path).map_err(LexEpubError::Io)?;
+                let reader = FuturesBufReader::new(AllowStdIo::new(file));
+                let archive = self.archive(reader).await?;
+                Ok(Self::archive_entry_names(&archive))
+            }
+            EpubDataSource::Bytes(bytes) => {
+                let reader = FuturesBufReader::new(FuturesCursor::new(bytes.as_ref()));
+                let archive = self.archive(reader).await?;
+                Ok(Self::archive_entry_names(&archive))
+            }
+            EpubDataSource::Reader(m) => {
+                let mut guard = m.lock().await;
+                let reader_ref: &mut (dyn AsyncReadSeek + '_) = &mut *guard;
+                let archive = self.archive(reader_ref).await?;
+                Ok(Self::archive_entry_names(&archive))
+            }
+        }
+    }
+
+    /// `(name, uncompressed size)` of every archive entry, in central
+    /// directory order. Only the central directory is read.
+    pub async fn entry_sizes(&self) -> Result<Vec<(String, u64)>> {
+        match &self.data_source {
+            EpubDataSource::FilePath(file_path) => {
+                let file = std::fs::File::open(file_path).map_err(LexEpubError::Io)?;
+                let reader = FuturesBufReader::new(AllowStdIo::new(file));
+                let archive = self.archive(reader).await?;
+                Ok(Self::archive_entry_sizes(&archive))
+            }
+            EpubDataSource::Bytes(bytes) => {
+                let reader = FuturesBufReader::new(FuturesCursor::new(bytes.as_ref()));
+                let archive = self.archive(reader).await?;
+                Ok(Self::archive_entry_sizes(&archive))
+            }
+            EpubDataSource::Reader(m) => {
+                let mut guard = m.lock().await;
+                let reader_ref: &mut (dyn AsyncReadSeek + '_) = &mut *guard;
+                let archive = self.archive(reader_ref).await?;
+                Ok(Self::archive_entry_sizes(&archive))
+            }
+        }
+    }
+
+    /// Uncompressed size of the entry named `path`, from the central
+    /// directory; the entry itself is not read
+    pub async fn entry_size(&self, path: &str) -> Result<u64> {
+        if self.directory.get().is_none() {
+            self.entry_names().await?;
+        }
+        let index = self.entry_index(path)?;
+        let directory = self.directory.get().expect("central directory is cached");
+        Ok(directory.file.entries()[index].uncompressed_size())
+    }
+
+    /// Read the `index`-th entry of the ZIP central directory, returning its
+    /// name and bytes. Avoids name lookups when walking the whole archive.
+    pub async fn read_entry_by_index(&self, index: usize) -> Result<(String, Vec<u8>)> {
+        match &self.data_source {
+            EpubDataSource::FilePath(file_path) => {
+                let file = std::fs::File::open(file_path).map_err(LexEpubError::Io)?;
+                let reader = FuturesBufReader::new(AllowStdIo::new(file));
+                let mut archive = self.archive(reader).await?;
+                Self::read_archive_entry(&mut archive, index).await
+            }
+            EpubDataSource::Bytes(bytes) => {
+                let reader = FuturesBufReader::new(FuturesCursor::new(bytes.as_ref()));
+                let mut archive = self.archive(reader).await?;
+                Self::read_archive_entry(&mut archive, index).await
+            }
+            EpubDataSource::Reader(m) => {
+                let mut guard = m.lock().await;
+                let reader_ref: &mut (dyn AsyncReadSeek + '_) = &mut *guard;
+                let mut archive = self.archive(reader_ref).await?;
+                Self::read_archive_entry(&mut archive, index).await
+            }
+        }
+    }
+
     /// Stream a specific file from EPUB directly to an AsyncWrite destination
     pub async fn read_file_to_writer<W: futures::AsyncWrite + Unpin + Send>(
         &self,
@@ -105,9 +248,7 @@ impl EpubExtractor {
         let file = std::fs::File::open(file_path).map_err(LexEpubError::Io)?;
         let allow = AllowStdIo::new(file);
         let reader = FuturesBufReader::new(allow);
-        let mut archive = ZipFileReader::new(reader)
-            .await
-            .map_err(LexEpubError::Zip)?;
+        let mut archive = self.archive(reader).await?;
 
         self.extract_file_from_archive(&mut archive, path).await
     }
@@ -116,9 +257,7 @@ impl EpubExtractor {
     async fn read_file_from_bytes(&self, data: &Bytes, path: &str) -> Result<Vec<u8>> {
         let cursor = FuturesCursor::new(data.as_ref());
         let reader = FuturesBufReader::new(cursor);
-        let mut archive = ZipFileReader::new(reader)
-            .await
-            .map_err(LexEpubError::Zip)?;
+        let mut archive = self.archive(reader).await?;
 
         self.extract_file_from_archive(&mut archive, path).await
     }
@@ -135,9 +274,7 @@ impl EpubExtractor {
 
         // Make the reference explicit to help type inference for ZipFileReader.
         let reader_ref: &mut (dyn AsyncReadSeek + '_) = &mut *guard;
-        let mut archive = ZipFileReader::new(reader_ref)
-            .await
-            .map_err(LexEpubError::Zip)?;
+        let mut archive = self.archive(reader_ref).await?;
 
         self.extract_file_from_archive(&mut archive, path).await
     }
@@ -151,24 +288,127 @@ impl EpubExtractor {
     where
         R: futures::AsyncBufRead + futures::AsyncSeek + Unpin,
     {
-        // Find entry by filename
-        let entries = archive.file().entries();
-        let entry_index = entries
+        let entry_index = self.entry_index(path)?;
+
+        let (_, file_data) = Self::read_archive_entry(archive, entry_index).await?;
+        Ok(file_data)
+    }
+
+    async fn extract_files_from_archive<R, F>(
+        &self,
+        archive: &mut ZipFileReader<R>,
+        paths: &[String],
+        each: &mut F,
+    ) -> Result<()>
+    where
+        R: futures::AsyncBufRead + futures::AsyncSeek + Unpin,
+        F: FnMut(usize, Result<Vec<u8>>) -> Result<()>,
+    {
+        for (index, path) in paths.iter().enumerate() {
+            each(index, self.extract_file_from_archive(archive, path).await)?;
+        }
+        Ok(())
+    }
+
+    /// Open `reader` as a ZIP archive, reusing the cached central directory
+    /// when an earlier call already parsed it
+    async fn archive<R>(&self, reader: R) -> Result<ZipFileReader<R>>
+    where
+        R: futures::AsyncBufRead + futures::AsyncSeek + Unpin,
+    {
+        if let Some(directory) = self.directory.get() {
+            return Ok(ZipFileReader::from_raw_parts(
+                reader,
+                directory.file.clone(),
+            ));
+        }
+        let archive = ZipFileReader::new(reader)
+            .await
+            .map_err(LexEpubError::Zip)?;
+        let mut index = HashMap::new();
+        for (i, entry) in archive.file().entries().iter().enumerate() {
+            if let Ok(filename) = entry.filename().as_str() {
+                index.entry(filename.to_string()).or_insert(i);
+            }
+        }
+        // Entries stored percent-encoded are also reachable by their decoded
+        // name; a literal name always wins
+        for (i, entry) in archive.file().entries().iter().enumerate() {
+            if let Ok(filename) = entry.filename().as_str() {
+                index.entry(percent_decode(filename)).or_insert(i);
+            }
+        }
+        let _ = self.directory.set(CentralDirectory {
+            file: archive.file().clone(),
+            index,
+        });
+        Ok(archive)
+    }
+
+    /// Central directory index of the entry named `path`. The directory is
+    /// cached by [`Self::archive`], which every read goes through first.
+    fn entry_index(&self, path: &str) -> Result<usize> {
+        self.directory
+            .get()
+            .and_then(|directory| {
+                directory
+                    .index
+                    .get(path)
+                    .or_else(|| directory.index.get(&percent_decode(path)))
+                    .copied()
+            })
+            .ok_or_else(|| LexEpubError::MissingFile(format!("File '{}' not found in EPUB", path)))
+    }
+
+    fn archive_entry_names<R>(archive: &ZipFileReader<R>) -> Vec<String>
+    where
+        R: futures::AsyncBufRead + futures::AsyncSeek + Unpin,
+    {
+        archive
+            .file()
+            .entries()
             .iter()
-            .enumerate()
-            .find_map(|(i, entry)| {
-                entry
-                    .filename()
-                    .as_str()
-                    .ok()
-                    .and_then(|filename| (filename == path).then_some(i))
+            .map(|entry| entry.filename().as_str().unwrap_or_default().to_string())
+            .collect()
+    }
+
+    fn archive_entry_sizes<R>(archive: &ZipFileReader<R>) -> Vec<(String, u64)>
+    where
+        R: futures::AsyncBufRead + futures::AsyncSeek + Unpin,
+    {
+        archive
+            .file()
+            .entries()
+            .iter()
+            .map(|entry| {
+                (
+                    entry.filename().as_str().unwrap_or_default().to_string(),
+                    entry.uncompressed_size(),
+                )
             })
-            .ok_or_else(|| {
-                LexEpubError::MissingFile(format!("File '{}' not found in EPUB", path))
-            })?;
+            .collect()
+    }
+
+    /// Read an entry's name and bytes by its central directory index
+    async fn read_archive_entry<R>(
+        archive: &mut ZipFileReader<R>,
+        index: usize,
+    ) -> Result<(String, Vec<u8>)>
+    where
+        R: futures::AsyncBufRead + futures::AsyncSeek + Unpin,
+    {
+        let name = archive
+            .file()
+            .entries()
+            .get(index)
+            .ok_or_else(|| LexEpubError::MissingFile(format!("No entry at index {}", index)))?
+            .filename()
+            .as_str()
+            .map_err(LexEpubError::Zip)?
+            .to_string();
 
         let mut entry_reader = archive
-            .reader_without_entry(entry_index)
+            .reader_without_entry(index)
             .await
             .map_err(LexEpubError::Zip)?;
 
@@ -180,7 +420,7 @@ impl EpubExtractor {
             .await
             .map_err(LexEpubError::Io)?;
 
-        Ok(file_data)
+        Ok((name, file_data))
     }
 }
 
@@ -194,9 +434,7 @@ impl EpubExtractor {
         let file = std::fs::File::open(file_path).map_err(LexEpubError::Io)?;
         let allow = AllowStdIo::new(file);
         let reader = FuturesBufReader::new(allow);
-        let mut archive = ZipFileReader::new(reader)
-            .await
-            .map_err(LexEpubError::Zip)?;
+        let mut archive = self.archive(reader).await?;
         self.extract_file_from_archive_to_writer(&mut archive, path, writer)
             .await
     }
@@ -209,9 +447,7 @@ impl EpubExtractor {
     ) -> Result<u64> {
         let cursor = FuturesCursor::new(data.as_ref());
         let reader = FuturesBufReader::new(cursor);
-        let mut archive = ZipFileReader::new(reader)
-            .await
-            .map_err(LexEpubError::Zip)?;
+        let mut archive = self.archive(reader).await?;
         self.extract_file_from_archive_to_writer(&mut archive, path, writer)
             .await
     }
@@ -226,9 +462,7 @@ impl EpubExtractor {
             _ => unreachable!(),
         };
         let reader_ref: &mut (dyn AsyncReadSeek + '_) = &mut *guard;
-        let mut archive = ZipFileReader::new(reader_ref)
-            .await
-            .map_err(LexEpubError::Zip)?;
+        let mut archive = self.archive(reader_ref).await?;
         self.extract_file_from_archive_to_writer(&mut archive, path, writer)
             .await
     }
@@ -243,20 +477,7 @@ impl EpubExtractor {
         R: futures::AsyncBufRead + futures::AsyncSeek + Unpin,
         W: futures::AsyncWrite + Unpin + Send,
     {
-        let entries = archive.file().entries();
-        let entry_index = entries
-            .iter()
-            .enumerate()
-            .find_map(|(i, entry)| {
-                entry
-                    .filename()
-                    .as_str()
-                    .ok()
-                    .and_then(|filename| (filename == path).then_some(i))
-            })
-            .ok_or_else(|| {
-                LexEpubError::MissingFile(format!("File '{}' not found in EPUB", path))
-            })?;
+        let entry_index = self.entry_index(path)?;
 
         let mut entry_reader = archive
             .reader_without_entry(entry_index)
@@ -268,3 +489,29 @@ impl EpubExtractor {
             .map_err(LexEpubError::Io)
     }
 }
+
+/// Decode `%XX` escapes in an href; malformed escapes are kept verbatim
+pub(crate) fn percent_decode(href: &str) -> String {
+    let bytes = href.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escape = (bytes[i] == b'%')
+            .then(|| href.get(i + 1..i + 3))
+            .flatten()
+            // from_str_radix alone would also take a sign (`%+5`)
+            .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escape {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -1,13 +1,187 @@
-use crate::core::chapter::{AstNode, Chapter, ParsedChapter};
+use crate::core::chapter::{AstNode, Chapter, ParsedChapter, TextBlock, TextBlockKind};
 use crate::error::{LexEpubError, Result};
 use std::collections::HashMap;
 use tl::ParserOptions;
 
+/// Line separator used when joining extracted lines and blocks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n`
+    #[default]
+    Lf,
+    /// `\r\n`, for consumers writing text files on Windows
+    CrLf,
+}
+
+impl LineEnding {
+    /// The separator itself
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+/// Custom word counter for chapter statistics, e.g. one that understands
+/// languages written without spaces
+pub type WordCounter = std::sync::Arc<dyn Fn(&str) -> usize + Send + Sync>;
+
+/// Count words with `counter`, or by whitespace separation when none is set
+pub fn count_words(text: &str, counter: Option<&WordCounter>) -> usize {
+    match counter {
+        Some(counter) => counter(text),
+        None => text.split_whitespace().count(),
+    }
+}
+
+/// Word count that treats every CJK ideograph and kana as a word of its own
+/// (Chinese and Japanese are written without spaces), and counts other
+/// text by whitespace separation. CJK punctuation separates words.
+#[cfg(feature = "cjk")]
+pub fn cjk_word_count(text: &str) -> usize {
+    let mut count = 0;
+    let mut in_word = false;
+    for c in text.chars() {
+        if is_cjk_word_char(c) {
+            count += 1;
+            in_word = false;
+        } else if c.is_whitespace()
+            || matches!(c, '\u{3000}'..='\u{303F}' | '\u{FF01}'..='\u{FF0F}')
+        {
+            in_word = false;
+        } else if !in_word {
+            count += 1;
+            in_word = true;
+        }
+    }
+    count
+}
+
+/// Whether `c` is a CJK ideograph or kana, each of which is a word of its
+/// own in text written without spaces
+#[cfg(any(feature = "cjk", not(feature = "unicode-segmentation")))]
+fn is_cjk_word_char(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'       // Hiragana, Katakana
+        | '\u{31F0}'..='\u{31FF}'     // Katakana phonetic extensions
+        | '\u{3400}'..='\u{4DBF}'     // CJK extension A
+        | '\u{4E00}'..='\u{9FFF}'     // CJK unified ideographs
+        | '\u{F900}'..='\u{FAFF}'     // CJK compatibility ideographs
+        | '\u{FF66}'..='\u{FF9F}'     // Halfwidth katakana
+        | '\u{20000}'..='\u{2FA1F}'   // Extensions B onwards
+    )
+}
+
+/// Post-processing options applied to extracted plain text
+#[derive(Debug, Clone, Default)]
+pub struct TextExtractOptions {
+    /// Map typographic punctuation (curly quotes, en/em dashes, ellipsis) to
+    /// ASCII equivalents. Off by default to preserve fidelity.
+    pub normalize_punctuation: bool,
+    /// Newline style of the output. Defaults to `Lf`.
+    pub line_ending: LineEnding,
+    /// Keep `epub:type="annotation"` asides in the text. Off by default so
+    /// annotation layers don't interrupt the prose; see
+    /// [`extract_annotations`] to read them separately.
+    pub include_annotations: bool,
+    /// Line emitted in place of each `<hr>` (e.g. `* * *` for a scene
+    /// break). `None`, the default, drops thematic breaks.
+    pub hr_marker: Option<String>,
+    /// Keep the document `<title>` as the first line of the text. Off by
+    /// default; the rest of `<head>` is always skipped.
+    pub include_title: bool,
+    /// Keep the contents of `<script>` and `<style>` elements. Off by
+    /// default so code and CSS rules never leak into the prose.
+    pub include_scripts: bool,
+    /// Leave character references (`&amp;`, `&#8217;`) as written instead
+    /// of decoding them. Off by default.
+    pub keep_entities: bool,
+}
+
+impl TextExtractOptions {
+    /// Create options with all transformations disabled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable or disable typographic punctuation normalization
+    pub fn normalize_punctuation(mut self, enabled: bool) -> Self {
+        self.normalize_punctuation = enabled;
+        self
+    }
+
+    /// Set the newline style of the output
+    pub fn line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    /// Keep or drop annotation asides
+    pub fn include_annotations(mut self, enabled: bool) -> Self {
+        self.include_annotations = enabled;
+        self
+    }
+
+    /// Emit `marker` on its own line wherever an `<hr>` appears
+    pub fn hr_marker(mut self, marker: impl Into<String>) -> Self {
+        self.hr_marker = Some(marker.into());
+        self
+    }
+
+    /// Keep or drop the document `<title>`
+    pub fn include_title(mut self, enabled: bool) -> Self {
+        self.include_title = enabled;
+        self
+    }
+
+    /// Keep or drop `<script>` and `<style>` contents
+    pub fn include_scripts(mut self, enabled: bool) -> Self {
+        self.include_scripts = enabled;
+        self
+    }
+
+    /// Keep character references undecoded
+    pub fn keep_entities(mut self, enabled: bool) -> Self {
+        self.keep_entities = enabled;
+        self
+    }
+
+    /// Text of a run between tags, with entities decoded unless
+    /// `keep_entities` is set
+    fn decode<'a>(&self, text: &'a str) -> std::borrow::Cow<'a, str> {
+        if self.keep_entities {
+            std::borrow::Cow::Borrowed(text)
+        } else {
+            html_escape::decode_html_entities(text)
+        }
+    }
+
+    /// Apply the configured transformations to already-extracted text
+    fn apply(&self, text: String) -> String {
+        let text = if self.normalize_punctuation {
+            normalize_punctuation(&text)
+        } else {
+            text
+        };
+        match self.line_ending {
+            LineEnding::Lf => text,
+            LineEnding::CrLf => text.replace('\n', self.line_ending.as_str()),
+        }
+    }
+}
+
 /// Configurable chapter parser
 #[derive(Clone)]
 pub struct ChapterParser {
     pub text_only: bool,
     pub with_ast: bool,
+    pub text_options: TextExtractOptions,
+    /// Word counter for `word_count`; whitespace separation when `None`
+    pub word_counter: Option<WordCounter>,
+    /// Deepest element level kept in the AST; deeper elements collapse into
+    /// a text node holding their text. Unlimited when `None`.
+    pub max_ast_depth: Option<usize>,
 }
 
 impl Default for ChapterParser {
@@ -15,6 +189,9 @@ impl Default for ChapterParser {
         Self {
             text_only: true,
             with_ast: false,
+            text_options: TextExtractOptions::default(),
+            word_counter: None,
+            max_ast_depth: None,
         }
     }
 }
@@ -46,12 +223,45 @@ impl ChapterParser {
         self
     }
 
-    /// Parse a chapter into the requested format
+    /// Set the options used when extracting plain text
+    pub fn text_options(mut self, options: TextExtractOptions) -> Self {
+        self.text_options = options;
+        self
+    }
+
+    /// Set the word counter used for `word_count`
+    pub fn word_counter(mut self, counter: Option<WordCounter>) -> Self {
+        self.word_counter = counter;
+        self
+    }
+
+    /// Cap AST nesting at `depth` element levels, bounding the work done on
+    /// pathologically nested documents
+    pub fn max_ast_depth(mut self, depth: usize) -> Self {
+        self.max_ast_depth = Some(depth);
+        self
+    }
+
+    /// Parse a chapter into the requested format. Non-markup spine items
+    /// (e.g. a bare image) yield an empty chapter instead of garbage text.
     pub fn parse_chapter(&self, chapter: Chapter) -> Result<ParsedChapter> {
+        if !chapter.is_markup() {
+            return Ok(ParsedChapter {
+                chapter_info: chapter,
+                title: None,
+                content: String::new(),
+                ast: None,
+                word_count: 0,
+                char_count: 0,
+                is_scripted: false,
+                dropcap: None,
+            });
+        }
+
         let content_str = std::str::from_utf8(&chapter.content)?;
 
         let ast = if self.with_ast {
-            Some(parse_html_ast(content_str)?)
+            Some(parse_html_ast(content_str, self.max_ast_depth)?)
         } else {
             None
         };
@@ -59,11 +269,13 @@ impl ChapterParser {
         let content = if !self.text_only && !self.with_ast {
             content_str.to_string()
         } else {
-            extract_text_content(content_str)?
+            extract_text_content_with(content_str, &self.text_options)?
         };
 
-        let word_count = content.split_whitespace().count();
+        let word_count = count_words(&content, self.word_counter.as_ref());
         let char_count = content.chars().count();
+        let is_scripted = has_script_tag(content_str);
+        let dropcap = detect_dropcap(content_str);
 
         let title = ast.as_ref().and_then(extract_title_from_ast).or_else(|| {
             content
@@ -79,13 +291,714 @@ impl ChapterParser {
             ast,
             word_count,
             char_count,
+            is_scripted,
+            dropcap,
+        })
+    }
+}
+
+/// Cheap check for an opening `<script` tag anywhere in the markup
+pub fn has_script_tag(html: &str) -> bool {
+    html.as_bytes()
+        .windows(7)
+        .any(|w| w.eq_ignore_ascii_case(b"<script"))
+}
+
+/// Cheap check for image content (`<img>`, `<image>`, or `<svg>`)
+pub fn has_image_tag(html: &str) -> bool {
+    let bytes = html.as_bytes();
+    bytes
+        .windows(4)
+        .any(|w| w.eq_ignore_ascii_case(b"<img") || w.eq_ignore_ascii_case(b"<svg"))
+        || bytes.windows(6).any(|w| w.eq_ignore_ascii_case(b"<image"))
+}
+
+/// Character a chapter renders as a drop cap, if any: the text of the first
+/// element whose class names a drop cap (`dropcap`, `drop-cap`, ...), or,
+/// when an inline stylesheet styles `::first-letter`, the first letter of
+/// the first paragraph.
+pub fn detect_dropcap(html: &str) -> Option<char> {
+    let lower = html.to_ascii_lowercase();
+    let has_class_hint = lower.contains("dropcap") || lower.contains("drop-cap");
+    let has_first_letter = lower.contains(":first-letter");
+    if !has_class_hint && !has_first_letter {
+        return None;
+    }
+
+    let dom = tl::parse(html, ParserOptions::default()).ok()?;
+    let parser = dom.parser();
+    let tags = || dom.nodes().iter().filter_map(|node| node.as_tag());
+    let text_of =
+        |tag: &tl::HTMLTag| html_escape::decode_html_entities(&tag.inner_text(parser)).into_owned();
+
+    if has_class_hint {
+        let dropcap = tags().find(|tag| {
+            tag.attributes().class().is_some_and(|class| {
+                class.as_utf8_str().split_whitespace().any(|name| {
+                    name.to_ascii_lowercase()
+                        .replace(['-', '_'], "")
+                        .contains("dropcap")
+                })
+            })
+        });
+        if let Some(tag) = dropcap {
+            if let Some(c) = text_of(tag).chars().find(|c| !c.is_whitespace()) {
+                return Some(c);
+            }
+        }
+    }
+
+    if has_first_letter {
+        let styled = tags().any(|tag| {
+            tag.name().as_utf8_str().eq_ignore_ascii_case("style")
+                && tag.inner_text(parser).contains(":first-letter")
+        });
+        if styled {
+            return tags()
+                .filter(|tag| tag.name().as_utf8_str().eq_ignore_ascii_case("p"))
+                .find_map(|tag| text_of(tag).chars().find(|c| c.is_alphanumeric()));
+        }
+    }
+    None
+}
+
+/// Links in a navigation document that carry an `epub:type` (landmarks such
+/// as `bodymatter`, `toc`, `cover`), as `(epub:type, href)` pairs in document
+/// order.
+pub fn nav_landmarks(html: &str) -> Result<Vec<(String, String)>> {
+    let dom = tl::parse(html, ParserOptions::default())
+        .map_err(|e| LexEpubError::Html(format!("Failed to parse HTML: {}", e)))?;
+
+    let mut landmarks = Vec::new();
+    for node in dom.nodes() {
+        let Some(tag) = node.as_tag() else {
+            continue;
+        };
+        if !tag.name().as_utf8_str().eq_ignore_ascii_case("a") {
+            continue;
+        }
+        let attrs = tag.attributes();
+        if let (Some(kind), Some(href)) = (
+            attrs.get("epub:type").flatten(),
+            attrs.get("href").flatten(),
+        ) {
+            landmarks.push((
+                kind.as_utf8_str().to_string(),
+                href.as_utf8_str().to_string(),
+            ));
+        }
+    }
+    Ok(landmarks)
+}
+
+/// Markup of a navigation document's `<nav epub:type="toc">`, with every
+/// link `href` passed through `rewrite`. `None` when the document has no toc
+/// nav.
+pub fn nav_toc_html(html: &str, rewrite: impl Fn(&str) -> String) -> Result<Option<String>> {
+    let mut dom = tl::parse(html, ParserOptions::default())
+        .map_err(|e| LexEpubError::Html(format!("Failed to parse HTML: {}", e)))?;
+
+    let toc_index = dom.nodes().iter().position(|node| {
+        node.as_tag().is_some_and(|tag| {
+            tag.name().as_utf8_str().eq_ignore_ascii_case("nav")
+                && tag
+                    .attributes()
+                    .get("epub:type")
+                    .flatten()
+                    .is_some_and(|kind| kind.as_utf8_str().split_whitespace().any(|k| k == "toc"))
+        })
+    });
+    let Some(toc_index) = toc_index else {
+        return Ok(None);
+    };
+
+    for node in dom.nodes_mut() {
+        let Some(tag) = node.as_tag_mut() else {
+            continue;
+        };
+        if !tag.name().as_utf8_str().eq_ignore_ascii_case("a") {
+            continue;
+        }
+        if let Some(Some(href)) = tag.attributes_mut().get_mut("href") {
+            let rewritten = rewrite(&href.as_utf8_str());
+            let _ = href.set(rewritten);
+        }
+    }
+
+    Ok(dom.nodes()[toc_index]
+        .as_tag()
+        .map(|tag| tag.outer_html(dom.parser())))
+}
+
+/// Entries of a navigation document's `<nav epub:type="toc">` list, nested
+/// as in the document. Hrefs are returned as written; entries without a
+/// link (a `<span>` heading) have an empty `src`. `None` when the document
+/// has no toc nav.
+pub fn nav_toc_points(html: &str) -> Result<Option<Vec<crate::core::ncx::NavPoint>>> {
+    let dom = tl::parse(html, ParserOptions::default())
+        .map_err(|e| LexEpubError::Html(format!("Failed to parse HTML: {}", e)))?;
+    let parser = dom.parser();
+
+    let toc = dom.nodes().iter().find_map(|node| {
+        node.as_tag().filter(|tag| {
+            tag.name().as_utf8_str().eq_ignore_ascii_case("nav")
+                && tag
+                    .attributes()
+                    .get("epub:type")
+                    .flatten()
+                    .is_some_and(|kind| kind.as_utf8_str().split_whitespace().any(|k| k == "toc"))
+        })
+    });
+    let Some(toc) = toc else {
+        return Ok(None);
+    };
+
+    let list = find_child_tag(toc, parser, "ol");
+    Ok(Some(
+        list.map_or_else(Vec::new, |ol| nav_list_points(ol, parser)),
+    ))
+}
+
+/// First descendant of `tag` named `name`, depth-first
+fn find_child_tag<'a>(
+    tag: &'a tl::HTMLTag<'a>,
+    parser: &'a tl::Parser<'a>,
+    name: &str,
+) -> Option<&'a tl::HTMLTag<'a>> {
+    for handle in tag.children().top().iter() {
+        let Some(child) = handle.get(parser).and_then(|node| node.as_tag()) else {
+            continue;
+        };
+        if child.name().as_utf8_str().eq_ignore_ascii_case(name) {
+            return Some(child);
+        }
+        if let Some(found) = find_child_tag(child, parser, name) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+fn nav_list_points(ol: &tl::HTMLTag, parser: &tl::Parser) -> Vec<crate::core::ncx::NavPoint> {
+    let mut points = Vec::new();
+    for handle in ol.children().top().iter() {
+        let Some(li) = handle.get(parser).and_then(|node| node.as_tag()) else {
+            continue;
+        };
+        if !li.name().as_utf8_str().eq_ignore_ascii_case("li") {
+            continue;
+        }
+
+        let mut point = crate::core::ncx::NavPoint::default();
+        for child in li.children().top().iter() {
+            let Some(child) = child.get(parser).and_then(|node| node.as_tag()) else {
+                continue;
+            };
+            let name = child.name().as_utf8_str().to_ascii_lowercase();
+            match name.as_str() {
+                "a" | "span" if point.label.is_empty() => {
+                    let text =
+                        html_escape::decode_html_entities(&child.inner_text(parser)).into_owned();
+                    point.label = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                    if let Some(Some(href)) = child.attributes().get("href") {
+                        point.src =
+                            html_escape::decode_html_entities(&href.as_utf8_str()).into_owned();
+                    }
+                }
+                "ol" => point.children = nav_list_points(child, parser),
+                _ => {}
+            }
+        }
+        points.push(point);
+    }
+    points
+}
+
+/// An element carrying an `epub:type`, from [`semantic_elements`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SemanticElement {
+    /// The `epub:type` value, whitespace-normalized
+    pub epub_type: String,
+    pub id: Option<String>,
+    /// Text of the element's first heading
+    pub title: Option<String>,
+}
+
+/// Elements carrying an `epub:type` (`chapter`, `bibliography`, `figure`...)
+/// in document order. Links are skipped: an `epub:type` on `<a>` marks a
+/// reference (`noteref`, landmarks), not content.
+pub fn semantic_elements(html: &str) -> Result<Vec<SemanticElement>> {
+    let dom = tl::parse(html, ParserOptions::default())
+        .map_err(|e| LexEpubError::Html(format!("Failed to parse HTML: {}", e)))?;
+    let parser = dom.parser();
+
+    let mut elements = Vec::new();
+    for node in dom.nodes() {
+        let Some(tag) = node.as_tag() else {
+            continue;
+        };
+        if tag.name().as_utf8_str().eq_ignore_ascii_case("a") {
+            continue;
+        }
+        let attrs = tag.attributes();
+        let Some(kind) = attrs.get("epub:type").flatten() else {
+            continue;
+        };
+        let epub_type = kind
+            .as_utf8_str()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if epub_type.is_empty() {
+            continue;
+        }
+        let id = attrs.id().map(|id| id.as_utf8_str().to_string());
+        let title = tag
+            .children()
+            .all(parser)
+            .iter()
+            .filter_map(|child| child.as_tag())
+            .find(|child| {
+                let name = child.name().as_utf8_str();
+                name.len() == 2
+                    && name.starts_with(['h', 'H'])
+                    && matches!(name.as_bytes()[1], b'1'..=b'6')
+            })
+            .map(|heading| {
+                html_escape::decode_html_entities(&heading.inner_text(parser))
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .filter(|title| !title.is_empty());
+        elements.push(SemanticElement {
+            epub_type,
+            id,
+            title,
+        });
+    }
+    Ok(elements)
+}
+
+/// Every resource reference in a document (`a`/`link`/`area` hrefs,
+/// `img`/`source`/`audio`/`video`/`script`/`iframe` srcs, and SVG
+/// `image` xlink:hrefs), unresolved, in document order. `xml:base`
+/// attributes are not reported or applied.
+pub fn resource_references(html: &str) -> Result<Vec<String>> {
+    let dom = tl::parse(html, ParserOptions::default())
+        .map_err(|e| LexEpubError::Html(format!("Failed to parse HTML: {}", e)))?;
+
+    let mut references = Vec::new();
+    for node in dom.nodes() {
+        let Some(tag) = node.as_tag() else {
+            continue;
+        };
+        let name = tag.name().as_utf8_str().to_ascii_lowercase();
+        let attr = match name.as_str() {
+            "a" | "link" | "area" => "href",
+            "img" | "source" | "audio" | "video" | "script" | "iframe" | "embed" => "src",
+            "image" => "xlink:href",
+            _ => continue,
+        };
+        if let Some(value) = tag.attributes().get(attr).flatten() {
+            let value = value.as_utf8_str().trim().to_string();
+            if !value.is_empty() {
+                references.push(value);
+            }
+        }
+    }
+    Ok(references)
+}
+
+/// A stylesheet a chapter pulls in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChapterStyle {
+    /// `<link rel="stylesheet" href="...">`, with the raw href
+    Linked(String),
+    /// Contents of an inline `<style>` element
+    Inline(String),
+}
+
+/// Collect a chapter's linked and inline stylesheets in document order.
+/// Linked hrefs are raw; any `xml:base` in scope is not applied.
+pub fn chapter_styles(html: &str) -> Result<Vec<ChapterStyle>> {
+    let dom = tl::parse(html, ParserOptions::default())
+        .map_err(|e| LexEpubError::Html(format!("Failed to parse HTML: {}", e)))?;
+    let parser = dom.parser();
+
+    let mut styles = Vec::new();
+    for node in dom.nodes() {
+        let Some(tag) = node.as_tag() else {
+            continue;
+        };
+        let name = tag.name().as_utf8_str();
+        if name.eq_ignore_ascii_case("link") {
+            let attrs = tag.attributes();
+            let is_stylesheet = attrs
+                .get("rel")
+                .flatten()
+                .map(|rel| {
+                    rel.as_utf8_str()
+                        .split_whitespace()
+                        .any(|r| r.eq_ignore_ascii_case("stylesheet"))
+                })
+                .unwrap_or(false);
+            if let Some(href) = attrs.get("href").flatten() {
+                if is_stylesheet {
+                    styles.push(ChapterStyle::Linked(href.as_utf8_str().to_string()));
+                }
+            }
+        } else if name.eq_ignore_ascii_case("style") {
+            styles.push(ChapterStyle::Inline(tag.inner_text(parser).to_string()));
+        }
+    }
+    Ok(styles)
+}
+
+/// Extract clean text content from HTML with default options
+pub fn extract_text_content(html: &str) -> Result<String> {
+    extract_text_content_with(html, &TextExtractOptions::default())
+}
+
+/// Options for Markdown rendering
+#[derive(Debug, Clone, Default)]
+pub struct MarkdownOptions {
+    /// Append `{#id}` to headings that carry an `id`, so internal links keep
+    /// resolving after conversion
+    pub heading_ids: bool,
+}
+
+impl MarkdownOptions {
+    /// Create options with all extras disabled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable or disable `{#id}` heading anchors
+    pub fn heading_ids(mut self, enabled: bool) -> Self {
+        self.heading_ids = enabled;
+        self
+    }
+}
+
+/// Render chapter HTML as Markdown with default options
+pub fn extract_markdown_content(html: &str) -> Result<String> {
+    extract_markdown_content_with(html, &MarkdownOptions::default())
+}
+
+/// Render chapter HTML as Markdown, one block per paragraph, heading, list
+/// item, quote, or code block, separated by blank lines. `em`/`i` become
+/// `*...*` and `strong`/`b` become `**...**`.
+pub fn extract_markdown_content_with(html: &str, options: &MarkdownOptions) -> Result<String> {
+    let blocks = collect_blocks(html, true)?;
+    let rendered: Vec<String> = blocks
+        .iter()
+        .map(|block| match block.kind {
+            TextBlockKind::Heading(level) => {
+                let mut line = format!("{} {}", "#".repeat(level as usize), block.text);
+                if let Some(id) = block.id.as_ref().filter(|_| options.heading_ids) {
+                    line.push_str(&format!(" {{#{}}}", id));
+                }
+                line
+            }
+            TextBlockKind::Paragraph => block.text.clone(),
+            TextBlockKind::ListItem => format!("- {}", block.text),
+            TextBlockKind::Quote => format!("> {}", block.text),
+            TextBlockKind::Preformatted => format!("```\n{}\n```", block.text),
         })
+        .collect();
+    Ok(rendered.join("\n\n"))
+}
+
+/// Split text into words, returning each word with its offset in chars.
+///
+/// With the `unicode-segmentation` feature, words follow the Unicode word
+/// boundary rules (UAX #29). Without it, a word is a run of alphanumeric
+/// characters in any script, with apostrophes between letters (`don't`,
+/// `l’homme`) kept inside the word. Either way each CJK ideograph is a word
+/// of its own, since Chinese and Japanese are written without spaces.
+pub fn word_spans(text: &str) -> Vec<(usize, &str)> {
+    #[cfg(feature = "unicode-segmentation")]
+    {
+        use unicode_segmentation::UnicodeSegmentation;
+
+        let mut words = Vec::new();
+        // Byte offsets come back in order, so chars are counted incrementally
+        let (mut byte, mut chars) = (0, 0);
+        for (pos, word) in text.unicode_word_indices() {
+            chars += text[byte..pos].chars().count();
+            byte = pos;
+            words.push((chars, word));
+        }
+        words
+    }
+    #[cfg(not(feature = "unicode-segmentation"))]
+    alphanumeric_word_spans(text)
+}
+
+/// [`word_spans`] without Unicode segmentation: alphanumeric runs, with CJK
+/// ideographs and kana split one per word
+#[cfg(not(feature = "unicode-segmentation"))]
+fn alphanumeric_word_spans(text: &str) -> Vec<(usize, &str)> {
+    let mut words = Vec::new();
+    let mut start: Option<(usize, usize)> = None;
+    let mut chars = text.char_indices().enumerate().peekable();
+
+    while let Some((char_pos, (byte_pos, c))) = chars.next() {
+        let joins = matches!(c, '\'' | '\u{2019}')
+            && start.is_some()
+            && chars
+                .peek()
+                .is_some_and(|(_, (_, next))| next.is_alphanumeric() && !is_cjk_word_char(*next));
+        if is_cjk_word_char(c) {
+            if let Some((word_char, word_byte)) = start.take() {
+                words.push((word_char, &text[word_byte..byte_pos]));
+            }
+            words.push((char_pos, &text[byte_pos..byte_pos + c.len_utf8()]));
+        } else if c.is_alphanumeric() || joins {
+            start.get_or_insert((char_pos, byte_pos));
+        } else if let Some((word_char, word_byte)) = start.take() {
+            words.push((word_char, &text[word_byte..byte_pos]));
+        }
+    }
+    if let Some((word_char, word_byte)) = start {
+        words.push((word_char, &text[word_byte..]));
+    }
+    words
+}
+
+/// Split text into sentences, trimmed, in order.
+///
+/// Line breaks always end a sentence. `.`, `!`, `?`, and `…` end one when
+/// followed by whitespace, unless a `.` comes before a lowercase word or
+/// after a known abbreviation for `language` (a BCP 47 tag; English rules
+/// when `None` or unsupported) or a single-letter initial. Greek also ends
+/// questions with `;`. CJK and Devanagari full stops always end a sentence.
+pub fn split_sentences<'a>(text: &'a str, language: Option<&str>) -> Vec<&'a str> {
+    let primary = language
+        .and_then(|tag| tag.split(['-', '_']).next())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let abbreviations = sentence_abbreviations(&primary);
+    let greek = primary == "el";
+
+    let mut sentences = Vec::new();
+    for line in text.lines() {
+        let mut start = 0;
+        let mut chars = line.char_indices().peekable();
+        while let Some((pos, c)) = chars.next() {
+            let always = matches!(c, '。' | '！' | '？' | '।' | '॥');
+            let ambiguous = matches!(c, '.' | '!' | '?' | '…') || (greek && c == ';');
+            if !always && !ambiguous {
+                continue;
+            }
+            // Swallow repeated terminators and closing quotes/brackets
+            let mut end = pos + c.len_utf8();
+            while let Some(&(next_pos, next)) = chars.peek() {
+                if matches!(
+                    next,
+                    '.' | '!' | '?' | '…' | '"' | '\'' | '”' | '’' | '»' | ')' | ']'
+                ) {
+                    end = next_pos + next.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            if !always {
+                let at_break = chars.peek().is_none_or(|(_, next)| next.is_whitespace());
+                if !at_break {
+                    continue;
+                }
+                // A period before a lowercase word is an abbreviation
+                let next_lowercase = line[end..].trim_start().starts_with(char::is_lowercase);
+                if c == '.' && next_lowercase {
+                    continue;
+                }
+                if c == '.' {
+                    let word_start = line[..pos].rfind(char::is_whitespace).map_or(0, |i| i + 1);
+                    let word = line[word_start..=pos].to_lowercase();
+                    let is_initial = word.chars().count() == 2
+                        && word.starts_with(|ch: char| ch.is_alphabetic());
+                    if is_initial || abbreviations.contains(&word.as_str()) {
+                        continue;
+                    }
+                }
+            }
+            let sentence = line[start..end].trim();
+            if !sentence.is_empty() {
+                sentences.push(sentence);
+            }
+            start = end;
+        }
+        let rest = line[start..].trim();
+        if !rest.is_empty() {
+            sentences.push(rest);
+        }
+    }
+    sentences
+}
+
+/// Abbreviations (lowercase, with their trailing period) that do not end a
+/// sentence, by primary language subtag
+fn sentence_abbreviations(language: &str) -> &'static [&'static str] {
+    match language {
+        "de" => &[
+            "hr.", "fr.", "dr.", "prof.", "nr.", "str.", "bzw.", "ca.", "z.b.", "d.h.", "u.a.",
+            "usw.", "vgl.", "evtl.", "ggf.", "s.",
+        ],
+        "fr" => &[
+            "m.", "mme.", "mlle.", "mm.", "dr.", "st.", "ste.", "etc.", "cf.", "p.", "env.",
+        ],
+        "es" => &[
+            "sr.", "sra.", "srta.", "dr.", "dra.", "ud.", "uds.", "etc.", "pág.", "núm.",
+        ],
+        "it" => &["sig.", "sig.ra.", "dott.", "prof.", "ecc.", "pag."],
+        _ => &[
+            "mr.", "mrs.", "ms.", "dr.", "prof.", "st.", "jr.", "sr.", "vs.", "etc.", "e.g.",
+            "i.e.", "a.m.", "p.m.", "no.", "mt.", "capt.", "gen.", "col.", "lt.", "rev.",
+        ],
     }
 }
 
+/// Collapse insignificant whitespace in markup.
+///
+/// Runs of whitespace in text collapse to a single space, and
+/// whitespace-only text between tags is dropped unless it separates two
+/// inline elements (where it renders as a space). Tags, comments, and the
+/// contents of `<pre>`, `<textarea>`, `<script>`, and `<style>` are copied
+/// verbatim.
+pub fn minify_html(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    let mut prev_tag = String::new();
+
+    while !rest.is_empty() {
+        if rest.starts_with("<!--") {
+            let end = rest.find("-->").map_or(rest.len(), |i| i + 3);
+            out.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+        if rest.starts_with('<') {
+            let end = markup_tag_end(rest);
+            let tag = &rest[..end];
+            out.push_str(tag);
+            rest = &rest[end..];
+            prev_tag = markup_tag_name(tag);
+            let opens_raw = !tag.starts_with("</")
+                && !tag.ends_with("/>")
+                && matches!(prev_tag.as_str(), "pre" | "textarea" | "script" | "style");
+            if opens_raw {
+                // Lowercasing is ASCII-only, so byte offsets still line up
+                let close = format!("</{}", prev_tag);
+                let end = rest.to_ascii_lowercase().find(&close).unwrap_or(rest.len());
+                out.push_str(&rest[..end]);
+                rest = &rest[end..];
+            }
+            continue;
+        }
+
+        let end = rest.find('<').unwrap_or(rest.len());
+        let text = &rest[..end];
+        rest = &rest[end..];
+        // ASCII whitespace only: a no-break space is content
+        if text.bytes().all(|b| b.is_ascii_whitespace()) {
+            let next_tag = markup_tag_name(&rest[..markup_tag_end(rest)]);
+            if is_inline_tag(&prev_tag) && is_inline_tag(&next_tag) {
+                out.push(' ');
+            }
+        } else {
+            let mut in_space = false;
+            for c in text.chars() {
+                if c.is_ascii_whitespace() {
+                    if !in_space {
+                        out.push(' ');
+                    }
+                    in_space = true;
+                } else {
+                    out.push(c);
+                    in_space = false;
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Byte length of the tag at the start of `markup`, honouring quoted
+/// attribute values that contain `>`
+fn markup_tag_end(markup: &str) -> usize {
+    let mut quote = None;
+    for (i, c) in markup.char_indices().skip(1) {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, '>') => return i + 1,
+            _ => {}
+        }
+    }
+    markup.len()
+}
+
+/// Lowercased element name of a start or end tag; empty for anything else
+fn markup_tag_name(tag: &str) -> String {
+    tag.trim_start_matches('<')
+        .trim_start_matches('/')
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, ':' | '-'))
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+fn is_inline_tag(name: &str) -> bool {
+    matches!(
+        name,
+        "a" | "abbr"
+            | "b"
+            | "bdi"
+            | "bdo"
+            | "cite"
+            | "code"
+            | "dfn"
+            | "em"
+            | "i"
+            | "img"
+            | "kbd"
+            | "mark"
+            | "q"
+            | "s"
+            | "samp"
+            | "small"
+            | "span"
+            | "strong"
+            | "sub"
+            | "sup"
+            | "time"
+            | "u"
+            | "var"
+    )
+}
+
+/// Map curly quotes, en/em dashes, and the ellipsis character to ASCII
+pub fn normalize_punctuation(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' | '\u{2032}' => out.push('\''),
+            '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' | '\u{2033}' => out.push('"'),
+            '\u{2010}' | '\u{2011}' | '\u{2012}' | '\u{2013}' => out.push('-'),
+            '\u{2014}' | '\u{2015}' => out.push_str("--"),
+            '\u{2026}' => out.push_str("..."),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
 #[cfg(not(feature = "lowmem"))]
 /// Extract clean text content from HTML using tl
-pub fn extract_text_content(html: &str) -> Result<String> {
+pub fn extract_text_content_with(html: &str, options: &TextExtractOptions) -> Result<String> {
     let dom = tl::parse(html, ParserOptions::default())
         .map_err(|e| LexEpubError::Html(format!("Failed to parse HTML: {}", e)))?;
 
@@ -94,40 +1007,161 @@ pub fn extract_text_content(html: &str) -> Result<String> {
 
     // Extract text from top-level children
     for handle in dom.children() {
-        extract_text_recursive(*handle, parser, &mut text);
+        extract_text_recursive(*handle, parser, options, &mut text);
     }
 
-    // Clean up excess whitespace and newlines
-    let cleaned = text
-        .lines()
+    Ok(options.apply(clean_lines(&text)))
+}
+
+/// Trim every line and drop the empty ones
+fn clean_lines(text: &str) -> String {
+    text.lines()
         .map(|line| line.trim())
         .filter(|line| !line.is_empty())
         .collect::<Vec<_>>()
-        .join("\n");
+        .join("\n")
+}
+
+/// Whether an element is an annotation (`epub:type` includes `annotation`)
+fn is_annotation(tag: &tl::HTMLTag) -> bool {
+    tag.attributes()
+        .get("epub:type")
+        .flatten()
+        .is_some_and(|kind| {
+            kind.as_utf8_str()
+                .split_whitespace()
+                .any(|k| k == "annotation")
+        })
+}
+
+/// Text of each `epub:type="annotation"` element (typically an `<aside>`),
+/// in document order. Annotations nested inside another are part of the
+/// outer one's text.
+pub fn extract_annotations(html: &str) -> Result<Vec<String>> {
+    let dom = tl::parse(html, ParserOptions::default())
+        .map_err(|e| LexEpubError::Html(format!("Failed to parse HTML: {}", e)))?;
+    let parser = dom.parser();
 
-    Ok(cleaned)
+    let mut annotations = Vec::new();
+    for handle in dom.children() {
+        collect_annotations(*handle, parser, &mut annotations);
+    }
+    Ok(annotations)
+}
+
+fn collect_annotations(handle: tl::NodeHandle, parser: &tl::Parser, annotations: &mut Vec<String>) {
+    let Some(tag) = handle.get(parser).and_then(|node| node.as_tag()) else {
+        return;
+    };
+    if is_annotation(tag) {
+        let options = TextExtractOptions::new().include_annotations(true);
+        let mut text = String::new();
+        for child in tag.children().top().iter() {
+            extract_text_recursive(*child, parser, &options, &mut text);
+        }
+        let text = clean_lines(&text);
+        if !text.is_empty() {
+            annotations.push(text);
+        }
+    } else {
+        for child in tag.children().top().iter() {
+            collect_annotations(*child, parser, annotations);
+        }
+    }
+}
+
+/// Text of the element whose `id` attribute is `id`, descendants included,
+/// cleaned as by [`extract_text_content_with`]. `None` when no element
+/// carries that id. The element itself is always walked, so a targeted
+/// annotation `<aside>` yields its text even when annotations are excluded.
+pub fn element_text_by_id(
+    html: &str,
+    id: &str,
+    options: &TextExtractOptions,
+) -> Result<Option<String>> {
+    let dom = tl::parse(html, ParserOptions::default())
+        .map_err(|e| LexEpubError::Html(format!("Failed to parse HTML: {}", e)))?;
+    let parser = dom.parser();
+
+    let Some(tag) = dom
+        .get_element_by_id(id)
+        .and_then(|handle| handle.get(parser))
+        .and_then(|node| node.as_tag())
+    else {
+        return Ok(None);
+    };
+    let mut text = String::new();
+    for child in tag.children().top().iter() {
+        extract_text_recursive(*child, parser, options, &mut text);
+    }
+    Ok(Some(options.apply(clean_lines(&text))))
+}
+
+/// Whether an element's text is left out of extracted prose: `<script>` and
+/// `<style>` unless `include_scripts` is set, and `<head>` and `<title>`
+/// unless `include_title` is set
+fn is_non_prose_tag(name: &str, options: &TextExtractOptions) -> bool {
+    let name = name.to_ascii_lowercase();
+    match name.as_str() {
+        "script" | "style" => !options.include_scripts,
+        "head" | "title" => !options.include_title,
+        _ => false,
+    }
+}
+
+/// Append already entity-decoded text, dropping zero-width spaces: like
+/// `<wbr>` (which yields no output at all) they only mark a line-break
+/// opportunity, so they must not split words or end up in the extracted
+/// text. Non-breaking spaces (`&nbsp;`) become regular spaces.
+fn push_visible_text(output: &mut String, text: &str) {
+    output.extend(text.chars().filter_map(|c| match c {
+        '\u{200B}' => None,
+        '\u{00A0}' => Some(' '),
+        c => Some(c),
+    }));
 }
 
 /// Recursively extract text from tl nodes
-#[allow(dead_code)]
-fn extract_text_recursive(handle: tl::NodeHandle, parser: &tl::Parser, output: &mut String) {
+fn extract_text_recursive(
+    handle: tl::NodeHandle,
+    parser: &tl::Parser,
+    options: &TextExtractOptions,
+    output: &mut String,
+) {
     if let Some(node) = handle.get(parser) {
         match node {
             tl::Node::Raw(text_bytes) => {
                 let text_str = text_bytes.as_utf8_str();
-                let decoded = html_escape::decode_html_entities(&text_str);
-                output.push_str(&decoded);
+                push_visible_text(output, &options.decode(&text_str));
             }
             tl::Node::Tag(tag) => {
                 let tag_name = tag.name().as_utf8_str();
+                // JavaScript source and CSS rules stay out of the prose
+                // unless asked for. The head only holds the `<title>`,
+                // which is kept on request.
+                if is_non_prose_tag(&tag_name, options) {
+                    return;
+                }
+                if !options.include_annotations && is_annotation(tag) {
+                    return;
+                }
+                // tl reads a spaceless `<hr/>` as an `hr/` element wrapping
+                // what follows, so children are still walked below
+                if tag_name.trim_end_matches('/').eq_ignore_ascii_case("hr") {
+                    if let Some(marker) = options.hr_marker.as_deref() {
+                        output.push('\n');
+                        output.push_str(marker);
+                        output.push('\n');
+                    }
+                }
                 let is_block = matches!(
                     tag_name.as_ref(),
-                    "p" | "div" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "br" | "li"
+                    "p" | "div" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "br" | "li" | "title"
                 );
 
                 // Recursively process children
                 for child_handle in tag.children().top().iter() {
-                    extract_text_recursive(*child_handle, parser, output);
+                    extract_text_recursive(*child_handle, parser, options, output);
                 }
 
                 // Add newlines after block elements
@@ -143,22 +1177,82 @@ fn extract_text_recursive(handle: tl::NodeHandle, parser: &tl::Parser, output: &
 #[cfg(feature = "lowmem")]
 /// Lightweight HTML-to-text extractor for low-memory targets.
 // Not as robust as the tl-based version, but avoids the overhead of building a full DOM tree, haha.
-pub fn extract_text_content(html: &str) -> Result<String> {
+pub fn extract_text_content_with(html: &str, options: &TextExtractOptions) -> Result<String> {
     let mut out = String::new();
     let mut in_tag = false;
     let mut tag_buf = String::new();
     let mut last_was_space = false;
+    // Element whose body is skipped entirely (`script`, `style`, and unless
+    // the title is wanted `head` and `title`) until its closing tag
+    let mut skipping: Option<String> = None;
+    // Skipped annotation element (any tag, as for `is_annotation`) and the
+    // nesting depth of same-named elements inside it
+    let mut annotation: Option<String> = None;
+    let mut annotation_depth = 0usize;
 
     for c in html.chars() {
         if in_tag {
             if c == '>' {
                 in_tag = false;
-                let tag = tag_buf.trim().trim_start_matches('/').to_ascii_lowercase();
+                let raw = tag_buf.trim().to_ascii_lowercase();
+                let is_closing = raw.starts_with('/');
+                let tag = raw.trim_start_matches('/');
+                let name = tag
+                    .split(|ch: char| ch.is_whitespace() || ch == '/')
+                    .next()
+                    .unwrap_or("");
+                if let Some(skipped) = skipping.as_deref() {
+                    if is_closing && name == skipped {
+                        skipping = None;
+                    }
+                    tag_buf.clear();
+                    continue;
+                }
+                if is_non_prose_tag(name, options) {
+                    if !is_closing && !tag.ends_with('/') {
+                        skipping = Some(name.to_string());
+                    }
+                    tag_buf.clear();
+                    continue;
+                }
+                if let Some(open) = annotation.as_deref() {
+                    if name == open && !tag.ends_with('/') {
+                        if !is_closing {
+                            annotation_depth += 1;
+                        } else if annotation_depth > 1 {
+                            annotation_depth -= 1;
+                        } else {
+                            annotation = None;
+                        }
+                    }
+                    tag_buf.clear();
+                    continue;
+                }
+                if !is_closing
+                    && !tag.ends_with('/')
+                    && !options.include_annotations
+                    && tag_epub_type(tag)
+                        .is_some_and(|kind| kind.split_whitespace().any(|k| k == "annotation"))
+                {
+                    annotation = Some(name.to_string());
+                    annotation_depth = 1;
+                    tag_buf.clear();
+                    continue;
+                }
+                if let Some(marker) = options.hr_marker.as_ref().filter(|_| name == "hr") {
+                    out.push('\n');
+                    out.push_str(marker);
+                    out.push('\n');
+                    last_was_space = false;
+                    tag_buf.clear();
+                    continue;
+                }
                 if tag.starts_with('p')
                     || tag.starts_with("div")
                     || tag.starts_with("br")
                     || tag.starts_with('h')
                     || tag.starts_with("li")
+                    || tag.starts_with("title")
                 {
                     out.push('\n');
                 }
@@ -169,6 +1263,9 @@ pub fn extract_text_content(html: &str) -> Result<String> {
         } else if c == '<' {
             in_tag = true;
             tag_buf.clear();
+        } else if skipping.is_some() || annotation.is_some() || c == '\u{200B}' {
+            // Zero-width spaces, like `<wbr>`, produce no output
+            continue;
         } else {
             if c.is_whitespace() {
                 if !last_was_space {
@@ -182,18 +1279,216 @@ pub fn extract_text_content(html: &str) -> Result<String> {
         }
     }
 
-    let cleaned = out
-        .lines()
-        .map(|line| line.trim())
-        .filter(|line| !line.is_empty())
-        .collect::<Vec<_>>()
-        .join("\n");
+    // Tags are gone, so entities (`&amp;`, `&#8217;`, `&lt;`) can be decoded
+    // in one go without any of them being mistaken for markup
+    let mut text = String::with_capacity(out.len());
+    push_visible_text(&mut text, &options.decode(&out));
+
+    Ok(options.apply(clean_lines(&text)))
+}
+
+/// Value of the `epub:type` attribute in the inside of a start tag, such as
+/// `section epub:type="annotation"`
+#[cfg(feature = "lowmem")]
+fn tag_epub_type(tag: &str) -> Option<&str> {
+    let mut rest = tag;
+    while let Some(at) = rest.find("epub:type") {
+        let preceded_by_space = rest[..at].ends_with(char::is_whitespace);
+        rest = &rest[at + "epub:type".len()..];
+        let Some(value) = rest.trim_start().strip_prefix('=') else {
+            continue;
+        };
+        if !preceded_by_space {
+            continue;
+        }
+        let value = value.trim_start();
+        let quote = value.chars().next()?;
+        return if quote == '"' || quote == '\'' {
+            value[1..].split(quote).next()
+        } else {
+            value.split(char::is_whitespace).next()
+        };
+    }
+    None
+}
 
-    Ok(cleaned)
+/// Split HTML into block-level text units (headings, paragraphs, list
+/// items...). Only block-level elements start a new block; inline elements
+/// such as `<em>` or `<a>` stay in the surrounding one. Paragraphs inside a
+/// `<blockquote>` are quote blocks. Whitespace inside each block is
+/// collapsed to single spaces.
+pub fn extract_text_blocks(html: &str) -> Result<Vec<TextBlock>> {
+    collect_blocks(html, false)
+}
+
+/// Block splitting shared by [`extract_text_blocks`] and the Markdown
+/// renderer; `inline_marks` wraps emphasis in `*` and strong text in `**`.
+fn collect_blocks(html: &str, inline_marks: bool) -> Result<Vec<TextBlock>> {
+    let dom = tl::parse(html, ParserOptions::default())
+        .map_err(|e| LexEpubError::Html(format!("Failed to parse HTML: {}", e)))?;
+
+    let parser = dom.parser();
+    let mut blocks = Vec::new();
+    let mut current = String::new();
+    let mut id = None;
+
+    for handle in dom.children() {
+        collect_blocks_recursive(
+            *handle,
+            parser,
+            TextBlockKind::Paragraph,
+            &mut id,
+            &mut current,
+            &mut blocks,
+            inline_marks,
+        );
+    }
+    flush_block(TextBlockKind::Paragraph, &mut id, &mut current, &mut blocks);
+
+    Ok(blocks)
+}
+
+fn block_kind_for(tag_name: &str) -> Option<TextBlockKind> {
+    match tag_name {
+        "h1" => Some(TextBlockKind::Heading(1)),
+        "h2" => Some(TextBlockKind::Heading(2)),
+        "h3" => Some(TextBlockKind::Heading(3)),
+        "h4" => Some(TextBlockKind::Heading(4)),
+        "h5" => Some(TextBlockKind::Heading(5)),
+        "h6" => Some(TextBlockKind::Heading(6)),
+        "p" | "div" | "section" | "article" | "aside" | "header" | "footer" | "figure"
+        | "figcaption" | "dt" | "dd" | "td" | "th" | "caption" | "body" => {
+            Some(TextBlockKind::Paragraph)
+        }
+        "li" => Some(TextBlockKind::ListItem),
+        "blockquote" => Some(TextBlockKind::Quote),
+        "pre" => Some(TextBlockKind::Preformatted),
+        _ => None,
+    }
+}
+
+/// Push the pending text as a block. The element id goes to the first block
+/// the element produces.
+fn flush_block(
+    kind: TextBlockKind,
+    id: &mut Option<String>,
+    current: &mut String,
+    blocks: &mut Vec<TextBlock>,
+) {
+    let text = if kind == TextBlockKind::Preformatted {
+        current.trim_matches('\n').to_string()
+    } else {
+        current.split_whitespace().collect::<Vec<_>>().join(" ")
+    };
+    if !text.is_empty() {
+        blocks.push(TextBlock {
+            kind,
+            text,
+            id: id.take(),
+        });
+    }
+    current.clear();
+}
+
+fn collect_blocks_recursive(
+    handle: tl::NodeHandle,
+    parser: &tl::Parser,
+    kind: TextBlockKind,
+    id: &mut Option<String>,
+    current: &mut String,
+    blocks: &mut Vec<TextBlock>,
+    inline_marks: bool,
+) {
+    let Some(node) = handle.get(parser) else {
+        return;
+    };
+    match node {
+        tl::Node::Raw(text_bytes) => {
+            let text_str = text_bytes.as_utf8_str();
+            push_visible_text(current, &html_escape::decode_html_entities(&text_str));
+        }
+        tl::Node::Tag(tag) => {
+            let tag_name = tag.name().as_utf8_str().to_ascii_lowercase();
+            if matches!(tag_name.as_str(), "script" | "style" | "head") || is_annotation(tag) {
+                return;
+            }
+            if tag_name == "br" {
+                current.push(' ');
+                return;
+            }
+
+            // Inline elements just contribute text to the enclosing block;
+            // only block-level elements start (and end) a new one.
+            match block_kind_for(&tag_name) {
+                Some(block_kind) => {
+                    let block_kind = match (kind, block_kind) {
+                        (TextBlockKind::Quote, TextBlockKind::Paragraph) => TextBlockKind::Quote,
+                        _ => block_kind,
+                    };
+                    flush_block(kind, id, current, blocks);
+                    let mut block_id = tag
+                        .attributes()
+                        .id()
+                        .map(|value| value.as_utf8_str().to_string());
+                    for child in tag.children().top().iter() {
+                        collect_blocks_recursive(
+                            *child,
+                            parser,
+                            block_kind,
+                            &mut block_id,
+                            current,
+                            blocks,
+                            inline_marks,
+                        );
+                    }
+                    flush_block(block_kind, &mut block_id, current, blocks);
+                }
+                None => {
+                    let mark = match tag_name.as_str() {
+                        _ if !inline_marks || kind == TextBlockKind::Preformatted => "",
+                        "em" | "i" => "*",
+                        "strong" | "b" => "**",
+                        _ => "",
+                    };
+                    let start = current.len();
+                    let block_count = blocks.len();
+                    for child in tag.children().top().iter() {
+                        collect_blocks_recursive(
+                            *child,
+                            parser,
+                            kind,
+                            id,
+                            current,
+                            blocks,
+                            inline_marks,
+                        );
+                    }
+                    // Keep surrounding whitespace outside the markers, and
+                    // skip them when the element closed a block mid-way
+                    if !mark.is_empty() && blocks.len() == block_count {
+                        let inner = current.split_off(start);
+                        let trimmed = inner.trim();
+                        if trimmed.is_empty() {
+                            current.push_str(&inner);
+                        } else {
+                            let leading = &inner[..inner.len() - inner.trim_start().len()];
+                            let trailing = &inner[inner.trim_end().len()..];
+                            current.push_str(leading);
+                            current.push_str(mark);
+                            current.push_str(trimmed);
+                            current.push_str(mark);
+                            current.push_str(trailing);
+                        }
+                    }
+                }
+            }
+        }
+        tl::Node::Comment(_) => {}
+    }
 }
 
 /// Parse HTML into AST structure using tl
-fn parse_html_ast(html: &str) -> Result<AstNode> {
+fn parse_html_ast(html: &str, max_depth: Option<usize>) -> Result<AstNode> {
     let dom = tl::parse(html, ParserOptions::default())
         .map_err(|e| LexEpubError::Html(format!("Failed to parse HTML: {}", e)))?;
 
@@ -203,7 +1498,7 @@ fn parse_html_ast(html: &str) -> Result<AstNode> {
     let mut root_children = Vec::new();
 
     for handle in dom.children() {
-        if let Some(ast_child) = node_to_ast(*handle, parser) {
+        if let Some(ast_child) = node_to_ast(*handle, parser, 1, max_depth) {
             root_children.push(ast_child);
         }
     }
@@ -217,11 +1512,21 @@ fn parse_html_ast(html: &str) -> Result<AstNode> {
     })
 }
 
-/// Convert tl node handle to our AstNode format
-fn node_to_ast(handle: tl::NodeHandle, parser: &tl::Parser) -> Option<AstNode> {
+/// Convert tl node handle to our AstNode format. `depth` is the element
+/// level of the node (1 for top-level elements); elements past `max_depth`
+/// become a text node with their concatenated text (see [`collapsed_text`]).
+fn node_to_ast(
+    handle: tl::NodeHandle,
+    parser: &tl::Parser,
+    depth: usize,
+    max_depth: Option<usize>,
+) -> Option<AstNode> {
     let node = handle.get(parser)?;
 
     match node {
+        tl::Node::Tag(tag) if max_depth.is_some_and(|max| depth > max) => {
+            collapsed_text(tag, parser).map(|content| AstNode::Text { content })
+        }
         tl::Node::Tag(tag) => {
             let mut attrs = HashMap::new();
 
@@ -237,7 +1542,7 @@ fn node_to_ast(handle: tl::NodeHandle, parser: &tl::Parser) -> Option<AstNode> {
 
             let mut children = Vec::new();
             for child_handle in tag.children().top().iter() {
-                if let Some(child_ast) = node_to_ast(*child_handle, parser) {
+                if let Some(child_ast) = node_to_ast(*child_handle, parser, depth + 1, max_depth) {
                     children.push(child_ast);
                 }
             }
@@ -258,6 +1563,37 @@ fn node_to_ast(handle: tl::NodeHandle, parser: &tl::Parser) -> Option<AstNode> {
     }
 }
 
+/// Text of an element collapsed past the AST depth limit, `None` for an
+/// element text extraction leaves out (`<script>`, `<style>`, `<head>`,
+/// `<title>`). The subtree is walked with an explicit stack, skipping those
+/// elements the same way, so arbitrarily deep nesting cannot overflow it.
+fn collapsed_text(tag: &tl::HTMLTag, parser: &tl::Parser) -> Option<String> {
+    let options = TextExtractOptions::default();
+    if is_non_prose_tag(&tag.name().as_utf8_str(), &options) {
+        return None;
+    }
+    let mut text = String::new();
+    // Children are pushed in reverse so they pop in document order
+    let mut pending: Vec<tl::NodeHandle> = tag.children().top().iter().copied().collect();
+    pending.reverse();
+    while let Some(handle) = pending.pop() {
+        match handle.get(parser) {
+            Some(tl::Node::Raw(raw)) => {
+                text.push_str(&html_escape::decode_html_entities(&raw.as_utf8_str()))
+            }
+            Some(tl::Node::Tag(child))
+                if !is_non_prose_tag(&child.name().as_utf8_str(), &options) =>
+            {
+                let start = pending.len();
+                pending.extend(child.children().top().iter().copied());
+                pending[start..].reverse();
+            }
+            _ => {}
+        }
+    }
+    Some(text)
+}
+
 fn extract_title_from_ast(ast: &AstNode) -> Option<String> {
     fn first_non_empty_text(node: &AstNode) -> Option<String> {
         match node {
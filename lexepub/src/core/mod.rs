@@ -1,14 +1,22 @@
 pub mod chapter;
+pub mod chunked;
 pub mod container;
 pub mod css;
+pub mod encoding;
+pub mod encryption;
 pub mod extractor;
 pub mod html_parser;
+pub mod ncx;
 pub mod opf_parser;
 
 // Re-export for convenience
 pub use chapter::*;
+pub use chunked::*;
 pub use container::*;
 pub use css::*;
+pub use encoding::*;
+pub use encryption::*;
 pub use extractor::*;
 pub use html_parser::*;
+pub use ncx::*;
 pub use opf_parser::*;
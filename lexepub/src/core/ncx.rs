@@ -0,0 +1,107 @@
+use crate::error::{LexEpubError, Result};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+/// One `<navPoint>` of an EPUB2 NCX table of contents
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NavPoint {
+    /// Text of the `<navLabel>`
+    pub label: String,
+    /// `src` of the `<content>` element, relative to the NCX file
+    pub src: String,
+    /// Nested navPoints
+    pub children: Vec<NavPoint>,
+    /// The `playOrder` attribute (NCX only)
+    pub play_order: Option<u32>,
+}
+
+/// File named in XML errors from [`parse_ncx`], which does not know the
+/// document's archive path
+const NCX_FILE: &str = "NCX document";
+
+/// Parse the `<navMap>` of an NCX document into a tree of navPoints, in
+/// document order
+pub fn parse_ncx(data: &[u8]) -> Result<Vec<NavPoint>> {
+    let mut reader = Reader::from_reader(data);
+
+    let mut roots = Vec::new();
+    // Open navPoints, innermost last
+    let mut stack: Vec<NavPoint> = Vec::new();
+    let mut in_label = false;
+    let mut in_nav_map = false;
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e)) => match e.local_name().as_ref() {
+                b"navMap" => in_nav_map = true,
+                b"navPoint" if in_nav_map => {
+                    let play_order = e
+                        .attributes()
+                        .flatten()
+                        .find(|attr| attr.key.as_ref() == b"playOrder")
+                        .and_then(|attr| String::from_utf8_lossy(&attr.value).trim().parse().ok());
+                    stack.push(NavPoint {
+                        play_order,
+                        ..NavPoint::default()
+                    });
+                }
+                b"navLabel" => in_label = true,
+                _ => {}
+            },
+            Ok(Event::Empty(ref e)) if e.local_name().as_ref() == b"content" => {
+                if let Some(point) = stack.last_mut() {
+                    for attr in e.attributes().flatten() {
+                        if attr.key.as_ref() == b"src" {
+                            point.src = String::from_utf8_lossy(&attr.value).to_string();
+                        }
+                    }
+                }
+            }
+            Ok(Event::Text(ref e)) if in_label => {
+                if let Some(point) = stack.last_mut() {
+                    point.label.push_str(&e.decode().unwrap_or_default());
+                }
+            }
+            Ok(Event::GeneralRef(ref e)) if in_label => {
+                if let Some(point) = stack.last_mut() {
+                    let name = e.decode().unwrap_or_default();
+                    let reference = format!("&{};", name);
+                    point
+                        .label
+                        .push_str(&html_escape::decode_html_entities(&reference));
+                }
+            }
+            Ok(Event::End(ref e)) => match e.local_name().as_ref() {
+                b"navMap" => in_nav_map = false,
+                b"navLabel" => {
+                    in_label = false;
+                    if let Some(point) = stack.last_mut() {
+                        point.label = point.label.split_whitespace().collect::<Vec<_>>().join(" ");
+                    }
+                }
+                b"navPoint" => {
+                    if let Some(point) = stack.pop() {
+                        match stack.last_mut() {
+                            Some(parent) => parent.children.push(point),
+                            None => roots.push(point),
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(LexEpubError::XmlInContext {
+                    file: NCX_FILE.to_string(),
+                    position: reader.error_position(),
+                    source: e,
+                })
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(roots)
+}
@@ -4,23 +4,211 @@ use quick_xml::reader::Reader;
 use std::collections::HashMap;
 use std::io::Cursor;
 
+/// A `dc:creator` together with its EPUB3 refinements
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Creator {
+    pub name: String,
+    /// The element's `id`, which `<meta refines="#id">` entries point at
+    pub id: Option<String>,
+    /// Renderings of the name in other scripts, as `(language, name)` pairs
+    /// from `alternate-script` refinements
+    pub alternates: Vec<(String, String)>,
+    /// MARC relator code such as `aut` or `ill`, from a `role` refinement
+    /// or the EPUB2 `opf:role` attribute
+    #[serde(default)]
+    pub role: Option<String>,
+    /// Sort form of the name ("Twain, Mark"), from a `file-as` refinement
+    /// or the EPUB2 `opf:file-as` attribute
+    #[serde(default)]
+    pub file_as: Option<String>,
+}
+
+/// A `dc:identifier` with its scheme
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Identifier {
+    /// `opf:scheme` (`ISBN`, `UUID`, `DOI`...), or inferred from a
+    /// `urn:isbn:`/`urn:uuid:` value when the attribute is absent
+    pub scheme: Option<String>,
+    pub value: String,
+    /// Whether `<package unique-identifier>` points at this identifier
+    pub is_unique_id: bool,
+}
+
+impl Identifier {
+    /// Whether the scheme is ISBN (case-insensitive)
+    pub fn is_isbn(&self) -> bool {
+        self.scheme
+            .as_deref()
+            .is_some_and(|scheme| scheme.eq_ignore_ascii_case("isbn"))
+    }
+}
+
+/// Scheme implied by a URN-style identifier value
+fn scheme_from_value(value: &str) -> Option<String> {
+    let lower = value.trim().to_ascii_lowercase();
+    if lower.starts_with("urn:isbn:") {
+        Some("ISBN".to_string())
+    } else if lower.starts_with("urn:uuid:") {
+        Some("UUID".to_string())
+    } else {
+        None
+    }
+}
+
+/// An EPUB3 `belongs-to-collection` entry, e.g. a series or a set
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Collection {
+    pub name: String,
+    /// `collection-type` refinement (`series`, `set`...)
+    pub collection_type: Option<String>,
+    /// `group-position` refinement: the book's place in the collection
+    pub position: Option<f32>,
+    /// Index in the same list of the collection this one belongs to, from a
+    /// `belongs-to-collection` meta refining it
+    #[serde(default)]
+    pub parent: Option<usize>,
+}
+
+/// Dublin Core and OPF elements inside `<metadata>` that map to a field
+const HANDLED_METADATA_ELEMENTS: [&str; 12] = [
+    "title",
+    "creator",
+    "description",
+    "language",
+    "subject",
+    "publisher",
+    "date",
+    "identifier",
+    "rights",
+    "contributor",
+    "meta",
+    "link",
+];
+
+/// `<meta property>` values (top-level or refinements) that map to a field
+const HANDLED_META_PROPERTIES: [&str; 17] = [
+    "rendition:layout",
+    "dcterms:license",
+    "cc:license",
+    "dcterms:conformsTo",
+    "a11y:certifiedBy",
+    "a11y:certifierReport",
+    "belongs-to-collection",
+    "collection-type",
+    "group-position",
+    "alternate-script",
+    "role",
+    "file-as",
+    "primary",
+    "display-seq",
+    "schema:numberOfPages",
+    "dcterms:dateCopyrighted",
+    "dcterms:modified",
+];
+
+/// `<link rel>` tokens that map to a field
+const HANDLED_LINK_RELS: [&str; 5] = [
+    "cc:license",
+    "license",
+    "dcterms:license",
+    "dcterms:conformsTo",
+    "a11y:certifierReport",
+];
+
+/// Whether the crate does something with manifest items of this media
+/// type: markup, stylesheets, images, fonts, or the NCX
+fn is_handled_media_type(media_type: &str) -> bool {
+    let essence = media_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    crate::core::chapter::is_markup_media_type(&essence)
+        || essence.starts_with("image/")
+        || essence.starts_with("font/")
+        || matches!(
+            essence.as_str(),
+            "text/css"
+                | "application/x-dtbncx+xml"
+                | "application/font-woff"
+                | "application/font-sfnt"
+                | "application/vnd.ms-opentype"
+                | "application/x-font-ttf"
+                | "application/x-font-opentype"
+        )
+}
+
 /// Metadata extracted from OPF file
 #[derive(Debug, Clone)]
 pub struct OpfMetadata {
     pub title: Option<String>,
+    /// Every `dc:title`, ordered by `display-seq` refinements where present
+    /// (unsequenced titles follow in document order)
+    pub titles: Vec<String>,
     pub version: Option<String>,
     pub creators: Vec<String>,
+    /// Creators with their refinements, parallel to `creators`
+    pub creator_details: Vec<Creator>,
     pub description: Option<String>,
     pub languages: Vec<String>,
     pub subjects: Vec<String>,
     pub publisher: Option<String>,
+    /// Publication date: the `dc:date` with `opf:event="publication"`,
+    /// otherwise the last one without an event, otherwise the last one
     pub date: Option<String>,
-    pub identifiers: Vec<String>,
+    /// Every `dc:date` as `(opf:event, value)`, in document order
+    pub dates: Vec<(Option<String>, String)>,
+    /// `dcterms:modified` of the whole book
+    pub modified: Option<String>,
+    pub identifiers: Vec<Identifier>,
+    /// Value of the identifier referenced by `<package unique-identifier>`
+    pub unique_identifier: Option<String>,
     pub rights: Option<String>,
     pub contributors: Vec<String>,
     pub spine: Vec<String>,
     pub manifest: HashMap<String, (String, String)>,
+    /// Raw `properties` attribute of manifest items, keyed by item id
+    pub manifest_properties: HashMap<String, String>,
     pub cover_image_id: Option<String>,
+    /// `page-progression-direction` of the spine (`ltr`, `rtl`, or `default`)
+    pub page_progression_direction: Option<String>,
+    /// Manifest id of the NCX, from the spine's `toc` attribute
+    pub toc_id: Option<String>,
+    /// Value of `<meta property="rendition:layout">` (`reflowable` or
+    /// `pre-paginated`)
+    pub rendition_layout: Option<String>,
+    /// License URL from `<link rel="cc:license">` or a `dcterms:license` meta
+    pub license_link: Option<String>,
+    /// EPUB2 `<guide>` references as `(type, href)`, hrefs relative to the OPF
+    pub guide: Vec<(String, String)>,
+    /// Language a `<meta refines="#lang-id" property="primary">` marks as
+    /// the primary one, if any
+    pub primary_language: Option<String>,
+    /// Accessibility conformance claims from `dcterms:conformsTo` metas and
+    /// links (a specification name or URL each)
+    pub conforms_to: Vec<String>,
+    /// Value of the `a11y:certifiedBy` meta
+    pub certified_by: Option<String>,
+    /// `belongs-to-collection` entries in document order, including
+    /// collections nested inside another one
+    pub collections: Vec<Collection>,
+    /// `a11y:certifierReport` meta value or link href
+    pub certifier_report: Option<String>,
+    /// `schema:numberOfPages` of the whole book (print page count)
+    pub number_of_pages: Option<u32>,
+    /// `dcterms:dateCopyrighted` of the whole book
+    pub copyright_date: Option<String>,
+}
+
+impl OpfMetadata {
+    /// Check whether a manifest item declares the given `properties` token
+    /// (e.g. `scripted`, `nav`, `cover-image`).
+    pub fn item_has_property(&self, id: &str, property: &str) -> bool {
+        self.manifest_properties
+            .get(id)
+            .is_some_and(|props| props.split_whitespace().any(|p| p == property))
+    }
 }
 
 pub struct OpfParser {
@@ -35,6 +223,16 @@ impl OpfParser {
         }
     }
 
+    /// XML error at the reader's current error position. The archive path
+    /// is not known here; callers that have it attach it.
+    fn xml_error(&self, source: quick_xml::Error) -> LexEpubError {
+        LexEpubError::XmlInContext {
+            file: "package document".to_string(),
+            position: self.reader.error_position(),
+            source,
+        }
+    }
+
     /// Parse OPF file for metadata
     pub fn parse_metadata(&mut self, data: &[u8]) -> Result<OpfMetadata> {
         self.reader = Reader::from_reader(std::io::Cursor::new(data.to_vec()));
@@ -42,40 +240,142 @@ impl OpfParser {
 
         let mut metadata = OpfMetadata {
             title: None,
+            titles: Vec::new(),
             version: None,
             creators: Vec::new(),
+            creator_details: Vec::new(),
             description: None,
             languages: Vec::new(),
             subjects: Vec::new(),
             publisher: None,
             date: None,
+            dates: Vec::new(),
+            modified: None,
             identifiers: Vec::new(),
+            unique_identifier: None,
             rights: None,
             contributors: Vec::new(),
             spine: Vec::new(),
             manifest: HashMap::new(),
+            manifest_properties: HashMap::new(),
             cover_image_id: None,
+            page_progression_direction: None,
+            toc_id: None,
+            rendition_layout: None,
+            license_link: None,
+            guide: Vec::new(),
+            primary_language: None,
+            conforms_to: Vec::new(),
+            certified_by: None,
+            certifier_report: None,
+            number_of_pages: None,
+            copyright_date: None,
+            collections: Vec::new(),
         };
 
         let mut in_metadata = false;
         let mut in_manifest = false;
         let mut in_spine = false;
         let mut current_element = String::new();
+        let mut unique_identifier_id = None;
+        let mut current_element_id = None;
+        let mut current_meta_property = None;
+        let mut current_refines = None;
+        let mut current_lang = None;
+        let mut current_event = None;
+        let mut current_scheme = None;
+        // EPUB2 `opf:role` / `opf:file-as` on the current creator
+        let mut current_role = None;
+        let mut current_file_as = None;
+        // (refined id, property, xml:lang, value), resolved once all creators are known
+        let mut refinements: Vec<(String, String, Option<String>, String)> = Vec::new();
+        // (element id, language) for dc:language entries that have an id
+        let mut language_ids: Vec<(String, String)> = Vec::new();
+        // (element id, title) for every dc:title, in document order
+        let mut titles: Vec<(Option<String>, String)> = Vec::new();
+        // (element id, refined id, collection) for every belongs-to-collection
+        // meta; parents are linked once every id is known
+        let mut collections: Vec<(Option<String>, Option<String>, Collection)> = Vec::new();
         let mut buf = Vec::new();
 
         loop {
             match self.reader.read_event_into(&mut buf) {
+                // Declarations, stylesheet PIs and comments ahead of `<package>`
+                // fall through to the catch-all arm. Elements are matched by
+                // local name so prefixed OPFs (`opf:package`) parse the same.
                 Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e)) => {
-                    let tag_name = String::from_utf8_lossy(e.name().as_ref()).to_lowercase();
+                    let tag_name = String::from_utf8_lossy(e.local_name().as_ref()).to_lowercase();
                     current_element = tag_name.clone();
 
-                    if current_element == "package" {
-                        for attr in e.attributes().flatten() {
-                            if attr.key.as_ref() == b"version" {
+                    current_element_id = None;
+                    current_meta_property = None;
+                    current_refines = None;
+                    current_lang = None;
+                    current_event = None;
+                    current_scheme = None;
+                    current_role = None;
+                    current_file_as = None;
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"refines" if current_element == "meta" => {
+                                let target = String::from_utf8_lossy(&attr.value);
+                                current_refines = Some(target.trim_start_matches('#').to_string());
+                            }
+                            b"xml:lang" => {
+                                current_lang =
+                                    Some(String::from_utf8_lossy(&attr.value).to_string());
+                            }
+                            b"page-progression-direction" if current_element == "spine" => {
+                                metadata.page_progression_direction =
+                                    Some(String::from_utf8_lossy(&attr.value).to_string());
+                            }
+                            b"property" if current_element == "meta" => {
+                                current_meta_property =
+                                    Some(String::from_utf8_lossy(&attr.value).to_string());
+                            }
+                            b"toc" if current_element == "spine" => {
+                                metadata.toc_id =
+                                    Some(String::from_utf8_lossy(&attr.value).to_string());
+                            }
+                            b"version" if current_element == "package" => {
                                 metadata.version =
                                     Some(String::from_utf8_lossy(&attr.value).to_string());
-                                break;
                             }
+                            b"unique-identifier" if current_element == "package" => {
+                                unique_identifier_id =
+                                    Some(String::from_utf8_lossy(&attr.value).to_string());
+                            }
+                            b"id" => {
+                                current_element_id =
+                                    Some(String::from_utf8_lossy(&attr.value).to_string());
+                            }
+                            // `opf:event` on EPUB2 dates
+                            _ if current_element == "date"
+                                && attr.key.local_name().as_ref() == b"event" =>
+                            {
+                                current_event =
+                                    Some(String::from_utf8_lossy(&attr.value).trim().to_string());
+                            }
+                            // `opf:scheme` on EPUB2 identifiers
+                            _ if current_element == "identifier"
+                                && attr.key.local_name().as_ref() == b"scheme" =>
+                            {
+                                current_scheme =
+                                    Some(String::from_utf8_lossy(&attr.value).trim().to_string());
+                            }
+                            _ if current_element == "creator"
+                                && attr.key.local_name().as_ref() == b"role" =>
+                            {
+                                current_role =
+                                    Some(String::from_utf8_lossy(&attr.value).trim().to_string());
+                            }
+                            _ if current_element == "creator"
+                                && attr.key.local_name().as_ref() == b"file-as" =>
+                            {
+                                current_file_as =
+                                    Some(String::from_utf8_lossy(&attr.value).trim().to_string());
+                            }
+                            _ => {}
                         }
                     }
 
@@ -87,7 +387,7 @@ impl OpfParser {
                             let mut id = String::new();
                             let mut href = String::new();
                             let mut media_type = String::new();
-                            let mut is_cover = false;
+                            let mut properties = String::new();
                             for attr in e.attributes().flatten() {
                                 match attr.key.as_ref() {
                                     b"id" => id = String::from_utf8_lossy(&attr.value).to_string(),
@@ -99,18 +399,19 @@ impl OpfParser {
                                             String::from_utf8_lossy(&attr.value).to_string()
                                     }
                                     b"properties" => {
-                                        let props = String::from_utf8_lossy(&attr.value);
-                                        if props.contains("cover-image") {
-                                            is_cover = true;
-                                        }
+                                        properties =
+                                            String::from_utf8_lossy(&attr.value).to_string()
                                     }
                                     _ => {}
                                 }
                             }
                             if !id.is_empty() && !href.is_empty() {
-                                if is_cover {
+                                if properties.contains("cover-image") {
                                     metadata.cover_image_id = Some(id.clone());
                                 }
+                                if !properties.is_empty() {
+                                    metadata.manifest_properties.insert(id.clone(), properties);
+                                }
                                 metadata.manifest.insert(id, (href, media_type));
                             }
                         }
@@ -123,6 +424,51 @@ impl OpfParser {
                                 }
                             }
                         }
+                        "reference" => {
+                            let mut kind = String::new();
+                            let mut href = String::new();
+                            for attr in e.attributes().flatten() {
+                                match attr.key.as_ref() {
+                                    b"type" => {
+                                        kind = String::from_utf8_lossy(&attr.value).to_string()
+                                    }
+                                    b"href" => {
+                                        href = String::from_utf8_lossy(&attr.value).to_string()
+                                    }
+                                    _ => {}
+                                }
+                            }
+                            if !kind.is_empty() && !href.is_empty() {
+                                metadata.guide.push((kind, href));
+                            }
+                        }
+                        "link" if in_metadata => {
+                            let mut rel = String::new();
+                            let mut href = String::new();
+                            for attr in e.attributes().flatten() {
+                                match attr.key.as_ref() {
+                                    b"rel" => {
+                                        rel = String::from_utf8_lossy(&attr.value).to_string()
+                                    }
+                                    b"href" => {
+                                        href = String::from_utf8_lossy(&attr.value).to_string()
+                                    }
+                                    _ => {}
+                                }
+                            }
+                            let is_license = rel
+                                .split_whitespace()
+                                .any(|r| matches!(r, "cc:license" | "license" | "dcterms:license"));
+                            if href.is_empty() {
+                                // Nothing to record
+                            } else if is_license {
+                                metadata.license_link.get_or_insert(href);
+                            } else if rel.split_whitespace().any(|r| r == "dcterms:conformsTo") {
+                                metadata.conforms_to.push(href);
+                            } else if rel.split_whitespace().any(|r| r == "a11y:certifierReport") {
+                                metadata.certifier_report.get_or_insert(href);
+                            }
+                        }
                         "meta" if in_metadata => {
                             let mut name = String::new();
                             let mut content = String::new();
@@ -156,15 +502,28 @@ impl OpfParser {
                     if in_metadata {
                         match current_element.as_str() {
                             "dc:title" | "title" => {
+                                titles.push((current_element_id.clone(), text.clone()));
                                 metadata.title = Some(text);
                             }
                             "dc:creator" | "creator" => {
+                                metadata.creator_details.push(Creator {
+                                    name: text.clone(),
+                                    id: current_element_id.clone(),
+                                    alternates: Vec::new(),
+                                    role: current_role.clone().filter(|role| !role.is_empty()),
+                                    file_as: current_file_as
+                                        .clone()
+                                        .filter(|file_as| !file_as.is_empty()),
+                                });
                                 metadata.creators.push(text);
                             }
                             "dc:description" | "description" => {
                                 metadata.description = Some(text);
                             }
                             "dc:language" | "language" => {
+                                if let Some(id) = &current_element_id {
+                                    language_ids.push((id.clone(), text.clone()));
+                                }
                                 metadata.languages.push(text);
                             }
                             "dc:subject" | "subject" => {
@@ -174,10 +533,22 @@ impl OpfParser {
                                 metadata.publisher = Some(text);
                             }
                             "dc:date" | "date" => {
-                                metadata.date = Some(text);
+                                metadata.dates.push((current_event.clone(), text));
                             }
                             "dc:identifier" | "identifier" => {
-                                metadata.identifiers.push(text);
+                                let is_unique_id = unique_identifier_id.is_some()
+                                    && unique_identifier_id == current_element_id;
+                                if is_unique_id {
+                                    metadata.unique_identifier = Some(text.clone());
+                                }
+                                metadata.identifiers.push(Identifier {
+                                    scheme: current_scheme
+                                        .clone()
+                                        .filter(|scheme| !scheme.is_empty())
+                                        .or_else(|| scheme_from_value(&text)),
+                                    value: text,
+                                    is_unique_id,
+                                });
                             }
                             "dc:rights" | "rights" => {
                                 metadata.rights = Some(text);
@@ -185,12 +556,103 @@ impl OpfParser {
                             "dc:contributor" | "contributor" => {
                                 metadata.contributors.push(text);
                             }
+                            "meta"
+                                if current_meta_property.as_deref() == Some("rendition:layout") =>
+                            {
+                                metadata.rendition_layout = Some(text.trim().to_string());
+                            }
+                            "meta"
+                                if current_refines.is_none()
+                                    && matches!(
+                                        current_meta_property.as_deref(),
+                                        Some("dcterms:license" | "cc:license")
+                                    ) =>
+                            {
+                                metadata
+                                    .license_link
+                                    .get_or_insert_with(|| text.trim().to_string());
+                            }
+                            "meta"
+                                if current_refines.is_none()
+                                    && current_meta_property.as_deref()
+                                        == Some("dcterms:conformsTo") =>
+                            {
+                                metadata.conforms_to.push(text.trim().to_string());
+                            }
+                            "meta"
+                                if current_refines.is_none()
+                                    && current_meta_property.as_deref()
+                                        == Some("dcterms:modified") =>
+                            {
+                                metadata.modified = Some(text.trim().to_string());
+                            }
+                            "meta"
+                                if current_meta_property.as_deref() == Some("a11y:certifiedBy") =>
+                            {
+                                metadata
+                                    .certified_by
+                                    .get_or_insert_with(|| text.trim().to_string());
+                            }
+                            // Usually refines the certifier, but there is only one
+                            "meta"
+                                if current_meta_property.as_deref()
+                                    == Some("a11y:certifierReport") =>
+                            {
+                                metadata
+                                    .certifier_report
+                                    .get_or_insert_with(|| text.trim().to_string());
+                            }
+                            // Whole-book metas: top-level, refining the package
+                            // document itself, or its unique identifier
+                            "meta"
+                                if matches!(
+                                    current_meta_property.as_deref(),
+                                    Some("schema:numberOfPages" | "dcterms:dateCopyrighted")
+                                ) && current_refines.as_ref().is_none_or(|target| {
+                                    target.is_empty()
+                                        || Some(target) == unique_identifier_id.as_ref()
+                                }) =>
+                            {
+                                let value = text.trim();
+                                if current_meta_property.as_deref() == Some("schema:numberOfPages")
+                                {
+                                    metadata.number_of_pages = value.parse().ok();
+                                } else {
+                                    metadata.copyright_date = Some(value.to_string());
+                                }
+                            }
+                            // Top-level, or refining (nested in) another collection
+                            "meta"
+                                if current_meta_property.as_deref()
+                                    == Some("belongs-to-collection") =>
+                            {
+                                collections.push((
+                                    current_element_id.clone(),
+                                    current_refines.clone(),
+                                    Collection {
+                                        name: text.trim().to_string(),
+                                        ..Collection::default()
+                                    },
+                                ));
+                            }
+                            "meta" => {
+                                if let (Some(target), Some(property)) =
+                                    (&current_refines, &current_meta_property)
+                                {
+                                    refinements.push((
+                                        target.clone(),
+                                        property.clone(),
+                                        current_lang.clone(),
+                                        text.trim().to_string(),
+                                    ));
+                                }
+                            }
                             _ => {}
                         }
                     }
                 }
                 Ok(Event::End(ref e)) => {
-                    let tag_name = String::from_utf8_lossy(e.name().as_ref()).to_lowercase();
+                    let tag_name = String::from_utf8_lossy(e.local_name().as_ref()).to_lowercase();
                     match tag_name.as_str() {
                         "metadata" => in_metadata = false,
                         "manifest" => in_manifest = false,
@@ -199,11 +661,118 @@ impl OpfParser {
                     }
                 }
                 Ok(Event::Eof) => break,
-                Err(e) => return Err(LexEpubError::Xml(e)),
+                Err(e) => return Err(self.xml_error(e)),
                 _ => buf.clear(),
             }
         }
 
+        let event_is = |event: &Option<String>, name: &str| {
+            event
+                .as_deref()
+                .is_some_and(|event| event.eq_ignore_ascii_case(name))
+        };
+        metadata.date = metadata
+            .dates
+            .iter()
+            .find(|(event, _)| event_is(event, "publication"))
+            .or_else(|| {
+                metadata
+                    .dates
+                    .iter()
+                    .rev()
+                    .find(|(event, _)| event.is_none())
+            })
+            .or_else(|| metadata.dates.last())
+            .map(|(_, date)| date.clone());
+
+        // Sequenced entries first, by display-seq; the rest keep document order
+        let display_seq = |id: Option<&String>| {
+            let seq = id.and_then(|id| {
+                refinements
+                    .iter()
+                    .find(|(target, property, _, _)| target == id && property == "display-seq")
+                    .and_then(|(_, _, _, value)| value.parse::<u32>().ok())
+            });
+            (seq.is_none(), seq.unwrap_or_default())
+        };
+        titles.sort_by_key(|(id, _)| display_seq(id.as_ref()));
+        metadata.titles = titles.into_iter().map(|(_, title)| title).collect();
+        metadata
+            .creator_details
+            .sort_by_key(|creator| display_seq(creator.id.as_ref()));
+        metadata.creators = metadata
+            .creator_details
+            .iter()
+            .map(|creator| creator.name.clone())
+            .collect();
+
+        for (target, property, lang, value) in refinements {
+            let collection = collections
+                .iter_mut()
+                .find(|(id, _, _)| id.as_deref() == Some(target.as_str()))
+                .map(|(_, _, collection)| collection);
+            match property.as_str() {
+                "collection-type" => {
+                    if let Some(collection) = collection {
+                        collection.collection_type = Some(value);
+                    }
+                }
+                "group-position" => {
+                    if let Some(collection) = collection {
+                        collection.position = value.parse().ok();
+                    }
+                }
+                "alternate-script" => {
+                    if let Some(creator) = metadata
+                        .creator_details
+                        .iter_mut()
+                        .find(|c| c.id.as_deref() == Some(target.as_str()))
+                    {
+                        creator.alternates.push((lang.unwrap_or_default(), value));
+                    }
+                }
+                "role" | "file-as" => {
+                    if let Some(creator) = metadata
+                        .creator_details
+                        .iter_mut()
+                        .find(|c| c.id.as_deref() == Some(target.as_str()))
+                    {
+                        let field = if property == "role" {
+                            &mut creator.role
+                        } else {
+                            &mut creator.file_as
+                        };
+                        field.get_or_insert(value);
+                    }
+                }
+                "primary" if !matches!(value.as_str(), "false" | "no" | "0") => {
+                    if let Some((_, language)) = language_ids.iter().find(|(id, _)| *id == target) {
+                        metadata
+                            .primary_language
+                            .get_or_insert_with(|| language.clone());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // A collection refining another is the one that contains it
+        for index in 0..collections.len() {
+            let Some(child) = collections[index].1.clone() else {
+                continue;
+            };
+            if let Some((_, _, collection)) = collections
+                .iter_mut()
+                .find(|(id, _, _)| id.as_deref() == Some(child.as_str()))
+            {
+                collection.parent.get_or_insert(index);
+            }
+        }
+        metadata.collections = collections
+            .into_iter()
+            .map(|(_, _, collection)| collection)
+            .collect();
+
         Ok(metadata)
     }
 
@@ -219,7 +788,7 @@ impl OpfParser {
         loop {
             match self.reader.read_event_into(&mut buf) {
                 Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e)) => {
-                    let tag_name = String::from_utf8_lossy(e.name().as_ref()).to_lowercase();
+                    let tag_name = String::from_utf8_lossy(e.local_name().as_ref()).to_lowercase();
 
                     if tag_name == "spine" {
                         in_spine = true;
@@ -234,13 +803,13 @@ impl OpfParser {
                     }
                 }
                 Ok(Event::End(ref e)) => {
-                    let tag_name = String::from_utf8_lossy(e.name().as_ref()).to_lowercase();
+                    let tag_name = String::from_utf8_lossy(e.local_name().as_ref()).to_lowercase();
                     if tag_name == "spine" {
                         in_spine = false;
                     }
                 }
                 Ok(Event::Eof) => break,
-                Err(e) => return Err(LexEpubError::Xml(e)),
+                Err(e) => return Err(self.xml_error(e)),
                 _ => buf.clear(),
             }
         }
@@ -248,6 +817,101 @@ impl OpfParser {
         Ok(spine)
     }
 
+    /// Metadata and manifest content that [`Self::parse_metadata`] does not
+    /// map to any field, for diagnosing missing metadata: one entry per
+    /// distinct `element <name>`, `meta property <property>`,
+    /// `meta name <name>`, `link rel <rel>`, or `media-type <type>`, in
+    /// document order.
+    pub fn parse_unhandled(&mut self, data: &[u8]) -> Result<Vec<String>> {
+        self.reader = Reader::from_reader(std::io::Cursor::new(data.to_vec()));
+        self.reader.config_mut().trim_text(true);
+
+        let mut unhandled: Vec<String> = Vec::new();
+        let mut report = |entry: String| {
+            if !unhandled.contains(&entry) {
+                unhandled.push(entry);
+            }
+        };
+        // Depth of the element inside <metadata>, 0 outside it
+        let mut metadata_depth = 0usize;
+        let mut in_manifest = false;
+        let mut buf = Vec::new();
+
+        loop {
+            let (e, is_empty) = match self.reader.read_event_into(&mut buf) {
+                Ok(Event::Start(e)) => (e.into_owned(), false),
+                Ok(Event::Empty(e)) => (e.into_owned(), true),
+                Ok(Event::End(ref e)) => {
+                    match e.local_name().as_ref() {
+                        b"metadata" => metadata_depth = 0,
+                        b"manifest" => in_manifest = false,
+                        _ if metadata_depth > 1 => metadata_depth -= 1,
+                        _ => {}
+                    }
+                    continue;
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(self.xml_error(e)),
+                _ => {
+                    buf.clear();
+                    continue;
+                }
+            };
+            let tag_name = String::from_utf8_lossy(e.local_name().as_ref()).to_lowercase();
+            let attr = |key: &[u8]| {
+                e.attributes()
+                    .flatten()
+                    .find(|a| a.key.as_ref() == key)
+                    .map(|a| String::from_utf8_lossy(&a.value).to_string())
+            };
+
+            if tag_name == "metadata" {
+                metadata_depth = 1;
+            } else if tag_name == "manifest" {
+                in_manifest = true;
+            } else if metadata_depth == 1 {
+                match tag_name.as_str() {
+                    "meta" => {
+                        if let Some(property) = attr(b"property") {
+                            if !HANDLED_META_PROPERTIES.contains(&property.as_str()) {
+                                report(format!("meta property {}", property));
+                            }
+                        } else if let Some(name) = attr(b"name") {
+                            if name != "cover" {
+                                report(format!("meta name {}", name));
+                            }
+                        }
+                    }
+                    "link" => {
+                        for rel in attr(b"rel").unwrap_or_default().split_whitespace() {
+                            if !HANDLED_LINK_RELS.contains(&rel) {
+                                report(format!("link rel {}", rel));
+                            }
+                        }
+                    }
+                    name if !HANDLED_METADATA_ELEMENTS.contains(&name) => {
+                        let qualified = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                        report(format!("element {}", qualified));
+                    }
+                    _ => {}
+                }
+                if !is_empty {
+                    metadata_depth += 1;
+                }
+            } else if metadata_depth > 1 && !is_empty {
+                metadata_depth += 1;
+            } else if in_manifest && tag_name == "item" {
+                let media_type = attr(b"media-type").unwrap_or_default();
+                if !is_handled_media_type(&media_type) {
+                    report(format!("media-type {}", media_type));
+                }
+            }
+            buf.clear();
+        }
+
+        Ok(unhandled)
+    }
+
     /// Get the cover image item ID from OPF metadata
     pub fn get_cover_image_id(&mut self, data: &[u8]) -> Result<Option<String>> {
         let metadata = self.parse_metadata(data)?;
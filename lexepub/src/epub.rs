@@ -1,9 +1,12 @@
-use crate::core::chapter::{Chapter, ChapterStream, ParsedChapter};
-use crate::core::container::ContainerParser;
-use crate::core::extractor::EpubExtractor;
-use crate::core::opf_parser::OpfParser;
-use crate::error::{LexEpubError, Result};
+use crate::core::chapter::{Chapter, ChapterStream, ParsedChapter, TextBlockKind};
+use crate::core::container::{ContainerParser, RootFile};
+use crate::core::encryption::{EncryptionInfo, EncryptionParser};
+use crate::core::extractor::{percent_decode, EpubExtractor};
+use crate::core::html_parser::{ChapterStyle, MarkdownOptions, TextExtractOptions, WordCounter};
+use crate::core::opf_parser::{OpfMetadata, OpfParser};
+use crate::error::{LexEpubError, Result, XmlContext};
 use bytes::Bytes;
+use sha1::{Digest, Sha1};
 use std::path::Path;
 
 /// Main EPUB processing struct
@@ -12,31 +15,309 @@ pub struct LexEpub {
     metadata: Option<EpubMetadata>,
     /// Cached full (AST + text) chapter extraction
     chapters: Option<Vec<ParsedChapter>>,
+    /// Individually accessed chapters, bounded by `chapter_cache_size`
+    chapter_cache: ChapterCache,
     /// Cached text-only extraction (cheaper than full AST parse)
     text_chapters: Option<Vec<String>>,
     /// Cached aggregate word count — avoids re-extracting just for counts
     cached_word_count: Option<usize>,
     /// Cached aggregate char count
     cached_char_count: Option<usize>,
+    /// Parsed `META-INF/encryption.xml` (empty when the archive has none)
+    encryption: Option<EncryptionInfo>,
+    /// Options applied to every plain-text extraction
+    text_options: TextExtractOptions,
+    /// Book-level extraction behaviour
+    options: LexEpubOptions,
+    /// Package document path and bytes: the default rendition resolved
+    /// through container.xml, until [`LexEpub::select_rendition`] replaces it
+    opf: Option<(String, Vec<u8>)>,
+    /// Parsed package document (spine, manifest, metadata)
+    package: Option<OpfMetadata>,
+    /// Book stylesheet, parsed on the first single-chapter access
+    stylesheet: Option<crate::core::css::Stylesheet>,
+}
+
+/// What to do with a chapter that failed to read or parse, as decided by a
+/// [`LexEpubOptions::on_chapter_error`] handler
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ErrorAction {
+    /// Leave the chapter out
+    Skip,
+    /// Stop extraction with [`LexEpubError::ChapterFailed`]
+    Abort,
+    /// Use this text as the chapter's content
+    Substitute(String),
+}
+
+/// Per-failure decision hook, see [`LexEpubOptions::on_chapter_error`]
+pub type ChapterErrorFn = dyn FnMut(&str, &LexEpubError) -> ErrorAction + Send;
+
+/// Shared form of a [`ChapterErrorFn`] stored in [`LexEpubOptions`]
+pub type ChapterErrorHandler = std::sync::Arc<std::sync::Mutex<Box<ChapterErrorFn>>>;
+
+/// Extraction behaviour of a [`LexEpub`], set with [`LexEpub::set_options`]
+#[derive(Clone, Default)]
+pub struct LexEpubOptions {
+    /// Stop at the first chapter that cannot be read or parsed and return
+    /// [`LexEpubError::ChapterFailed`]. Off by default: the eager extraction
+    /// paths skip failing chapters, and streams yield each failure as an
+    /// error item and carry on.
+    pub strict: bool,
+    /// Word counter behind `word_count` and the book totals. `None` counts
+    /// whitespace-separated words, which undercounts CJK text; see
+    /// [`crate::core::html_parser::cjk_word_count`] (feature `cjk`).
+    pub word_tokenizer: Option<WordCounter>,
+    /// Maximum number of parsed chapters kept in memory, evicting the least
+    /// recently accessed. `None` (the default) keeps the whole book after the
+    /// first full extraction; with a bound, full extractions are not retained
+    /// and only [`LexEpub::chapter`] results are cached. `lowmem` builds
+    /// and `low_memory` never cache parsed chapters.
+    pub chapter_cache_size: Option<usize>,
+    /// Language (BCP 47 tag) used by [`LexEpub::sentences`] and the stemming
+    /// in [`LexEpub::vocabulary`] when the book declares none in
+    /// `dc:language`. Word and CJK counting do not depend on it.
+    pub assume_language: Option<String>,
+    /// Called with the href and error of every chapter that fails to read or
+    /// parse during eager extraction; overrides `strict`. Streams do not
+    /// consult it: [`LexEpub::extract_chapters_stream`] yields each failure
+    /// to the caller instead.
+    pub on_chapter_error: Option<ChapterErrorHandler>,
+    /// Largest chapter, in uncompressed bytes, that extraction will read.
+    /// Bigger chapters fail with [`LexEpubError::ChapterError`] without being
+    /// inflated and are then handled like any other failing chapter.
+    pub max_chapter_size: Option<u64>,
+    /// Keep no parsed chapters or chapter text between calls and count words
+    /// chapter by chapter, as `lowmem` builds always do. The HTML-to-text extractor is
+    /// still chosen at compile time by the `lowmem` feature.
+    pub low_memory: bool,
+    /// Text extraction options (script/style contents, entity decoding...)
+    /// to apply with these options; `None` keeps the book's current ones.
+    /// See [`LexEpub::set_text_options`].
+    pub text_options: Option<TextExtractOptions>,
+}
+
+impl std::fmt::Debug for LexEpubOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LexEpubOptions")
+            .field("strict", &self.strict)
+            .field(
+                "word_tokenizer",
+                &self.word_tokenizer.as_ref().map(|_| "<fn>"),
+            )
+            .field("chapter_cache_size", &self.chapter_cache_size)
+            .field("assume_language", &self.assume_language)
+            .field(
+                "on_chapter_error",
+                &self.on_chapter_error.as_ref().map(|_| "<fn>"),
+            )
+            .field("max_chapter_size", &self.max_chapter_size)
+            .field("low_memory", &self.low_memory)
+            .field("text_options", &self.text_options)
+            .finish()
+    }
+}
+
+impl LexEpubOptions {
+    /// Create lenient default options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fail on the first unreadable chapter instead of skipping it
+    pub fn strict(mut self, enabled: bool) -> Self {
+        self.strict = enabled;
+        self
+    }
+
+    /// Count words with a custom tokenizer instead of whitespace separation
+    pub fn word_tokenizer(mut self, tokenizer: Box<dyn Fn(&str) -> usize + Send + Sync>) -> Self {
+        self.word_tokenizer = Some(tokenizer.into());
+        self
+    }
+
+    /// Keep at most `size` parsed chapters in memory (LRU)
+    pub fn chapter_cache_size(mut self, size: usize) -> Self {
+        self.chapter_cache_size = Some(size);
+        self
+    }
+
+    /// Fall back to `code` for sentence splitting and stemming when the book
+    /// declares no language
+    pub fn assume_language(mut self, code: impl Into<String>) -> Self {
+        self.assume_language = Some(code.into());
+        self
+    }
+
+    /// Decide per failing chapter whether to skip it, abort, or substitute
+    /// text for it
+    pub fn on_chapter_error(mut self, handler: Box<ChapterErrorFn>) -> Self {
+        self.on_chapter_error = Some(std::sync::Arc::new(std::sync::Mutex::new(handler)));
+        self
+    }
+
+    /// Fail chapters larger than `bytes` (uncompressed) instead of parsing them
+    pub fn max_chapter_size(mut self, bytes: u64) -> Self {
+        self.max_chapter_size = Some(bytes);
+        self
+    }
+
+    /// Trade speed for memory at runtime: never cache parsed chapters
+    pub fn low_memory(mut self, enabled: bool) -> Self {
+        self.low_memory = enabled;
+        self
+    }
+
+    /// Extract chapter text with `options`
+    pub fn text_options(mut self, options: TextExtractOptions) -> Self {
+        self.text_options = Some(options);
+        self
+    }
+}
+
+/// Parsed chapters keyed by spine position, most recently accessed last
+#[derive(Default)]
+struct ChapterCache {
+    entries: std::collections::VecDeque<(usize, ParsedChapter)>,
+}
+
+impl ChapterCache {
+    /// Look up a chapter and mark it most recently used
+    fn get(&mut self, index: usize) -> Option<ParsedChapter> {
+        let position = self.entries.iter().position(|(i, _)| *i == index)?;
+        let entry = self.entries.remove(position)?;
+        let chapter = entry.1.clone();
+        self.entries.push_back(entry);
+        Some(chapter)
+    }
+
+    /// Store a chapter, evicting the least recently used beyond `capacity`
+    fn insert(&mut self, index: usize, chapter: ParsedChapter, capacity: usize) {
+        self.entries.retain(|(i, _)| *i != index);
+        self.entries.push_back((index, chapter));
+        while self.entries.len() > capacity {
+            self.entries.pop_front();
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// Parsed metadata of a book opened with [`LexEpub::open_metadata_only`].
+///
+/// Holds no archive handle or extractor, so thousands can be kept around
+/// cheaply by catalog scanners. Chapter content is not available; reopen
+/// the book with [`LexEpub::open`] to extract it.
+#[derive(Clone, Debug)]
+pub struct MetadataHandle {
+    metadata: EpubMetadata,
+}
+
+impl MetadataHandle {
+    /// The parsed metadata
+    pub fn metadata(&self) -> &EpubMetadata {
+        &self.metadata
+    }
+
+    /// Take the parsed metadata
+    pub fn into_metadata(self) -> EpubMetadata {
+        self.metadata
+    }
 }
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct EpubMetadata {
     pub title: Option<String>,
+    /// All titles (main title, subtitles, collection titles...) in their
+    /// intended display order, honouring EPUB3 `display-seq` refinements
+    #[serde(default)]
+    pub title_parts: Vec<String>,
     pub version: Option<String>,
     pub authors: Vec<String>,
+    /// Authors with their refinements (alternate scripts), parallel to `authors`
+    #[serde(default)]
+    pub creators: Vec<crate::core::opf_parser::Creator>,
     pub description: Option<String>,
     pub languages: Vec<String>,
     pub subjects: Vec<String>,
     pub publisher: Option<String>,
     pub publication_date: Option<String>,
-    pub identifiers: Vec<String>,
+    /// Every `dc:date` as `(event, value)` in document order; the event is
+    /// EPUB2's `opf:event` (`publication`, `modification`, `creation`...)
+    #[serde(default)]
+    pub dates: Vec<(Option<String>, String)>,
+    /// Last-modified timestamp of the package (`dcterms:modified`). See
+    /// [`EpubMetadata::modified_date`].
+    #[serde(default)]
+    pub modified: Option<String>,
+    pub identifiers: Vec<crate::core::opf_parser::Identifier>,
     pub rights: Option<String>,
     pub contributors: Vec<String>,
+    /// Spine idrefs in reading order
     pub spine: Vec<String>,
+    /// Archive paths of the spine items in reading order, one per entry of
+    /// `spine` so indices line up; an idref with no manifest item gets an
+    /// empty string. Filled by [`LexEpub::get_metadata`].
+    #[serde(default)]
+    pub spine_hrefs: Vec<String>,
+    /// Whether the OPF declares a cover image (`cover-image` property or
+    /// `<meta name="cover">`)
     pub has_cover: bool,
     pub cover_image_format: Option<String>,
+    /// Number of spine items
     pub chapter_count: usize,
+    /// Page progression direction declared on the spine
+    #[serde(default)]
+    pub reading_direction: ReadingDirection,
+    /// Whether the book is fixed-layout (`rendition:layout` is `pre-paginated`)
+    #[serde(default)]
+    pub is_fixed_layout: bool,
+    /// License URL declared by an OPF `<link rel="cc:license">` (or a
+    /// `dcterms:license` meta). See [`EpubMetadata::license_url`].
+    #[serde(default)]
+    pub license_link: Option<String>,
+    /// Language explicitly marked primary by a refinement. See
+    /// [`EpubMetadata::primary_language`].
+    #[serde(default)]
+    pub marked_primary_language: Option<String>,
+    /// Accessibility conformance claims (`dcterms:conformsTo`), e.g.
+    /// `EPUB Accessibility 1.1 - WCAG 2.2 Level AA` or a specification URL
+    #[serde(default)]
+    pub conforms_to: Vec<String>,
+    /// Party that certified the conformance claim (`a11y:certifiedBy`)
+    #[serde(default)]
+    pub certified_by: Option<String>,
+    /// Location of the certifier's report (`a11y:certifierReport`)
+    #[serde(default)]
+    pub certifier_report: Option<String>,
+    /// Series and sets the book belongs to (`belongs-to-collection`), with
+    /// the book's position in each
+    #[serde(default)]
+    pub collections: Vec<crate::core::opf_parser::Collection>,
+    /// Print page count of the whole book (`schema:numberOfPages`)
+    #[serde(default)]
+    pub number_of_pages: Option<u32>,
+    /// Copyright date of the whole book (`dcterms:dateCopyrighted`)
+    #[serde(default)]
+    pub copyright_date: Option<String>,
+}
+
+/// Page progression direction from `<spine page-progression-direction>`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ReadingDirection {
+    /// Left to right
+    Ltr,
+    /// Right to left (manga, Arabic, Hebrew...)
+    Rtl,
+    /// Not declared; the reading system decides
+    #[default]
+    Default,
 }
 
 /// Convert OPF metadata to EPUB metadata
@@ -48,20 +329,39 @@ impl From<crate::core::opf_parser::OpfMetadata> for EpubMetadata {
             .and_then(|id| opf.manifest.get(id).map(|(_, mime)| mime.clone()));
         Self {
             title: opf.title,
+            title_parts: opf.titles,
             version: opf.version,
             authors: opf.creators,
+            creators: opf.creator_details,
             description: opf.description,
             languages: opf.languages,
             subjects: opf.subjects,
             publisher: opf.publisher,
             publication_date: opf.date,
+            dates: opf.dates,
+            modified: opf.modified,
             identifiers: opf.identifiers,
             rights: opf.rights,
             contributors: opf.contributors,
             spine: opf.spine.clone(),
+            spine_hrefs: Vec::new(),
             has_cover: opf.cover_image_id.is_some(),
             cover_image_format,
             chapter_count: opf.spine.len(),
+            reading_direction: match opf.page_progression_direction.as_deref() {
+                Some("ltr") => ReadingDirection::Ltr,
+                Some("rtl") => ReadingDirection::Rtl,
+                _ => ReadingDirection::Default,
+            },
+            is_fixed_layout: opf.rendition_layout.as_deref() == Some("pre-paginated"),
+            license_link: opf.license_link,
+            marked_primary_language: opf.primary_language,
+            conforms_to: opf.conforms_to,
+            certified_by: opf.certified_by,
+            certifier_report: opf.certifier_report,
+            collections: opf.collections,
+            number_of_pages: opf.number_of_pages,
+            copyright_date: opf.copyright_date,
         }
     }
 }
@@ -74,7 +374,337 @@ pub struct TocEntry {
     pub title: String,
 }
 
+/// One entry of the book's navigational table of contents, from
+/// [`LexEpub::table_of_contents`]
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TocNode {
+    pub label: String,
+    /// Archive path of the target file, without fragment (empty for
+    /// unlinked headings)
+    pub href: String,
+    /// Fragment identifier of the target, without the `#`
+    pub fragment: Option<String>,
+    /// Sub-entries
+    pub children: Vec<TocNode>,
+    /// NCX `playOrder`, if declared
+    pub play_order: Option<u32>,
+}
+
+/// One-glance structural health check of the archive, from
+/// [`LexEpub::archive_overview`]
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ArchiveOverview {
+    /// Entries in the ZIP central directory
+    pub entry_count: usize,
+    /// Whether a `mimetype` entry is present
+    pub has_mimetype: bool,
+    /// Whether `META-INF/container.xml` is present
+    pub has_container: bool,
+    /// Package documents declared in container.xml (rootfiles of media type
+    /// `application/oebps-package+xml`) that are present in the archive
+    pub opf_count: usize,
+    /// NCX items in the manifest
+    pub ncx_count: usize,
+    /// Navigation documents (`properties="nav"`) in the manifest
+    pub nav_count: usize,
+}
+
+/// A heading-delimited section of the book, independent of file boundaries
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Section {
+    /// Heading text (empty for content before the first heading)
+    pub title: String,
+    /// Heading level 1-6 (0 for content before the first heading)
+    pub level: u8,
+    /// Block texts under the heading, newline-separated
+    pub text: String,
+}
+
+/// An element tagged with a structural `epub:type`, from
+/// [`LexEpub::semantic_sections`]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SemanticSection {
+    /// The `epub:type` value, e.g. `bibliography` or `backmatter appendix`
+    pub epub_type: String,
+    /// Archive path of the chapter, with `#id` when the element has one
+    pub href: String,
+    /// Text of the element's first heading
+    pub title: Option<String>,
+}
+
+/// Every problem found by [`LexEpub::validate_report`], collected in one pass
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct ValidationReport {
+    /// Violations of the OCF/OPF requirements
+    pub errors: Vec<String>,
+    /// Problems a reading system can usually work around
+    pub warnings: Vec<String>,
+}
+
+impl ValidationReport {
+    /// Whether no errors were found (warnings are allowed)
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Summary for a catalog card, from [`LexEpub::info_card`]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct InfoCard {
+    pub title: Option<String>,
+    pub authors: Vec<String>,
+    /// Number of spine items
+    pub chapter_count: usize,
+    /// Uncompressed size of every archive entry combined, in bytes
+    pub total_size: u64,
+    /// Cover scaled to fit [`INFO_CARD_THUMBNAIL_DIM`] pixels (PNG, or JPEG
+    /// for JPEG covers). Only produced with the `image` feature, and `None`
+    /// when the cover cannot be decoded.
+    #[serde(default)]
+    pub cover_thumbnail: Option<Vec<u8>>,
+}
+
+/// A spine chapter that failed to read or parse, from
+/// [`LexEpub::extract_chapters_verbose`]
+#[derive(Debug)]
+pub struct ChapterError {
+    /// Spine idref
+    pub id: String,
+    /// Archive path of the chapter; empty when the manifest has no item
+    /// for the idref
+    pub href: String,
+    pub error: LexEpubError,
+}
+
+/// Word and character counts of one spine chapter, from
+/// [`LexEpub::chapter_stats`]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ChapterStats {
+    /// Archive path of the chapter
+    pub href: String,
+    pub word_count: usize,
+    pub char_count: usize,
+}
+
+impl From<&ParsedChapter> for ChapterStats {
+    fn from(chapter: &ParsedChapter) -> Self {
+        Self {
+            href: chapter.chapter_info.href.clone(),
+            word_count: chapter.word_count,
+            char_count: chapter.char_count,
+        }
+    }
+}
+
+/// Longest side, in pixels, of [`InfoCard::cover_thumbnail`]
+pub const INFO_CARD_THUMBNAIL_DIM: u32 = 160;
+
+/// Broad presentation style of a book, for choosing between a text renderer
+/// and an image pager
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ContentKind {
+    /// Ordinary reflowable text
+    Reflowable,
+    /// Fixed-layout pages that are almost all images (comics, manga)
+    FixedLayoutComic,
+    /// Fixed-layout pages mixing text and artwork (picture books, magazines)
+    FixedLayoutIllustrated,
+    /// Reflowable package whose pages carry images but no real text
+    ImageOnly,
+}
+
+/// A word with its position in the book, for building search indexes
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Token {
+    pub text: String,
+    /// Spine position of the chapter the word came from
+    pub chapter_index: usize,
+    /// Offset in chars into that chapter's extracted text
+    pub char_offset: usize,
+}
+
+/// A run of whole paragraphs sized for embedding or LLM ingestion, from
+/// [`LexEpub::chunks`]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Chunk {
+    /// The chunk's slice of the chapter text, paragraph breaks included
+    pub text: String,
+    /// Spine position of the chapter the chunk came from
+    pub chapter_index: usize,
+    /// Offset in chars into that chapter's text blocks joined by line breaks
+    pub start_offset: usize,
+    /// Words in `text`, as [`LexEpub::tokens`] splits them
+    pub word_count: usize,
+}
+
+/// One match from [`LexEpub::search`]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SearchHit {
+    /// Spine position of the chapter the match is in
+    pub chapter_index: usize,
+    /// Archive path of that chapter
+    pub href: String,
+    /// Offset in chars into the chapter's extracted text
+    pub char_offset: usize,
+    /// The match with up to [`SEARCH_SNIPPET_CONTEXT`] chars either side,
+    /// line breaks replaced by spaces
+    pub snippet: String,
+}
+
+/// Chars of context kept on each side of a match in [`SearchHit::snippet`]
+pub const SEARCH_SNIPPET_CONTEXT: usize = 40;
+
+/// How [`LexEpub::vocabulary`] decides whether two tokens are the same word
+#[derive(Clone, Debug, Default)]
+pub struct VocabOptions {
+    /// Treat `The` and `the` as one word
+    pub case_fold: bool,
+    /// Reduce words to their stem (`running`, `runs` -> `run`) with a
+    /// Snowball stemmer for the book's primary language (English when it
+    /// has no stemmer or declares none)
+    #[cfg(feature = "stemming")]
+    pub stem: bool,
+}
+
+impl VocabOptions {
+    /// Count exact, case-sensitive word forms
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable or disable case folding
+    pub fn case_fold(mut self, enabled: bool) -> Self {
+        self.case_fold = enabled;
+        self
+    }
+
+    /// Enable or disable stemming
+    #[cfg(feature = "stemming")]
+    pub fn stem(mut self, enabled: bool) -> Self {
+        self.stem = enabled;
+        self
+    }
+}
+
+/// Vocabulary size of a book, from [`LexEpub::vocabulary`]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct VocabStats {
+    /// Words in the whole book, repeats included
+    pub total_tokens: usize,
+    /// Distinct words in the whole book
+    pub unique_tokens: usize,
+    /// Distinct words in each chapter, in spine order
+    pub per_chapter_unique: Vec<usize>,
+}
+
+/// A whole parsed book, from [`LexEpub::to_json`], for caching a book
+/// without keeping (or re-unzipping) the archive
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct SerializedBook {
+    pub metadata: EpubMetadata,
+    /// Spine chapters in reading order
+    pub chapters: Vec<SerializedChapter>,
+}
+
+impl SerializedBook {
+    /// Reload a book written by [`LexEpub::to_json`]
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+/// One chapter of a [`SerializedBook`]. The raw chapter bytes are left out.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct SerializedChapter {
+    /// Archive path of the chapter
+    pub href: String,
+    /// Spine idref
+    pub id: String,
+    pub title: Option<String>,
+    pub content: String,
+    pub word_count: usize,
+    pub char_count: usize,
+    /// `None` for spine items that are not markup
+    #[serde(default)]
+    pub ast: Option<crate::core::chapter::AstNode>,
+}
+
+impl From<ParsedChapter> for SerializedChapter {
+    fn from(chapter: ParsedChapter) -> Self {
+        Self {
+            href: chapter.chapter_info.href,
+            id: chapter.chapter_info.id,
+            title: chapter.title,
+            content: chapter.content,
+            word_count: chapter.word_count,
+            char_count: chapter.char_count,
+            ast: chapter.ast,
+        }
+    }
+}
+
 impl EpubMetadata {
+    /// The book's primary language: the one a refinement marks as primary,
+    /// otherwise the first `dc:language` listed (the conventional primary).
+    pub fn primary_language(&self) -> Option<&str> {
+        self.marked_primary_language
+            .as_deref()
+            .or_else(|| self.languages.first().map(String::as_str))
+    }
+
+    /// License URL for the book: the OPF license link if there is one,
+    /// otherwise the first `http(s)` URL mentioned in `dc:rights` (e.g. a
+    /// Creative Commons deed).
+    pub fn license_url(&self) -> Option<String> {
+        self.license_link
+            .clone()
+            .or_else(|| self.rights.as_deref().and_then(find_url))
+    }
+
+    /// When the book was last modified: the `dc:date` whose event is
+    /// `modification` (the last one if several), otherwise `dcterms:modified`.
+    pub fn modified_date(&self) -> Option<&str> {
+        self.dates
+            .iter()
+            .rev()
+            .find(|(event, _)| {
+                event
+                    .as_deref()
+                    .is_some_and(|event| event.eq_ignore_ascii_case("modification"))
+            })
+            .map(|(_, date)| date.as_str())
+            .or(self.modified.as_deref())
+    }
+
+    /// The first ISBN-scheme identifier, without any `urn:isbn:` prefix
+    pub fn isbn(&self) -> Option<&str> {
+        let value = self
+            .identifiers
+            .iter()
+            .find(|id| id.is_isbn())?
+            .value
+            .trim();
+        Some(
+            value
+                .get(..9)
+                .filter(|prefix| prefix.eq_ignore_ascii_case("urn:isbn:"))
+                .map_or(value, |_| &value[9..]),
+        )
+    }
+
+    /// Major EPUB version from the package `version` attribute (`2` for
+    /// "2.0", `3` for "3.0"), for branching between NCX and nav documents.
+    /// `None` when the attribute is missing or not a version number.
+    pub fn epub_version_major(&self) -> Option<u8> {
+        self.version
+            .as_deref()?
+            .trim()
+            .split('.')
+            .next()?
+            .parse()
+            .ok()
+    }
+
     /// Validates the metadata per EPUB standards (requires title, language, and identifier)
     pub fn validate(&self) -> std::result::Result<(), Vec<String>> {
         let mut errors = Vec::new();
@@ -132,8 +762,738 @@ impl LexEpub {
             .collect()
     }
 
-    pub async fn read_resource(&self, path: &str) -> Result<Vec<u8>> {
-        self.extractor.read_file(path).await
+    /// HTML of the book's table of contents: the nav document's `toc` section
+    /// (EPUB3), or an `<ol>` generated from the NCX (EPUB2). Link hrefs are
+    /// resolved to archive paths (fragments kept), matching the links in
+    /// extracted ASTs. `None` when the book has neither.
+    pub async fn toc_html(&mut self) -> Result<Option<String>> {
+        let (opf_path, metadata) = self.package().await?;
+
+        let nav_path = metadata
+            .manifest_properties
+            .iter()
+            .find(|(_, props)| props.split_whitespace().any(|p| p == "nav"))
+            .and_then(|(id, _)| manifest_item_path(&opf_path, &metadata, id));
+        if let Some(nav_path) = nav_path {
+            if let Ok(nav) = self.extractor.read_file(&nav_path).await {
+                let toc = crate::core::html_parser::nav_toc_html(
+                    &String::from_utf8_lossy(&nav),
+                    |href| resolve_href_against(&nav_path, href),
+                )?;
+                if toc.is_some() {
+                    return Ok(toc);
+                }
+            }
+        }
+
+        let ncx_path = metadata
+            .manifest
+            .iter()
+            .find(|(_, (_, media_type))| media_type == "application/x-dtbncx+xml")
+            .and_then(|(id, _)| manifest_item_path(&opf_path, &metadata, id));
+        let Some(ncx_path) = ncx_path else {
+            return Ok(None);
+        };
+        let Ok(ncx) = self.extractor.read_file(&ncx_path).await else {
+            return Ok(None);
+        };
+        let points = crate::core::ncx::parse_ncx(&ncx).in_file(&ncx_path)?;
+        if points.is_empty() {
+            return Ok(None);
+        }
+        let mut html = String::new();
+        nav_points_html(&points, &ncx_path, &mut html);
+        Ok(Some(html))
+    }
+
+    /// The book's nested table of contents, from the EPUB2 NCX (named by the
+    /// spine's `toc` attribute, or found by media type) or, failing that,
+    /// the EPUB3 navigation document's `toc` nav. Hrefs are resolved to
+    /// archive paths. Empty when the book has neither.
+    pub async fn table_of_contents(&mut self) -> Result<Vec<TocNode>> {
+        let (opf_path, metadata) = self.package().await?;
+
+        let ncx_id = metadata
+            .toc_id
+            .clone()
+            .filter(|id| metadata.manifest.contains_key(id))
+            .or_else(|| {
+                metadata
+                    .manifest
+                    .iter()
+                    .find(|(_, (_, media_type))| media_type == "application/x-dtbncx+xml")
+                    .map(|(id, _)| id.clone())
+            });
+        if let Some(ncx_path) = ncx_id.and_then(|id| manifest_item_path(&opf_path, &metadata, &id))
+        {
+            if let Ok(ncx) = self.extractor.read_file(&ncx_path).await {
+                let points = crate::core::ncx::parse_ncx(&ncx).in_file(&ncx_path)?;
+                if !points.is_empty() {
+                    return Ok(toc_nodes(&points, &ncx_path));
+                }
+            }
+        }
+
+        let nav_path = metadata
+            .manifest_properties
+            .iter()
+            .find(|(_, props)| props.split_whitespace().any(|p| p == "nav"))
+            .and_then(|(id, _)| manifest_item_path(&opf_path, &metadata, id));
+        if let Some(nav_path) = nav_path {
+            if let Ok(nav) = self.extractor.read_file(&nav_path).await {
+                let points =
+                    crate::core::html_parser::nav_toc_points(&String::from_utf8_lossy(&nav))?;
+                if let Some(points) = points {
+                    return Ok(toc_nodes(&points, &nav_path));
+                }
+            }
+        }
+        Ok(Vec::new())
+    }
+
+    /// Collapse the whole book into heading-delimited sections, ignoring
+    /// which spine file each block came from. Useful for summarization where
+    /// the heading hierarchy, not the file layout, defines the structure.
+    pub async fn sections(&mut self) -> Result<Vec<Section>> {
+        let mut sections = Vec::new();
+        let mut current = Section {
+            title: String::new(),
+            level: 0,
+            text: String::new(),
+        };
+
+        for (_, path) in self.spine_entries().await? {
+            let content = match self.read_chapter_file(&path).await {
+                Ok(content) => content,
+                Err(_) => continue,
+            };
+            let html = String::from_utf8_lossy(&content);
+            for block in crate::core::html_parser::extract_text_blocks(&html)? {
+                if let TextBlockKind::Heading(level) = block.kind {
+                    if !current.title.is_empty() || !current.text.is_empty() {
+                        sections.push(current);
+                    }
+                    current = Section {
+                        title: block.text,
+                        level,
+                        text: String::new(),
+                    };
+                } else {
+                    if !current.text.is_empty() {
+                        current.text.push('\n');
+                    }
+                    current.text.push_str(&block.text);
+                }
+            }
+        }
+
+        if !current.title.is_empty() || !current.text.is_empty() {
+            sections.push(current);
+        }
+        Ok(sections)
+    }
+
+    /// Every element with an `epub:type` across the spine (parts, chapters,
+    /// bibliographies, glossaries, figures, tables...), in reading order.
+    /// Unreadable chapters are skipped.
+    pub async fn semantic_sections(&mut self) -> Result<Vec<SemanticSection>> {
+        let mut sections = Vec::new();
+        for (_, path) in self.spine_entries().await? {
+            let Ok(content) = self.read_chapter_file(&path).await else {
+                continue;
+            };
+            let html = String::from_utf8_lossy(&content);
+            for element in crate::core::html_parser::semantic_elements(&html)? {
+                let href = match element.id {
+                    Some(id) => format!("{}#{}", path, id),
+                    None => path.clone(),
+                };
+                sections.push(SemanticSection {
+                    epub_type: element.epub_type,
+                    href,
+                    title: element.title,
+                });
+            }
+        }
+        Ok(sections)
+    }
+
+    /// Page progression direction declared by the spine
+    pub async fn reading_direction(&mut self) -> Result<ReadingDirection> {
+        Ok(self.get_metadata().await?.reading_direction)
+    }
+
+    /// Whether the book declares a fixed (pre-paginated) layout
+    pub async fn is_fixed_layout(&mut self) -> Result<bool> {
+        Ok(self.get_metadata().await?.is_fixed_layout)
+    }
+
+    /// Spine indices in the order a two-page-spread renderer lays them out
+    /// left to right.
+    ///
+    /// For right-to-left fixed-layout books each consecutive pair of pages
+    /// is swapped, so the first page of a spread sits on the right. All other
+    /// books are returned in spine order. This is presentation only: logical
+    /// reading order (the spine, chapter indices) is unchanged.
+    pub async fn spine_display_order(&mut self) -> Result<Vec<usize>> {
+        let metadata = self.get_metadata().await?;
+        let mut order: Vec<usize> = (0..metadata.spine.len()).collect();
+        if metadata.is_fixed_layout && metadata.reading_direction == ReadingDirection::Rtl {
+            for spread in order.chunks_mut(2) {
+                spread.reverse();
+            }
+        }
+        Ok(order)
+    }
+
+    /// Classify the book as reflowable text, a fixed-layout comic, an
+    /// illustrated fixed-layout book, or image-only pages.
+    ///
+    /// Heuristic: a spine page counts as image-only when it contains image
+    /// markup and fewer than 40 characters of body text. Fixed-layout books
+    /// are comics when at least 80% of pages are image-only (50% for
+    /// right-to-left books, where manga is the norm); reflowable books are
+    /// image-only when that holds and there is almost no text overall.
+    pub async fn content_kind(&mut self) -> Result<ContentKind> {
+        const PAGE_TEXT_THRESHOLD: usize = 40;
+
+        let metadata = self.get_metadata().await?;
+        let mut pages = 0;
+        let mut image_only_pages = 0;
+        let mut total_text = 0;
+        for (_, path) in self.spine_entries().await? {
+            let Ok(data) = self.read_chapter_file(&path).await else {
+                continue;
+            };
+            let html = String::from_utf8_lossy(&data);
+            let text_len: usize = crate::core::html_parser::extract_text_blocks(&html)?
+                .iter()
+                .map(|block| block.text.chars().filter(|c| !c.is_whitespace()).count())
+                .sum();
+            pages += 1;
+            total_text += text_len;
+            if text_len < PAGE_TEXT_THRESHOLD && crate::core::html_parser::has_image_tag(&html) {
+                image_only_pages += 1;
+            }
+        }
+
+        let image_ratio = if pages == 0 {
+            0.0
+        } else {
+            image_only_pages as f32 / pages as f32
+        };
+        let comic_ratio = if metadata.reading_direction == ReadingDirection::Rtl {
+            0.5
+        } else {
+            0.8
+        };
+
+        Ok(if metadata.is_fixed_layout {
+            if image_ratio >= comic_ratio {
+                ContentKind::FixedLayoutComic
+            } else {
+                ContentKind::FixedLayoutIllustrated
+            }
+        } else if image_ratio >= 0.8 && total_text < PAGE_TEXT_THRESHOLD * pages.max(1) {
+            ContentKind::ImageOnly
+        } else {
+            ContentKind::Reflowable
+        })
+    }
+
+    /// Spine index where the body matter begins, for "Start reading".
+    ///
+    /// Uses the nav document's `bodymatter` landmark (or an EPUB2 guide
+    /// reference of type `text`). Without one, the first substantial
+    /// chapter is picked: not the nav document, not a cover, TOC, title or
+    /// copyright page by name, and with at least 100 characters of text.
+    /// Falls back to 0.
+    pub async fn reading_start(&mut self) -> Result<usize> {
+        let (opf_path, metadata) = self.package().await?;
+
+        let landmark = self
+            .landmark_href(&opf_path, &metadata, "bodymatter", "text")
+            .await?;
+        if let Some(href) = landmark {
+            if let Some(index) = self.spine_index_of_href(&href).await? {
+                return Ok(index);
+            }
+        }
+
+        const FRONT_MATTER_NAMES: [&str; 7] = [
+            "cover",
+            "coverpage",
+            "toc",
+            "nav",
+            "title",
+            "titlepage",
+            "copyright",
+        ];
+        for (index, (id, path)) in self.spine_entries().await?.into_iter().enumerate() {
+            let name = format!("{} {}", id, path).to_ascii_lowercase();
+            // Whole words only, allowing a numbered `cover1` or `toc01`, so
+            // `navy` or `tocqueville` are not taken for front matter
+            let looks_like_front_matter = name
+                .split(|c: char| !c.is_ascii_alphanumeric())
+                .map(|word| word.trim_end_matches(|c: char| c.is_ascii_digit()))
+                .any(|word| FRONT_MATTER_NAMES.contains(&word));
+            if metadata.item_has_property(&id, "nav") || looks_like_front_matter {
+                continue;
+            }
+            let Ok(data) = self.read_chapter_file(&path).await else {
+                continue;
+            };
+            let text_len: usize =
+                crate::core::html_parser::extract_text_blocks(&String::from_utf8_lossy(&data))?
+                    .iter()
+                    .map(|block| block.text.chars().filter(|c| !c.is_whitespace()).count())
+                    .sum();
+            if text_len >= 100 {
+                return Ok(index);
+            }
+        }
+        Ok(0)
+    }
+
+    /// A short teaser for catalog previews: `dc:description` (markup
+    /// stripped) when present, otherwise the first paragraph of at least 80
+    /// characters from [`LexEpub::reading_start`] on. Cut to `max_chars` on a
+    /// word boundary with a trailing `…`. `None` when neither exists.
+    pub async fn blurb(&mut self, max_chars: usize) -> Result<Option<String>> {
+        let (_, metadata) = self.package().await?;
+        if let Some(description) = metadata.description {
+            let text = crate::core::html_parser::extract_text_content(&description)?;
+            let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !text.is_empty() {
+                return Ok(Some(truncate_words(&text, max_chars)));
+            }
+        }
+
+        let start = self.reading_start().await?;
+        for (_, path) in self.spine_entries().await?.into_iter().skip(start) {
+            let Ok(data) = self.read_chapter_file(&path).await else {
+                continue;
+            };
+            let blocks =
+                crate::core::html_parser::extract_text_blocks(&String::from_utf8_lossy(&data))?;
+            if let Some(block) = blocks.iter().find(|block| {
+                block.kind == crate::core::chapter::TextBlockKind::Paragraph
+                    && block.text.chars().count() >= 80
+            }) {
+                return Ok(Some(truncate_words(&block.text, max_chars)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Text of the copyright page, found through the `copyright-page`
+    /// landmark, the EPUB2 guide's `copyright` reference, or failing both the
+    /// first front-matter chapter (before [`LexEpub::reading_start`]) that
+    /// mentions "copyright" or carries a `©`. `None` when there is none.
+    pub async fn copyright_page(&mut self) -> Result<Option<String>> {
+        let (opf_path, metadata) = self.package().await?;
+
+        let landmark = self
+            .landmark_href(&opf_path, &metadata, "copyright-page", "copyright")
+            .await?;
+        let mut candidates: Vec<String> = landmark
+            .iter()
+            .map(|href| href.split('#').next().unwrap_or_default().to_string())
+            .collect();
+        let heuristic = candidates.is_empty();
+        if heuristic {
+            let start = self.reading_start().await?;
+            candidates = self
+                .spine_entries()
+                .await?
+                .into_iter()
+                .take(start)
+                .map(|(_, path)| path)
+                .collect();
+        }
+
+        for path in candidates {
+            let Ok(data) = self.read_chapter_file(&path).await else {
+                continue;
+            };
+            let text = crate::core::html_parser::extract_text_content_with(
+                &String::from_utf8_lossy(&data),
+                &self.text_options,
+            )?;
+            if !heuristic || text.contains('©') || text.to_lowercase().contains("copyright") {
+                return Ok(Some(text));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Archive path (with any fragment) of the nav landmark whose `epub:type`
+    /// includes `nav_type`, falling back to the EPUB2 guide reference of
+    /// `guide_type`
+    async fn landmark_href(
+        &self,
+        opf_path: &str,
+        metadata: &crate::core::opf_parser::OpfMetadata,
+        nav_type: &str,
+        guide_type: &str,
+    ) -> Result<Option<String>> {
+        let nav_path = metadata
+            .manifest_properties
+            .iter()
+            .find(|(_, props)| props.split_whitespace().any(|p| p == "nav"))
+            .and_then(|(id, _)| manifest_item_path(opf_path, metadata, id));
+        if let Some(nav_path) = nav_path {
+            if let Ok(nav) = self.extractor.read_file(&nav_path).await {
+                let landmark =
+                    crate::core::html_parser::nav_landmarks(&String::from_utf8_lossy(&nav))?
+                        .into_iter()
+                        .find(|(kind, _)| kind.split_whitespace().any(|k| k == nav_type))
+                        .map(|(_, href)| resolve_href_against(&nav_path, &href));
+                if landmark.is_some() {
+                    return Ok(landmark);
+                }
+            }
+        }
+        Ok(metadata
+            .guide
+            .iter()
+            .find(|(kind, _)| kind == guide_type)
+            .map(|(_, href)| resolve_href_against(opf_path, href)))
+    }
+
+    /// Manifest idref of the spine item at `index` (reading order position)
+    pub async fn spine_item_id(&mut self, index: usize) -> Result<Option<String>> {
+        Ok(self.get_metadata().await?.spine.get(index).cloned())
+    }
+
+    /// Spine position of a manifest idref
+    pub async fn spine_index_of_id(&mut self, id: &str) -> Result<Option<usize>> {
+        Ok(self
+            .get_metadata()
+            .await?
+            .spine
+            .iter()
+            .position(|item| item == id))
+    }
+
+    /// Archive path of a manifest item, resolved against the OPF directory
+    pub async fn item_href(&mut self, id: &str) -> Result<Option<String>> {
+        let (opf_path, metadata) = self.package().await?;
+        Ok(manifest_item_path(&opf_path, &metadata, id))
+    }
+
+    /// Spine position of an archive path. A trailing `#fragment` is ignored,
+    /// as are `./` segments and percent-encoding (`ch%201.xhtml`).
+    pub async fn spine_index_of_href(&mut self, href: &str) -> Result<Option<usize>> {
+        let target =
+            normalize_internal_path(&percent_decode(href.split('#').next().unwrap_or(href)));
+        let (opf_path, metadata) = self.package().await?;
+        Ok(metadata.spine.iter().position(|id| {
+            manifest_item_path(&opf_path, &metadata, id)
+                .is_some_and(|path| percent_decode(&path) == target)
+        }))
+    }
+
+    /// The spine chapter an archive path (e.g. a [`TocNode`] href, with or
+    /// without its fragment) points into, parsed as by [`Self::chapter`].
+    /// Fails with `MissingFile` when no spine item matches.
+    pub async fn chapter_by_href(&mut self, href: &str) -> Result<ParsedChapter> {
+        let index = self
+            .spine_index_of_href(href)
+            .await?
+            .ok_or_else(|| LexEpubError::MissingFile(format!("No spine item for '{}'", href)))?;
+        self.chapter(index).await
+    }
+
+    /// Text of the element a link such as `OEBPS/notes.xhtml#note12` points
+    /// at, for popup footnotes and TOC previews. `href_with_fragment` is an
+    /// archive path; percent-encoding and `./` segments are resolved as for
+    /// [`Self::spine_index_of_href`]. Without a fragment the whole document's
+    /// text is returned. Fails with `MissingFile` when the document or the
+    /// fragment's element does not exist.
+    pub async fn section_by_href(&mut self, href_with_fragment: &str) -> Result<String> {
+        let (path, fragment) = match href_with_fragment.split_once('#') {
+            Some((path, fragment)) => (path, Some(percent_decode(fragment))),
+            None => (href_with_fragment, None),
+        };
+        let path = normalize_internal_path(&percent_decode(path));
+        let html = self.read_chapter_file(&path).await?;
+        let html = String::from_utf8_lossy(&html);
+
+        match fragment.filter(|fragment| !fragment.is_empty()) {
+            None => crate::core::html_parser::extract_text_content_with(&html, &self.text_options),
+            Some(fragment) => {
+                crate::core::html_parser::element_text_by_id(&html, &fragment, &self.text_options)?
+                    .ok_or_else(|| {
+                        LexEpubError::MissingFile(format!(
+                            "No element with id '{}' in '{}'",
+                            fragment, path
+                        ))
+                    })
+            }
+        }
+    }
+
+    /// Stable content hash (hex SHA-1 of the decompressed bytes) for each
+    /// spine chapter, in reading order. Comparing against the fingerprints of
+    /// an earlier version of the book shows which chapters changed.
+    pub async fn chapter_fingerprints(&mut self) -> Result<Vec<String>> {
+        let mut fingerprints = Vec::new();
+        for (_, path) in self.spine_entries().await? {
+            let data = self.read_chapter_file(&path).await?;
+            let digest = Sha1::digest(&data);
+            fingerprints.push(digest.iter().map(|b| format!("{:02x}", b)).collect());
+        }
+        Ok(fingerprints)
+    }
+
+    /// Pairs of spine indices `(earlier, later)` whose extracted text is
+    /// identical, compared by hash. Every spine item resolving to the same
+    /// file (e.g. all hrefs pointing at the TOC) is a telltale of a broken
+    /// conversion. Chapters without text, such as image pages, are ignored.
+    pub async fn detect_duplicate_chapters(&mut self) -> Result<Vec<(usize, usize)>> {
+        let parser = crate::core::html_parser::ChapterParser::new()
+            .text_only()
+            .text_options(self.text_options.clone());
+        let mut seen: std::collections::HashMap<Vec<u8>, Vec<usize>> =
+            std::collections::HashMap::new();
+        let mut pairs = Vec::new();
+        for (index, (item_id, path)) in self.spine_entries().await?.into_iter().enumerate() {
+            let content = self.read_chapter_file(&path).await?;
+            let text = parser
+                .parse_chapter(Chapter::new(path, item_id, content))?
+                .content;
+            if text.trim().is_empty() {
+                continue;
+            }
+            let earlier = seen
+                .entry(Sha1::digest(text.as_bytes()).to_vec())
+                .or_default();
+            pairs.extend(earlier.iter().map(|&first| (first, index)));
+            earlier.push(index);
+        }
+        Ok(pairs)
+    }
+
+    /// Archive paths of the font files a chapter's stylesheets declare via
+    /// `@font-face { src: url(...) }`, de-duplicated in source order.
+    ///
+    /// Linked stylesheets (and their `@import`s) are resolved relative to the
+    /// chapter, ignoring `xml:base`, and font URLs relative to the stylesheet
+    /// declaring them.
+    /// Remote and `data:` sources are skipped. Use [`LexEpub::serve`] to get
+    /// the font bytes, de-obfuscated if the book obfuscates them.
+    pub async fn chapter_fonts(&mut self, index: usize) -> Result<Vec<String>> {
+        let chapter_path = self
+            .spine_entries()
+            .await?
+            .into_iter()
+            .nth(index)
+            .map(|(_, path)| path)
+            .ok_or_else(|| LexEpubError::ChapterError("Chapter index out of bounds".to_string()))?;
+        let html = self.read_chapter_file(&chapter_path).await?;
+        let html = String::from_utf8_lossy(&html);
+
+        // (path the stylesheet's URLs are relative to, css text); imports are
+        // appended as they are discovered
+        let mut visited = std::collections::HashSet::new();
+        let mut sheets = Vec::new();
+        for style in crate::core::html_parser::chapter_styles(&html)? {
+            match style {
+                ChapterStyle::Linked(href) => {
+                    let css_path = resolve_href_against(&chapter_path, &href);
+                    if let Some(css) = self.read_stylesheet(&css_path, &mut visited).await {
+                        sheets.push((css_path, css));
+                    }
+                }
+                ChapterStyle::Inline(css) => sheets.push((chapter_path.clone(), css)),
+            }
+        }
+
+        let mut fonts: Vec<String> = Vec::new();
+        let mut next = 0;
+        while next < sheets.len() {
+            let base = sheets[next].0.clone();
+            let stylesheet = crate::core::css::Stylesheet::parse(&sheets[next].1);
+            next += 1;
+
+            for import in stylesheet.import_urls() {
+                let import_path = resolve_href_against(&base, &import);
+                if let Some(css) = self.read_stylesheet(&import_path, &mut visited).await {
+                    sheets.push((import_path, css));
+                }
+            }
+
+            for url in stylesheet.font_face_urls() {
+                let font_path = resolve_href_against(&base, &url);
+                let font_path = font_path.split('#').next().unwrap_or_default().to_string();
+                if !is_external_url(&font_path) && !fonts.contains(&font_path) {
+                    fonts.push(font_path);
+                }
+            }
+        }
+        Ok(fonts)
+    }
+
+    /// Detected encoding and confidence for each spine chapter, as
+    /// `(href, encoding, confidence)`. Low confidence marks chapters that
+    /// are likely mis-decoded.
+    pub async fn encoding_report(&mut self) -> Result<Vec<(String, String, f32)>> {
+        let mut report = Vec::new();
+        for (_, path) in self.spine_entries().await? {
+            let data = self.read_chapter_file(&path).await?;
+            let guess = crate::core::encoding::detect_encoding(&data);
+            report.push((path, guess.encoding, guess.confidence));
+        }
+        Ok(report)
+    }
+
+    /// Read the `index`-th ZIP entry directly, returning its name and bytes.
+    /// Cheaper than name lookups when iterating every entry in the archive.
+    pub async fn read_entry_by_index(&mut self, index: usize) -> Result<(String, Vec<u8>)> {
+        self.extractor.read_entry_by_index(index).await
+    }
+
+    /// `(archive path, uncompressed bytes)` of every ZIP entry, read from the
+    /// central directory without decompressing anything
+    pub async fn size_breakdown(&mut self) -> Result<Vec<(String, u64)>> {
+        self.extractor.entry_sizes().await
+    }
+
+    /// Entry counts and the presence of the files every EPUB needs, for a
+    /// diagnostics panel. Reads the central directory and the OPF manifest
+    /// only; an unreadable OPF leaves the manifest counts at zero instead of
+    /// failing.
+    pub async fn archive_overview(&mut self) -> Result<ArchiveOverview> {
+        let names = self.extractor.entry_names().await?;
+        let mut overview = ArchiveOverview {
+            entry_count: names.len(),
+            has_mimetype: names.iter().any(|name| name == "mimetype"),
+            has_container: names.iter().any(|name| name == "META-INF/container.xml"),
+            ..ArchiveOverview::default()
+        };
+        if let Ok(rootfiles) = self.renditions().await {
+            overview.opf_count = rootfiles
+                .iter()
+                .filter(|rootfile| rootfile.is_package() && names.contains(&rootfile.full_path))
+                .count();
+        }
+
+        if let Ok((_, metadata)) = self.package().await {
+            overview.ncx_count = metadata
+                .manifest
+                .values()
+                .filter(|(_, media_type)| media_type == "application/x-dtbncx+xml")
+                .count();
+            overview.nav_count = metadata
+                .manifest
+                .keys()
+                .filter(|id| metadata.item_has_property(id, "nav"))
+                .count();
+        }
+        Ok(overview)
+    }
+
+    /// Everything a catalog card shows, cheaply: title, authors, chapter
+    /// count, total size, and (with the `image` feature) a small cover
+    /// thumbnail. Only container.xml, the OPF, the central directory, and the
+    /// cover entry are read; chapter bodies are not.
+    pub async fn info_card(&mut self) -> Result<InfoCard> {
+        let metadata = self.get_metadata().await?;
+        let total_size = self
+            .size_breakdown()
+            .await?
+            .iter()
+            .map(|(_, size)| size)
+            .sum();
+
+        // A cover the image decoder cannot handle leaves the card without a
+        // thumbnail rather than failing it
+        #[cfg(feature = "image")]
+        let cover_thumbnail = self
+            .cover_thumbnail(INFO_CARD_THUMBNAIL_DIM)
+            .await
+            .ok()
+            .flatten();
+        #[cfg(not(feature = "image"))]
+        let cover_thumbnail = None;
+
+        Ok(InfoCard {
+            title: metadata.title,
+            authors: metadata.authors,
+            chapter_count: metadata.chapter_count,
+            total_size,
+            cover_thumbnail,
+        })
+    }
+
+    pub async fn read_resource(&self, path: &str) -> Result<Vec<u8>> {
+        self.extractor.read_file(path).await
+    }
+
+    /// Bytes of the manifest item `id`, such as a font or stylesheet, with
+    /// obfuscated fonts de-obfuscated as by [`Self::serve`]. Fails with
+    /// `MissingFile` naming the id when the manifest has no such item.
+    pub async fn resource_by_id(&mut self, id: &str) -> Result<Vec<u8>> {
+        let path = self.item_href(id).await?.ok_or_else(|| {
+            LexEpubError::MissingFile(format!("No manifest item with id '{}'", id))
+        })?;
+        self.resource_by_href(&path).await
+    }
+
+    /// Bytes of the archive resource at `href`, an archive path. A trailing
+    /// `#fragment`, `./`/`../` segments and percent-encoding are resolved as
+    /// for [`Self::spine_index_of_href`], and obfuscated fonts are returned
+    /// de-obfuscated as by [`Self::serve`]. Fails with `MissingFile` naming
+    /// `href` when the archive has no such entry.
+    pub async fn resource_by_href(&mut self, href: &str) -> Result<Vec<u8>> {
+        let path = normalize_internal_path(&percent_decode(href.split('#').next().unwrap_or(href)));
+        let mut bytes = self
+            .extractor
+            .read_file(&path)
+            .await
+            .map_err(|error| match error {
+                LexEpubError::MissingFile(_) => {
+                    LexEpubError::MissingFile(format!("Resource '{}' not found in EPUB", href))
+                }
+                other => other,
+            })?;
+        self.deobfuscate_resource(&path, &mut bytes).await?;
+        Ok(bytes)
+    }
+
+    /// Read any archive resource together with its media type, as a
+    /// reader's internal resource handler needs.
+    ///
+    /// The media type comes from the manifest when the resource is declared
+    /// there, otherwise it is guessed from the file extension. Obfuscated
+    /// fonts are returned de-obfuscated.
+    pub async fn serve(&mut self, href: &str) -> Result<(Vec<u8>, String)> {
+        let path = normalize_internal_path(&percent_decode(href.split('#').next().unwrap_or(href)));
+        let (opf_path, metadata) = self.package().await?;
+
+        let opf_base = std::path::Path::new(&opf_path)
+            .parent()
+            .unwrap_or(std::path::Path::new(""));
+        let media_type = metadata
+            .manifest
+            .values()
+            .find(|(item_href, _)| manifest_href_path(opf_base, item_href) == path)
+            .map(|(_, media_type)| media_type.clone())
+            .filter(|media_type| !media_type.is_empty())
+            .unwrap_or_else(|| {
+                // The package document is identified by container.xml, not
+                // by an `.opf` extension
+                if path == normalize_internal_path(&opf_path) {
+                    crate::core::container::PACKAGE_MEDIA_TYPE.to_string()
+                } else {
+                    guess_media_type(&path).to_string()
+                }
+            });
+
+        let mut bytes = self.extractor.read_file(&path).await?;
+        self.deobfuscate_resource(&path, &mut bytes).await?;
+        Ok((bytes, media_type))
     }
 
     pub async fn resolve_chapter_resource_path(
@@ -217,6 +1577,15 @@ impl LexEpub {
         Ok(Self::with_extractor(extractor))
     }
 
+    /// Read only container.xml and the OPF, returning a lightweight handle
+    /// with the parsed metadata. The archive is closed before this returns,
+    /// which keeps bulk scans of many books cheap on file handles and memory.
+    pub async fn open_metadata_only<P: AsRef<Path>>(path: P) -> Result<MetadataHandle> {
+        let mut epub = Self::open(path).await?;
+        let metadata = epub.get_metadata().await?;
+        Ok(MetadataHandle { metadata })
+    }
+
     /// Synchronous wrapper for `open` (used by FFI and sync callers)
     pub fn open_sync<P: AsRef<Path>>(path: P) -> Result<Self> {
         futures::executor::block_on(LexEpub::open(path))
@@ -228,6 +1597,20 @@ impl LexEpub {
         Ok(Self::with_extractor(extractor))
     }
 
+    /// Open an EPUB from a file path with the given extraction options
+    pub async fn open_with<P: AsRef<Path>>(path: P, options: LexEpubOptions) -> Result<Self> {
+        let mut epub = Self::open(path).await?;
+        epub.set_options(options);
+        Ok(epub)
+    }
+
+    /// Create an EPUB from bytes with the given extraction options
+    pub async fn from_bytes_with(data: Bytes, options: LexEpubOptions) -> Result<Self> {
+        let mut epub = Self::from_bytes(data).await?;
+        epub.set_options(options);
+        Ok(epub)
+    }
+
     /// Create an EPUB from an async reader (streaming, does not copy the whole
     /// archive into memory). Useful for SD/LittleFS/flash-backed readers.
     pub async fn from_reader<R>(reader: R) -> Result<Self>
@@ -238,6 +1621,37 @@ impl LexEpub {
         Ok(Self::with_extractor(extractor))
     }
 
+    /// Create an EPUB from a forward-only reader, such as an HTTP body or a
+    /// pipe, that cannot seek. The ZIP central directory sits at the end of
+    /// the archive, so the whole book is read into memory first and then
+    /// opened as by [`LexEpub::from_bytes`]. Prefer [`LexEpub::from_reader`]
+    /// when the source can seek: it keeps only the entries being read.
+    pub async fn from_stream<R>(mut reader: R) -> Result<Self>
+    where
+        R: futures::AsyncRead + Unpin + Send + 'static,
+    {
+        use futures::AsyncReadExt;
+
+        let mut data = Vec::new();
+        reader
+            .read_to_end(&mut data)
+            .await
+            .map_err(LexEpubError::Io)?;
+        Self::from_bytes(Bytes::from(data)).await
+    }
+
+    /// Create an EPUB from chunks that arrive over time, such as a
+    /// progressive download feeding a channel. Chunks are buffered in memory
+    /// and reads wait for the bytes they need. The ZIP central directory sits
+    /// at the end of the archive, so opening waits for the whole stream to
+    /// finish; this saves collecting the chunks, not time to first metadata.
+    pub async fn from_chunk_stream<S>(stream: S) -> Result<Self>
+    where
+        S: futures::Stream<Item = Bytes> + Unpin + Send + 'static,
+    {
+        Self::from_reader(crate::core::chunked::ChunkedReader::new(stream)).await
+    }
+
     /// Create an EPUB from a blocking reader by wrapping it with
     /// `futures::io::AllowStdIo` (convenience for platforms with sync FS APIs).
     pub fn from_sync_reader<R>(reader: R) -> Result<Self>
@@ -255,10 +1669,128 @@ impl LexEpub {
             extractor,
             metadata: None,
             chapters: None,
+            chapter_cache: ChapterCache::default(),
             text_chapters: None,
             cached_word_count: None,
             cached_char_count: None,
+            encryption: None,
+            text_options: TextExtractOptions::default(),
+            options: LexEpubOptions::default(),
+            opf: None,
+            package: None,
+            stylesheet: None,
+        }
+    }
+
+    /// Change the book-level extraction options. Cached chapters are dropped
+    /// so the next extraction runs under the new options.
+    pub fn set_options(&mut self, options: LexEpubOptions) {
+        if let Some(ref text_options) = options.text_options {
+            self.text_options = text_options.clone();
+        }
+        self.options = options;
+        self.chapters = None;
+        self.chapter_cache.clear();
+        self.text_chapters = None;
+        self.cached_word_count = None;
+        self.cached_char_count = None;
+    }
+
+    /// The book-level extraction options in effect
+    pub fn options(&self) -> &LexEpubOptions {
+        &self.options
+    }
+
+    /// Whether parsed chapters are kept out of memory: always in `lowmem`
+    /// builds, otherwise when [`LexEpubOptions::low_memory`] is set
+    fn low_memory(&self) -> bool {
+        cfg!(feature = "lowmem") || self.options.low_memory
+    }
+
+    /// Decide what to do with a chapter that failed to read or parse: ask
+    /// the `on_chapter_error` handler if there is one, else abort in strict
+    /// mode and skip otherwise. `Ok(Some(text))` substitutes `text`.
+    fn chapter_failure(&self, href: &str, error: LexEpubError) -> Result<Option<String>> {
+        let action = match self.options.on_chapter_error {
+            Some(ref handler) => {
+                let mut handler = handler.lock().unwrap_or_else(|e| e.into_inner());
+                (*handler)(href, &error)
+            }
+            None if self.options.strict => ErrorAction::Abort,
+            None => ErrorAction::Skip,
+        };
+        match action {
+            ErrorAction::Skip => Ok(None),
+            ErrorAction::Substitute(text) => Ok(Some(text)),
+            ErrorAction::Abort => Err(LexEpubError::ChapterFailed {
+                href: href.to_string(),
+                source: Box::new(error),
+            }),
+        }
+    }
+
+    /// Read a spine chapter's bytes, failing chapters over
+    /// [`LexEpubOptions::max_chapter_size`] before they are inflated
+    async fn read_chapter_file(&self, path: &str) -> Result<Vec<u8>> {
+        crate::core::chapter::ensure_chapter_size(
+            &self.extractor,
+            path,
+            self.options.max_chapter_size,
+        )
+        .await?;
+        self.extractor.read_file(path).await
+    }
+
+    /// [`Self::chapter_failure`] for the eager extraction paths, turning
+    /// substitute text into a chapter
+    fn recover_chapter(
+        &self,
+        href: &str,
+        item_id: &str,
+        error: LexEpubError,
+    ) -> Result<Option<ParsedChapter>> {
+        let Some(content) = self.chapter_failure(href, error)? else {
+            return Ok(None);
+        };
+        Ok(Some(ParsedChapter {
+            chapter_info: Chapter::new(href.to_string(), item_id.to_string(), Vec::new()),
+            title: None,
+            word_count: crate::core::html_parser::count_words(
+                &content,
+                self.options.word_tokenizer.as_ref(),
+            ),
+            char_count: content.chars().count(),
+            content,
+            ast: None,
+            is_scripted: false,
+            dropcap: None,
+        }))
+    }
+
+    /// Change the plain-text extraction options, as reported by
+    /// [`Self::options`]. Cached chapter text and counts are dropped so the
+    /// next extraction picks up the new options.
+    pub fn set_text_options(&mut self, options: TextExtractOptions) {
+        self.options.text_options = Some(options.clone());
+        self.text_options = options;
+        self.chapters = None;
+        self.chapter_cache.clear();
+        self.text_chapters = None;
+        self.cached_word_count = None;
+        self.cached_char_count = None;
+    }
+
+    /// Borrow the cached chapter texts without cloning them. `None` until
+    /// a text or full extraction has filled the cache, and always `None` in
+    /// low-memory mode (`lowmem` builds or [`LexEpubOptions::low_memory`]),
+    /// which never fills it.
+    pub fn cached_chapter_texts(&self) -> Option<Vec<&str>> {
+        if let Some(ref chapters) = self.chapters {
+            return Some(chapters.iter().map(|c| c.content.as_str()).collect());
         }
+        self.text_chapters
+            .as_ref()
+            .map(|texts| texts.iter().map(String::as_str).collect())
     }
 
     /// Extract only text content from all chapters.
@@ -282,42 +1814,699 @@ impl LexEpub {
                 self.cached_char_count = Some(parsed.iter().map(|c| c.char_count).sum());
             }
 
+            if self.low_memory() {
+                return Ok(texts);
+            }
             self.text_chapters = Some(texts);
         }
         Ok(self.text_chapters.clone().unwrap())
     }
 
-    /// Extract chapters with AST for advanced processing
+    /// Chapter texts in reverse spine order (last chapter first), each paired
+    /// with its original spine index. Chapters that fail to extract are
+    /// skipped (or fail the call in strict mode) without shifting the
+    /// indices of the others.
+    pub async fn extract_text_reversed(&mut self) -> Result<Vec<(usize, String)>> {
+        let mut indexed: Vec<(usize, String)> = self
+            .spine_indexed_chapters()
+            .await?
+            .into_iter()
+            .map(|(index, chapter)| (index, chapter.content))
+            .collect();
+        indexed.reverse();
+        Ok(indexed)
+    }
+
+    /// Text-only parse of every chapter, each paired with its spine
+    /// position. Chapters that fail to extract are left out without shifting
+    /// the positions of the others.
+    async fn spine_indexed_chapters(&mut self) -> Result<Vec<(usize, ParsedChapter)>> {
+        let spine = self.spine_entries().await?;
+        let chapters = self.extract_chapters_text_only_internal().await?;
+
+        let mut next = 0;
+        let mut indexed = Vec::with_capacity(chapters.len());
+        for chapter in chapters {
+            let Some(offset) = spine[next..]
+                .iter()
+                .position(|(_, path)| *path == chapter.chapter_info.href)
+            else {
+                continue;
+            };
+            indexed.push((next + offset, chapter));
+            next += offset + 1;
+        }
+        Ok(indexed)
+    }
+
+    /// Every non-overlapping occurrence of `query` in the chapters' extracted
+    /// text, in reading order. The query is a plain substring; without
+    /// `case_sensitive`, letters are compared by their lowercase forms.
+    /// Fails with `InvalidArgument` for an empty query.
+    pub async fn search(&mut self, query: &str, case_sensitive: bool) -> Result<Vec<SearchHit>> {
+        if query.is_empty() {
+            return Err(LexEpubError::InvalidArgument(
+                "Search query is empty".to_string(),
+            ));
+        }
+        let query: Vec<char> = query.chars().collect();
+        self.search_with(|_, text| substring_matches(text, &query, case_sensitive))
+            .await
+    }
+
+    /// Like [`LexEpub::search`], but every non-overlapping, non-empty match
+    /// of the regular expression `pattern`. Fails with `InvalidPattern` when
+    /// the pattern does not compile.
+    #[cfg(feature = "regex")]
+    pub async fn search_regex(&mut self, pattern: &str) -> Result<Vec<SearchHit>> {
+        let regex =
+            regex::Regex::new(pattern).map_err(|e| LexEpubError::InvalidPattern(e.to_string()))?;
+        self.search_with(|content, _| {
+            // Matches come in byte order, so char offsets are counted on
+            // from the previous match
+            let (mut byte, mut char_offset) = (0, 0);
+            regex
+                .find_iter(content)
+                .filter(|found| !found.is_empty())
+                .map(|found| {
+                    char_offset += content[byte..found.start()].chars().count();
+                    byte = found.start();
+                    (char_offset, found.as_str().chars().count())
+                })
+                .collect()
+        })
+        .await
+    }
+
+    /// Run `find` over each chapter's text (as a string and as chars) and
+    /// turn the `(char offset, char length)` matches it returns into hits
+    async fn search_with(
+        &mut self,
+        find: impl Fn(&str, &[char]) -> Vec<(usize, usize)>,
+    ) -> Result<Vec<SearchHit>> {
+        let mut hits = Vec::new();
+        for (chapter_index, chapter) in self.spine_indexed_chapters().await? {
+            let text: Vec<char> = chapter.content.chars().collect();
+            for (start, len) in find(&chapter.content, &text) {
+                hits.push(SearchHit {
+                    chapter_index,
+                    href: chapter.chapter_info.href.clone(),
+                    char_offset: start,
+                    snippet: search_snippet(&text, start, len),
+                });
+            }
+        }
+        Ok(hits)
+    }
+
+    /// Extract the text of just the named spine files, in the order given,
+    /// as `(href, text)` pairs. Hrefs may be archive paths (as in
+    /// [`TocEntry::chapter_href`]) or manifest hrefs relative to the OPF.
+    ///
+    /// Fails with `MissingFile` for an href that is not in the manifest, and
+    /// with `InvalidArgument` for a manifest item that is not in the spine
+    /// (stylesheets, images, a navigation document left out of the spine).
+    pub async fn extract_hrefs(&mut self, hrefs: &[String]) -> Result<Vec<(String, String)>> {
+        let (opf_path, metadata) = self.package().await?;
+        let opf_base = std::path::Path::new(&opf_path)
+            .parent()
+            .unwrap_or(std::path::Path::new(""));
+
+        let parser = crate::core::html_parser::ChapterParser::new()
+            .text_only()
+            .text_options(self.text_options.clone())
+            .word_counter(self.options.word_tokenizer.clone());
+        let mut extracted = Vec::with_capacity(hrefs.len());
+        for href in hrefs {
+            let wanted = normalize_internal_path(href.split('#').next().unwrap_or(href));
+            let item = metadata.manifest.iter().find(|(_, (item_href, _))| {
+                let full_path = manifest_href_path(opf_base, item_href);
+                full_path == wanted || normalize_internal_path(item_href) == wanted
+            });
+            let Some((item_id, (item_href, media_type))) = item else {
+                return Err(LexEpubError::MissingFile(format!(
+                    "'{}' is not in the manifest",
+                    href
+                )));
+            };
+            if !metadata.spine.contains(item_id) {
+                return Err(LexEpubError::InvalidArgument(format!(
+                    "'{}' is not in the spine",
+                    href
+                )));
+            }
+
+            let full_path = manifest_href_path(opf_base, item_href);
+            let content = self.read_chapter_file(&full_path).await?;
+            let chapter =
+                Chapter::new(full_path, item_id.clone(), content).with_media_type(media_type);
+            let parsed = parser.parse_chapter(chapter)?;
+            extracted.push((href.clone(), parsed.content));
+        }
+        Ok(extracted)
+    }
+
+    /// Raw (X)HTML of one spine chapter. With `minify`, insignificant
+    /// whitespace is collapsed (see [`crate::core::html_parser::minify_html`])
+    /// to cut transfer size to a webview; `<pre>` content is kept exactly.
+    pub async fn chapter_html(&mut self, index: usize, minify: bool) -> Result<String> {
+        let path = self
+            .spine_entries()
+            .await?
+            .into_iter()
+            .nth(index)
+            .map(|(_, path)| path)
+            .ok_or_else(|| LexEpubError::ChapterError("Chapter index out of bounds".to_string()))?;
+        let html = self.read_chapter_file(&path).await?;
+        let html = String::from_utf8_lossy(&html);
+        Ok(if minify {
+            crate::core::html_parser::minify_html(&html)
+        } else {
+            html.into_owned()
+        })
+    }
+
+    /// One spine chapter as a self-contained HTML document for saving or
+    /// sharing: linked stylesheets are inlined into a `<style>` element in
+    /// the head, and images (`src`, or `href` on SVG `<image>`) become
+    /// base64 `data:` URIs, both resolved with any `xml:base` in scope.
+    /// `url()` references inside the CSS are left as they are; resources
+    /// that fail to read keep their original reference.
+    /// Scripts and event-handler attributes are stripped as in
+    /// [`AstNode::sanitize`](crate::core::chapter::AstNode::sanitize).
+    pub async fn chapter_standalone_html(&mut self, index: usize) -> Result<String> {
+        use crate::core::chapter::AstNode;
+        use base64::Engine;
+
+        let (id, path) = self
+            .spine_entries()
+            .await?
+            .into_iter()
+            .nth(index)
+            .ok_or_else(|| LexEpubError::ChapterError("Chapter index out of bounds".to_string()))?;
+        let data = self.read_chapter_file(&path).await?;
+        let html = String::from_utf8_lossy(&data);
+        // The XML declaration and doctype are replaced by an HTML5 doctype
+        let start = html.to_ascii_lowercase().find("<html").unwrap_or(0);
+        let parsed = crate::core::html_parser::ChapterParser::new()
+            .with_ast()
+            .parse_chapter(Chapter::new(path.clone(), id, html[start..].into()))?;
+        let mut ast = parsed.ast.unwrap_or_else(|| AstNode::Element {
+            tag: "root".to_string(),
+            attrs: std::collections::HashMap::new(),
+            styles: std::collections::HashMap::new(),
+            children: Vec::new(),
+        });
+        ast.sanitize();
+        normalize_ast_links(&mut ast, &path);
+
+        let mut stylesheets = Vec::new();
+        let mut images = Vec::new();
+        collect_standalone_refs(&ast, &mut stylesheets, &mut images);
+
+        let mut css = String::new();
+        for sheet in &stylesheets {
+            if let Ok(bytes) = self.extractor.read_file(sheet).await {
+                css.push_str(String::from_utf8_lossy(&bytes).trim());
+                css.push('\n');
+            }
+        }
+        let mut data_uris = std::collections::HashMap::new();
+        for src in images {
+            if let Ok((bytes, media_type)) = self.serve(&src).await {
+                let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+                data_uris.insert(src, format!("data:{};base64,{}", media_type, encoded));
+            }
+        }
+
+        inline_standalone_refs(&mut ast, &data_uris);
+        if !css.is_empty() {
+            let mut style = Some(AstNode::Element {
+                tag: "style".to_string(),
+                attrs: std::collections::HashMap::new(),
+                styles: std::collections::HashMap::new(),
+                children: vec![AstNode::Text { content: css }],
+            });
+            append_to_head(&mut ast, &mut style);
+            if let (Some(style), AstNode::Element { children, .. }) = (style, &mut ast) {
+                children.insert(0, style);
+            }
+        }
+        Ok(format!("<!DOCTYPE html>\n{}", ast.to_html()))
+    }
+
+    /// Render one spine chapter as Markdown (see
+    /// [`crate::core::html_parser::extract_markdown_content_with`]).
+    pub async fn chapter_markdown(
+        &mut self,
+        index: usize,
+        options: &MarkdownOptions,
+    ) -> Result<String> {
+        let path = self
+            .spine_entries()
+            .await?
+            .into_iter()
+            .nth(index)
+            .map(|(_, path)| path)
+            .ok_or_else(|| LexEpubError::ChapterError("Chapter index out of bounds".to_string()))?;
+        let html = self.read_chapter_file(&path).await?;
+        crate::core::html_parser::extract_markdown_content_with(
+            &String::from_utf8_lossy(&html),
+            options,
+        )
+    }
+
+    /// Render every spine chapter as Markdown with default
+    /// [`MarkdownOptions`], keeping heading levels, paragraph breaks, list
+    /// items, quotes, and emphasis. Unreadable chapters go through the
+    /// same skip/strict/`on_chapter_error` handling as text extraction.
+    pub async fn extract_markdown(&mut self) -> Result<Vec<String>> {
+        let options = MarkdownOptions::default();
+        let mut chapters = Vec::new();
+        for (_, path) in self.spine_entries().await? {
+            let markdown = match self.read_chapter_file(&path).await {
+                Ok(html) => crate::core::html_parser::extract_markdown_content_with(
+                    &String::from_utf8_lossy(&html),
+                    &options,
+                ),
+                Err(e) => Err(e),
+            };
+            match markdown {
+                Ok(markdown) => chapters.push(markdown),
+                Err(e) => chapters.extend(self.chapter_failure(&path, e)?),
+            }
+        }
+        Ok(chapters)
+    }
+
+    /// Text of the `epub:type="annotation"` asides in one spine chapter
+    /// (see [`crate::core::html_parser::extract_annotations`]). These are
+    /// left out of extracted text unless
+    /// [`TextExtractOptions::include_annotations`] is set.
+    pub async fn annotations(&mut self, index: usize) -> Result<Vec<String>> {
+        let path = self
+            .spine_entries()
+            .await?
+            .into_iter()
+            .nth(index)
+            .map(|(_, path)| path)
+            .ok_or_else(|| LexEpubError::ChapterError("Chapter index out of bounds".to_string()))?;
+        let html = self.read_chapter_file(&path).await?;
+        crate::core::html_parser::extract_annotations(&String::from_utf8_lossy(&html))
+    }
+
+    /// Dump the whole book as readable text: each chapter's title on its own
+    /// line, followed by the chapter text, with chapters separated by blank
+    /// lines.
+    pub async fn to_annotated_text(&mut self) -> Result<String> {
+        let toc = self.get_toc().await?;
+        let texts = self.extract_text_only().await?;
+
+        let newline = self.text_options.line_ending.as_str();
+        let mut out = String::new();
+        for (entry, text) in toc.iter().zip(texts.iter()) {
+            if !out.is_empty() {
+                out.push_str(newline);
+                out.push_str(newline);
+            }
+            out.push_str(&entry.title);
+            out.push_str(newline);
+            out.push_str(newline);
+            out.push_str(text);
+        }
+        Ok(out)
+    }
+
+    /// Every word in the book in reading order, tagged with its chapter's
+    /// spine position and char offset into that chapter's extracted text.
+    /// See [`crate::core::html_parser::word_spans`] for what counts as a
+    /// word; [`LexEpubOptions::word_tokenizer`] only counts words, so it
+    /// plays no part here. Chapters that fail to extract are left out, as in
+    /// [`LexEpub::search`].
+    pub async fn tokens(&mut self) -> Result<Vec<Token>> {
+        let chapters = self.spine_indexed_chapters().await?;
+        Ok(chapters
+            .iter()
+            .flat_map(|(chapter_index, chapter)| {
+                crate::core::html_parser::word_spans(&chapter.content)
+                    .into_iter()
+                    .map(move |(char_offset, word)| Token {
+                        text: word.to_string(),
+                        chapter_index: *chapter_index,
+                        char_offset,
+                    })
+            })
+            .collect())
+    }
+
+    /// Split every chapter into chunks of about `target_words` words for
+    /// retrieval pipelines. Whole paragraphs (the chapter's text blocks, see
+    /// [`crate::core::html_parser::extract_text_blocks`]) are packed until
+    /// the next one would exceed the target; only a paragraph longer than
+    /// the target on its own is cut mid-way. Each chunk after the first in a
+    /// chapter starts with up to `overlap_words` words from the end of the
+    /// previous one; they count towards the target, so the overlap shrinks
+    /// when the next paragraph needs the room. Words are those of
+    /// [`LexEpub::tokens`]. Chunks never span chapters.
+    pub async fn chunks(
+        &mut self,
+        target_words: usize,
+        overlap_words: usize,
+    ) -> Result<Vec<Chunk>> {
+        let mut chunks = Vec::new();
+        for (chapter_index, (_, path)) in self.spine_entries().await?.into_iter().enumerate() {
+            let text = match self.chapter_paragraphs(&path).await {
+                Ok(paragraphs) => paragraphs.join("\n"),
+                Err(error) => match self.chapter_failure(&path, error)? {
+                    Some(text) => text,
+                    None => continue,
+                },
+            };
+            chunks.extend(chapter_chunks(
+                &text,
+                chapter_index,
+                target_words.max(1),
+                overlap_words,
+            ));
+        }
+        Ok(chunks)
+    }
+
+    /// Text of every non-empty text block in a spine chapter, in order
+    async fn chapter_paragraphs(&self, path: &str) -> Result<Vec<String>> {
+        let data = self.read_chapter_file(path).await?;
+        let blocks =
+            crate::core::html_parser::extract_text_blocks(&String::from_utf8_lossy(&data))?;
+        Ok(blocks
+            .into_iter()
+            .map(|block| block.text)
+            .filter(|text| !text.trim().is_empty())
+            .collect())
+    }
+
+    /// Sentences of one chapter's extracted text, split with the rules of
+    /// the book's language (see
+    /// [`crate::core::html_parser::split_sentences`] and
+    /// [`LexEpub::text_language`]).
+    pub async fn sentences(&mut self, index: usize) -> Result<Vec<String>> {
+        let language = self.text_language().await?;
+        let texts = self.extract_text_only().await?;
+        let text = texts
+            .get(index)
+            .ok_or_else(|| LexEpubError::ChapterError("Chapter index out of bounds".to_string()))?;
+        Ok(
+            crate::core::html_parser::split_sentences(text, language.as_deref())
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+        )
+    }
+
+    /// Language used for sentence splitting and stemming: the book's primary
+    /// declared language, else [`LexEpubOptions::assume_language`]
+    pub async fn text_language(&mut self) -> Result<Option<String>> {
+        let metadata = self.get_metadata().await?;
+        Ok(metadata
+            .primary_language()
+            .map(str::to_string)
+            .or_else(|| self.options.assume_language.clone()))
+    }
+
+    /// Count total and distinct words, per chapter and for the whole book,
+    /// using the same word segmentation as [`LexEpub::tokens`].
+    pub async fn vocabulary(&mut self, opts: VocabOptions) -> Result<VocabStats> {
+        #[cfg(feature = "stemming")]
+        let stemmer = if opts.stem {
+            Some(stemmer_for(self.text_language().await?.as_deref()))
+        } else {
+            None
+        };
+        let texts = self.extract_text_only().await?;
+
+        let mut book = std::collections::HashSet::new();
+        let mut total_tokens = 0;
+        let mut per_chapter_unique = Vec::with_capacity(texts.len());
+        for text in &texts {
+            let mut chapter = std::collections::HashSet::new();
+            for (_, word) in crate::core::html_parser::word_spans(text) {
+                total_tokens += 1;
+                let word = if opts.case_fold {
+                    word.to_lowercase()
+                } else {
+                    word.to_string()
+                };
+                #[cfg(feature = "stemming")]
+                let word = match &stemmer {
+                    Some(stemmer) => stemmer.stem(&word).into_owned(),
+                    None => word,
+                };
+                chapter.insert(word);
+            }
+            per_chapter_unique.push(chapter.len());
+            book.extend(chapter);
+        }
+
+        Ok(VocabStats {
+            total_tokens,
+            unique_tokens: book.len(),
+            per_chapter_unique,
+        })
+    }
+
+    /// One spine chapter, parsed as by [`Self::extract_ast`]. Of the chapter
+    /// bodies only that chapter's file is read, so paginated readers need
+    /// not extract the whole book; the book's CSS is read and parsed on the
+    /// first call and kept. Repeated access is served from memory; with
+    /// [`LexEpubOptions::chapter_cache_size`] set, only the most recently
+    /// accessed chapters are kept. An index past the spine fails with
+    /// [`LexEpubError::ChapterError`].
+    pub async fn chapter(&mut self, index: usize) -> Result<ParsedChapter> {
+        self.ensure_not_encrypted().await?;
+        let entries = self.spine_entries().await?;
+        if let Some(ref chapters) = self.chapters {
+            // Positions only line up when no chapter was skipped
+            if chapters.len() == entries.len() {
+                if let Some(chapter) = chapters.get(index) {
+                    return Ok(chapter.clone());
+                }
+            }
+        }
+        if let Some(chapter) = self.chapter_cache.get(index) {
+            return Ok(chapter);
+        }
+
+        let (item_id, path) = entries
+            .into_iter()
+            .nth(index)
+            .ok_or_else(|| LexEpubError::ChapterError("Chapter index out of bounds".to_string()))?;
+        let (opf_path, metadata) = self.package().await?;
+
+        let media_type = metadata
+            .manifest
+            .get(&item_id)
+            .map(|(_, media_type)| media_type.as_str())
+            .unwrap_or_default();
+        let content = self.read_chapter_file(&path).await?;
+        let chapter =
+            Chapter::new(path.clone(), item_id.clone(), content).with_media_type(media_type);
+        let mut parsed = crate::core::html_parser::ChapterParser::new()
+            .with_both()
+            .text_options(self.text_options.clone())
+            .word_counter(self.options.word_tokenizer.clone())
+            .parse_chapter(chapter)?;
+        parsed.is_scripted |= metadata.item_has_property(&item_id, "scripted");
+        let stylesheet = match self.stylesheet.take() {
+            Some(stylesheet) => stylesheet,
+            None => {
+                let opf_base = Path::new(&opf_path).parent().unwrap_or(Path::new(""));
+                self.book_stylesheet(opf_base, &metadata).await
+            }
+        };
+        finish_full_chapter(&mut parsed, &path, &stylesheet);
+        self.stylesheet = Some(stylesheet);
+
+        // Low-memory mode never caches parsed chapters
+        let capacity = if self.low_memory() {
+            0
+        } else {
+            self.options.chapter_cache_size.unwrap_or(usize::MAX)
+        };
+        if capacity > 0 {
+            self.chapter_cache.insert(index, parsed.clone(), capacity);
+        }
+        Ok(parsed)
+    }
+
+    /// The chapter at spine position `index`; the same as [`Self::chapter`]
+    pub async fn chapter_at(&mut self, index: usize) -> Result<ParsedChapter> {
+        self.chapter(index).await
+    }
+
+    /// Number of parsed chapters currently held in memory
+    pub fn cached_chapter_count(&self) -> usize {
+        self.chapters.as_ref().map_or(0, Vec::len) + self.chapter_cache.len()
+    }
+
+    /// Extract chapters with AST for advanced processing. Each chapter
+    /// carries both its plain text and its styled AST; unlike
+    /// [`Self::extract_text_only`], results are never served from the
+    /// text-only cache.
     pub async fn extract_ast(&mut self) -> Result<Vec<ParsedChapter>> {
         self.extract_chapters().await
     }
 
+    /// Like [`Self::extract_ast`], but every chapter that fails to read or
+    /// parse is returned as a [`ChapterError`] next to the chapters that
+    /// succeeded, so tools can report e.g. "3 of 40 chapters failed".
+    /// `strict` and `on_chapter_error` do not apply, and nothing is cached.
+    pub async fn extract_chapters_verbose(
+        &mut self,
+    ) -> Result<(Vec<ParsedChapter>, Vec<ChapterError>)> {
+        let mut chapters = Vec::new();
+        let mut errors = Vec::new();
+        self.parse_spine_full(|_, id, href, parsed| {
+            match parsed {
+                Ok(chapter) => chapters.push(chapter),
+                Err(error) => errors.push(ChapterError { id, href, error }),
+            }
+            Ok(())
+        })
+        .await?;
+        Ok((chapters, errors))
+    }
+
+    /// The whole book as a forest: each extracted chapter's root
+    /// [`AstNode`](crate::core::chapter::AstNode), in spine order, so chapter
+    /// boundaries survive global transforms. Chapters without markup (a bare
+    /// image in the spine) contribute an empty root.
+    pub async fn book_ast(&mut self) -> Result<Vec<crate::core::chapter::AstNode>> {
+        Ok(self
+            .extract_chapters()
+            .await?
+            .into_iter()
+            .map(|chapter| {
+                chapter
+                    .ast
+                    .unwrap_or_else(|| crate::core::chapter::AstNode::Element {
+                        tag: "root".to_string(),
+                        attrs: std::collections::HashMap::new(),
+                        styles: std::collections::HashMap::new(),
+                        children: Vec::new(),
+                    })
+            })
+            .collect())
+    }
+
     /// Extract chapters as a stream for memory-efficient processing
     pub async fn extract_chapters_stream(&mut self) -> Result<ChapterStream> {
-        let (opf_path, opf_data) = self.read_opf().await?;
-        let mut opf_parser = OpfParser::new();
-        // parse_metadata() already populates spine
-        let metadata = opf_parser.parse_metadata(&opf_data)?;
+        self.ensure_not_encrypted().await?;
+        let (opf_path, metadata) = self.package().await?;
         let spine = metadata.spine.clone();
 
         // Resolve full paths for spine entries and return a streaming iterator
         let mut entries = Vec::new();
+        let mut items = Vec::new();
         let opf_base = std::path::Path::new(&opf_path)
             .parent()
             .unwrap_or(std::path::Path::new(""));
 
         for item_id in spine {
             if let Some(href) = metadata.manifest.get(&item_id) {
-                let full_path = opf_base.join(&href.0);
-                let full_path_str = full_path.to_string_lossy().to_string();
-                entries.push(full_path_str);
+                entries.push(manifest_href_path(opf_base, &href.0));
+                items.push((item_id, href.1.clone()));
+            } else {
+                // Fails in its place in the stream
+                entries.push(String::new());
+                items.push((item_id, String::new()));
             }
         }
 
-        Ok(ChapterStream::from_extractor(
-            self.extractor.clone(),
-            entries,
-        ))
+        let scripted_items = items
+            .iter()
+            .map(|(item_id, _)| item_id)
+            .filter(|item_id| metadata.item_has_property(item_id, "scripted"))
+            .cloned()
+            .collect();
+
+        Ok(
+            ChapterStream::from_extractor(self.extractor.clone(), entries)
+                .with_manifest_items(items)
+                .with_scripted_items(scripted_items)
+                .with_text_options(self.text_options.clone())
+                .with_strict(self.options.strict)
+                .with_word_counter(self.options.word_tokenizer.clone())
+                .with_max_chapter_size(self.options.max_chapter_size),
+        )
+    }
+
+    /// Like [`LexEpub::extract_chapters_stream`], but every chapter also
+    /// carries its AST, built as by [`LexEpub::extract_ast`], one chapter at
+    /// a time. Nothing is cached.
+    pub async fn extract_ast_stream(&mut self) -> Result<ChapterStream> {
+        let stream = self.extract_chapters_stream().await?;
+        let (opf_path, metadata) = self.package().await?;
+        let opf_base = std::path::Path::new(&opf_path)
+            .parent()
+            .unwrap_or(std::path::Path::new(""))
+            .to_path_buf();
+        let stylesheet = self.book_stylesheet(&opf_base, &metadata).await;
+        Ok(stream.with_ast(stylesheet))
+    }
+
+    /// The metadata and every chapter (text, counts, and AST, as from
+    /// [`LexEpub::extract_ast`]) as one JSON document; reload it with
+    /// [`SerializedBook::from_json`].
+    ///
+    /// The whole book, ASTs included, is held in memory while the document
+    /// is built, and the JSON is several times the size of the chapter
+    /// text. For large books prefer [`LexEpub::write_jsonl`], which streams
+    /// one chapter at a time.
+    pub async fn to_json(&mut self) -> Result<String> {
+        let book = SerializedBook {
+            metadata: self.get_metadata().await?,
+            chapters: self
+                .extract_ast()
+                .await?
+                .into_iter()
+                .map(SerializedChapter::from)
+                .collect(),
+        };
+        Ok(serde_json::to_string(&book)?)
+    }
+
+    /// Write the book as newline-delimited JSON, one
+    /// `{"index", "href", "title", "text"}` object per spine chapter.
+    ///
+    /// Chapters are pulled from [`LexEpub::extract_chapters_stream`] and
+    /// written as they are parsed, so memory use stays flat regardless of
+    /// book size.
+    pub async fn write_jsonl<W: futures::AsyncWrite + Unpin>(
+        &mut self,
+        mut writer: W,
+    ) -> Result<()> {
+        use futures::{AsyncWriteExt, StreamExt};
+
+        let mut stream = self.extract_chapters_stream().await?;
+        let mut index = 0;
+        while let Some(chapter) = stream.next().await {
+            let chapter = match chapter {
+                Ok(chapter) => chapter,
+                // Lenient streams report failing chapters and carry on
+                Err(_) if !self.options.strict => continue,
+                Err(e) => return Err(e),
+            };
+            let mut line = serde_json::to_vec(&serde_json::json!({
+                "index": index,
+                "href": chapter.chapter_info.href,
+                "title": chapter.title,
+                "text": chapter.content,
+            }))?;
+            line.push(b'\n');
+            writer.write_all(&line).await?;
+            index += 1;
+        }
+        writer.flush().await?;
+        Ok(())
     }
 
     /// Get metadata
@@ -329,22 +2518,357 @@ impl LexEpub {
         if let Some(ref metadata) = self.metadata {
             return Ok(metadata.clone());
         }
+        self.ensure_not_encrypted().await?;
+
+        let (opf_path, opf_metadata) = self.package().await?;
+        let spine_hrefs = spine_paths(&opf_path, &opf_metadata);
+        let mut epub_metadata: EpubMetadata = opf_metadata.into();
+        epub_metadata.spine_hrefs = spine_hrefs;
+        self.metadata = Some(epub_metadata.clone());
+        Ok(epub_metadata)
+    }
+
+    /// Every `<rootfile>` in container.xml, in document order. Books with
+    /// several renditions (e.g. a reflowable and a fixed-layout package)
+    /// list one per rendition.
+    pub async fn renditions(&mut self) -> Result<Vec<RootFile>> {
+        let container_data = self.extractor.read_file("META-INF/container.xml").await?;
+        Ok(ContainerParser::new()
+            .parse_container(&container_data)?
+            .rootfiles)
+    }
+
+    /// Read the book through the rendition at `index` in
+    /// [`LexEpub::renditions`] instead of the default (the first
+    /// `application/oebps-package+xml` rootfile). Cached metadata and
+    /// chapters are dropped. Fails with [`LexEpubError::InvalidArgument`]
+    /// for an index out of range or a rootfile that is not a package
+    /// document.
+    pub async fn select_rendition(&mut self, index: usize) -> Result<()> {
+        let rootfile = self
+            .renditions()
+            .await?
+            .into_iter()
+            .nth(index)
+            .ok_or_else(|| {
+                LexEpubError::InvalidArgument(format!("Rendition index {} out of bounds", index))
+            })?;
+        if !rootfile.is_package() {
+            return Err(LexEpubError::InvalidArgument(format!(
+                "Rendition '{}' is not a package document ({})",
+                rootfile.full_path, rootfile.media_type
+            )));
+        }
+        let opf_data = self.extractor.read_file(&rootfile.full_path).await?;
+
+        self.opf = Some((rootfile.full_path, opf_data));
+        self.package = None;
+        self.stylesheet = None;
+        self.metadata = None;
+        self.chapters = None;
+        self.chapter_cache.clear();
+        self.text_chapters = None;
+        self.cached_word_count = None;
+        self.cached_char_count = None;
+        Ok(())
+    }
+
+    /// Check the archive against the minimal OCF/OPF requirements and
+    /// collect every problem instead of stopping at the first one.
+    ///
+    /// Errors cover the `mimetype` entry, container.xml, the package
+    /// document, required metadata, and spine items that are unresolved or
+    /// missing. Warnings cover missing non-spine manifest files and broken
+    /// internal links in chapters. Links are resolved against the chapter
+    /// itself; `xml:base` is not applied. Only an unreadable archive is an
+    /// `Err`.
+    pub async fn validate_report(&mut self) -> Result<ValidationReport> {
+        let mut report = ValidationReport::default();
+        let names = self.extractor.entry_names().await?;
+        let first_entry = names.first().cloned();
+        let entries: std::collections::HashSet<String> = names
+            .iter()
+            .map(|name| normalize_internal_path(name))
+            .collect();
+
+        if !entries.contains("mimetype") {
+            report.errors.push("Missing mimetype file".to_string());
+        } else {
+            if first_entry.as_deref() != Some("mimetype") {
+                report
+                    .errors
+                    .push("mimetype is not the first entry in the archive".to_string());
+            }
+            let mimetype = self.extractor.read_file("mimetype").await?;
+            if mimetype != b"application/epub+zip" {
+                if String::from_utf8_lossy(&mimetype).trim() == "application/epub+zip" {
+                    report
+                        .warnings
+                        .push("mimetype contains surrounding whitespace".to_string());
+                } else {
+                    report.errors.push(format!(
+                        "mimetype is '{}', expected 'application/epub+zip'",
+                        String::from_utf8_lossy(&mimetype)
+                    ));
+                }
+            }
+        }
+
+        let Ok(container_data) = self.extractor.read_file("META-INF/container.xml").await else {
+            report
+                .errors
+                .push("Missing META-INF/container.xml".to_string());
+            return Ok(report);
+        };
+        let opf_path = match ContainerParser::new().parse_container(&container_data) {
+            Ok(container) => container.rootfile_path,
+            Err(e) => {
+                report.errors.push(format!("Invalid container.xml: {}", e));
+                return Ok(report);
+            }
+        };
+        let Ok(opf_data) = self.extractor.read_file(&opf_path).await else {
+            report
+                .errors
+                .push(format!("Missing package document '{}'", opf_path));
+            return Ok(report);
+        };
+        let metadata = match OpfParser::new()
+            .parse_metadata(&opf_data)
+            .in_file(&opf_path)
+        {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                report
+                    .errors
+                    .push(format!("Invalid package document '{}': {}", opf_path, e));
+                return Ok(report);
+            }
+        };
+
+        if let Err(problems) = EpubMetadata::from(metadata.clone()).validate() {
+            report.errors.extend(problems);
+        }
+
+        if metadata.spine.is_empty() {
+            report.errors.push("Spine is empty".to_string());
+        }
+        let mut chapter_paths = Vec::new();
+        for idref in &metadata.spine {
+            match manifest_item_path(&opf_path, &metadata, idref) {
+                None => report
+                    .errors
+                    .push(format!("Spine item '{}' is not in the manifest", idref)),
+                Some(path) if !entries.contains(&path) => report
+                    .errors
+                    .push(format!("Missing chapter file '{}'", path)),
+                Some(path) => chapter_paths.push(path),
+            }
+        }
+
+        let mut manifest_ids: Vec<&String> = metadata.manifest.keys().collect();
+        manifest_ids.sort();
+        for id in manifest_ids {
+            if metadata.spine.contains(id) {
+                continue;
+            }
+            if let Some(path) = manifest_item_path(&opf_path, &metadata, id) {
+                if !entries.contains(&path) {
+                    report.warnings.push(format!(
+                        "Manifest item '{}' points to missing file '{}'",
+                        id, path
+                    ));
+                }
+            }
+        }
+
+        for path in chapter_paths {
+            let html = match self.read_chapter_file(&path).await {
+                Ok(html) => html,
+                // Over the chapter size limit
+                Err(LexEpubError::ChapterError(message)) => {
+                    report.warnings.push(message);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            let Ok(references) =
+                crate::core::html_parser::resource_references(&String::from_utf8_lossy(&html))
+            else {
+                report
+                    .warnings
+                    .push(format!("Could not parse chapter '{}'", path));
+                continue;
+            };
+            for reference in references {
+                if reference.starts_with('#') || has_uri_scheme(&reference) {
+                    continue;
+                }
+                let resolved = resolve_href_against(&path, &reference);
+                // Entry names are stored decoded, as manifest hrefs are
+                let target = percent_decode(resolved.split('#').next().unwrap_or_default());
+                if !target.is_empty() && !entries.contains(target.as_str()) {
+                    report
+                        .warnings
+                        .push(format!("Broken link in '{}': '{}'", path, reference));
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Check the package structure up front, failing at the first problem:
+    /// the `mimetype` entry must read `application/epub+zip`, the package
+    /// document in use (the default rendition from `META-INF/container.xml`,
+    /// or the one picked with [`Self::select_rendition`]) must exist and
+    /// parse, and every spine idref must be in the manifest.
+    /// `open` and `from_bytes` accept anything; call this to fail fast
+    /// instead of on first extraction. See [`Self::validate_report`] for a
+    /// full report.
+    pub async fn validate(&mut self) -> Result<()> {
+        let mimetype = self.extractor.read_file("mimetype").await?;
+        let mimetype = String::from_utf8_lossy(&mimetype);
+        if mimetype.trim() != "application/epub+zip" {
+            return Err(LexEpubError::InvalidFormat(format!(
+                "mimetype is '{}', expected 'application/epub+zip'",
+                mimetype
+            )));
+        }
+
+        let (_, metadata) = self.package().await?;
+        match metadata
+            .spine
+            .iter()
+            .find(|idref| !metadata.manifest.contains_key(*idref))
+        {
+            Some(idref) => Err(unresolved_spine_item(idref)),
+            None => Ok(()),
+        }
+    }
+
+    /// Every manifest image as `(archive path, media type, bytes)`, sorted
+    /// by path. Images that fail to read are left out.
+    pub async fn images(&mut self) -> Result<Vec<(String, String, Vec<u8>)>> {
+        let (opf_path, metadata) = self.package().await?;
+
+        let mut items: Vec<(String, String)> = metadata
+            .manifest
+            .iter()
+            .filter(|(_, (_, media_type))| media_type.starts_with("image/"))
+            .filter_map(|(id, (_, media_type))| {
+                manifest_item_path(&opf_path, &metadata, id).map(|path| (path, media_type.clone()))
+            })
+            .collect();
+        items.sort();
+
+        let mut images = Vec::with_capacity(items.len());
+        for (path, media_type) in items {
+            if let Ok(data) = self.extractor.read_file(&path).await {
+                images.push((path, media_type, data));
+            }
+        }
+        Ok(images)
+    }
+
+    /// OPF metadata and manifest media types the parser met but did not map
+    /// to any field (see [`OpfParser::parse_unhandled`]), e.g. vendor
+    /// `<meta>` extensions or audio items. Useful when metadata seems to go
+    /// missing.
+    pub async fn unhandled_report(&mut self) -> Result<Vec<String>> {
+        let (opf_path, opf_data) = self.read_opf().await?;
+        OpfParser::new()
+            .parse_unhandled(&opf_data)
+            .in_file(&opf_path)
+    }
+
+    /// Manifest hrefs (relative to the OPF, sorted) that nothing reaches.
+    ///
+    /// Roots are the spine, the navigation document and NCX, and the cover
+    /// image; from there every reachable (X)HTML document and stylesheet is
+    /// scanned for references (links, images, media, stylesheets, `url(...)`
+    /// targets such as fonts, and `@import`s), relative to the referring
+    /// file without applying `xml:base`. Anything left over is dead weight
+    /// in the archive.
+    pub async fn orphaned_resources(&mut self) -> Result<Vec<String>> {
+        let (opf_path, metadata) = self.package().await?;
+
+        let items: Vec<(&String, String, &String)> = metadata
+            .manifest
+            .iter()
+            .filter_map(|(id, (href, media_type))| {
+                manifest_item_path(&opf_path, &metadata, id).map(|path| (href, path, media_type))
+            })
+            .collect();
+        let media_types: std::collections::HashMap<&str, &str> = items
+            .iter()
+            .map(|(_, path, media_type)| (path.as_str(), media_type.as_str()))
+            .collect();
+
+        let mut queue: Vec<String> = metadata
+            .manifest
+            .iter()
+            .filter(|(id, (_, media_type))| {
+                metadata.spine.contains(id)
+                    || metadata.item_has_property(id, "nav")
+                    || media_type == "application/x-dtbncx+xml"
+                    || metadata.cover_image_id.as_ref() == Some(id)
+            })
+            .filter_map(|(id, _)| manifest_item_path(&opf_path, &metadata, id))
+            .collect();
+        let mut reached = std::collections::HashSet::new();
+
+        while let Some(path) = queue.pop() {
+            if !reached.insert(path.clone()) {
+                continue;
+            }
+            let media_type = media_types
+                .get(path.as_str())
+                .copied()
+                .unwrap_or_else(|| guess_media_type(&path));
+            let is_css = media_type == "text/css";
+            if !is_css && !crate::core::chapter::is_markup_media_type(media_type) {
+                continue;
+            }
+            let Ok(data) = self.extractor.read_file(&path).await else {
+                continue;
+            };
+            let text = String::from_utf8_lossy(&data);
 
-        // Get OPF location
-        let container_data = self.extractor.read_file("META-INF/container.xml").await?;
-        let mut container_parser = ContainerParser::new();
-        let opf_path = container_parser
-            .parse_container(&container_data)?
-            .rootfile_path;
+            let references = if is_css {
+                let mut urls = crate::core::css::Stylesheet::parse(&text).import_urls();
+                urls.extend(crate::core::css::css_urls(&text));
+                urls
+            } else {
+                let mut urls =
+                    crate::core::html_parser::resource_references(&text).unwrap_or_default();
+                for style in crate::core::html_parser::chapter_styles(&text).unwrap_or_default() {
+                    if let ChapterStyle::Inline(css) = style {
+                        urls.extend(crate::core::css::css_urls(&css));
+                    }
+                }
+                urls
+            };
 
-        // Parse OPF metadata
-        let opf_data = self.extractor.read_file(&opf_path).await?;
-        let mut opf_parser = OpfParser::new();
-        let opf_metadata = opf_parser.parse_metadata(&opf_data)?;
+            for reference in references {
+                if reference.starts_with('#') || has_uri_scheme(&reference) {
+                    continue;
+                }
+                let resolved = resolve_href_against(&path, &reference);
+                let target = percent_decode(resolved.split('#').next().unwrap_or_default());
+                if !target.is_empty() && !reached.contains(&target) {
+                    queue.push(target);
+                }
+            }
+        }
 
-        let epub_metadata: EpubMetadata = opf_metadata.into();
-        self.metadata = Some(epub_metadata.clone());
-        Ok(epub_metadata)
+        let mut orphans: Vec<String> = items
+            .into_iter()
+            .filter(|(_, path, _)| !reached.contains(path))
+            .map(|(href, _, _)| href.clone())
+            .collect();
+        orphans.sort();
+        Ok(orphans)
     }
 
     /// Validates the metadata against basic EPUB standard requirements
@@ -394,6 +2918,57 @@ impl LexEpub {
         futures::executor::block_on(self.total_char_count())
     }
 
+    /// Total user-perceived characters (grapheme clusters) across all
+    /// chapters. See [`ParsedChapter::grapheme_count`].
+    #[cfg(feature = "unicode-segmentation")]
+    pub async fn total_grapheme_count(&mut self) -> Result<usize> {
+        let texts = self.extract_text_only().await?;
+        Ok(texts
+            .iter()
+            .map(|text| crate::core::chapter::grapheme_count(text))
+            .sum())
+    }
+
+    /// Estimated time to read the whole book at `words_per_minute`, from
+    /// [`LexEpub::total_word_count`]. `InvalidArgument` for 0 WPM.
+    pub async fn reading_time(&mut self, words_per_minute: u32) -> Result<std::time::Duration> {
+        let per_minute = reading_speed(words_per_minute)?;
+        let words = self.total_word_count().await?;
+        Ok(words_duration(words, per_minute))
+    }
+
+    /// [`LexEpub::reading_time`] at an average 200 words per minute
+    pub async fn reading_time_default(&mut self) -> Result<std::time::Duration> {
+        self.reading_time(200).await
+    }
+
+    /// Estimated reading time of each spine chapter at `words_per_minute`,
+    /// from the chapters' `word_count`. `InvalidArgument` for 0 WPM.
+    pub async fn chapter_reading_times(
+        &mut self,
+        words_per_minute: u32,
+    ) -> Result<Vec<std::time::Duration>> {
+        let per_minute = reading_speed(words_per_minute)?;
+        let counts: Vec<usize> = match self.chapters {
+            Some(ref chapters) => chapters.iter().map(|c| c.word_count).collect(),
+            None => self
+                .extract_text_only()
+                .await?
+                .iter()
+                .map(|text| {
+                    crate::core::html_parser::count_words(
+                        text,
+                        self.options.word_tokenizer.as_ref(),
+                    )
+                })
+                .collect(),
+        };
+        Ok(counts
+            .into_iter()
+            .map(|words| words_duration(words, per_minute))
+            .collect())
+    }
+
     /// Internal: populate word + char count caches in one pass, reusing
     /// whichever chapter cache is already warm.
     async fn populate_count_cache(&mut self) -> Result<()> {
@@ -406,61 +2981,72 @@ impl LexEpub {
         // Use the cheaper text-only path if the text cache is warm
         if let Some(ref texts) = self.text_chapters {
             let (words, chars) = texts.iter().fold((0usize, 0usize), |(w, c), t| {
-                (w + t.split_whitespace().count(), c + t.chars().count())
+                (
+                    w + crate::core::html_parser::count_words(
+                        t,
+                        self.options.word_tokenizer.as_ref(),
+                    ),
+                    c + t.chars().count(),
+                )
             });
             self.cached_word_count = Some(words);
             self.cached_char_count = Some(chars);
             return Ok(());
         }
-        // Cold path: run text-only extraction and cache everything
-        let parsed = self.extract_chapters_text_only_internal().await?;
-        let mut words = 0usize;
-        let mut chars = 0usize;
-        let mut texts = Vec::with_capacity(parsed.len());
-        for c in &parsed {
-            words += c.word_count;
-            chars += c.char_count;
-            texts.push(c.content.clone());
-        }
-        self.cached_word_count = Some(words);
-        self.cached_char_count = Some(chars);
-        self.text_chapters = Some(texts);
+        // Cold path: count without keeping any chapter text
+        let stats = self.chapter_stats().await?;
+        self.cached_word_count = Some(stats.iter().map(|s| s.word_count).sum());
+        self.cached_char_count = Some(stats.iter().map(|s| s.char_count).sum());
         Ok(())
     }
 
+    /// Word and character counts of each spine chapter, in reading order.
+    /// Chapters are read and parsed one at a time and their text is dropped
+    /// as soon as it is counted, so peak memory stays flat. Failing chapters
+    /// are handled as in [`LexEpub::extract_text_only`], including
+    /// [`LexEpubOptions::on_chapter_error`].
+    pub async fn chapter_stats(&mut self) -> Result<Vec<ChapterStats>> {
+        if let Some(ref chapters) = self.chapters {
+            return Ok(chapters.iter().map(ChapterStats::from).collect());
+        }
+
+        let mut stats = Vec::new();
+        self.for_each_text_chapter(|chapter| stats.push(ChapterStats::from(&chapter)))
+            .await?;
+        Ok(stats)
+    }
+
     /// Check if the EPUB has a cover image
     pub fn has_cover_sync(&mut self) -> Result<bool> {
         futures::executor::block_on(self.has_cover())
     }
 
+    /// Whether the OPF declares a cover image. Only metadata is inspected;
+    /// the image itself is not read.
     pub async fn has_cover(&mut self) -> Result<bool> {
         // Reuse metadata cache if available — avoids re-reading container/OPF
         if let Some(ref meta) = self.metadata {
             return Ok(meta.has_cover);
         }
 
-        // Use read_opf() SSOT for container.xml parsing
-        let (_opf_path, opf_data) = self.read_opf().await?;
-        let mut opf_parser = OpfParser::new();
-        let cover_id = opf_parser.get_cover_image_id(&opf_data)?;
-
-        Ok(cover_id.is_some())
+        let (_, metadata) = self.package().await?;
+        Ok(metadata.cover_image_id.is_some())
     }
 
     /// Extract the cover image bytes from the EPUB
-    pub fn cover_image_sync(&mut self) -> Result<Vec<u8>> {
+    pub fn cover_image_sync(&mut self) -> Result<Option<Vec<u8>>> {
         futures::executor::block_on(self.cover_image())
     }
 
-    pub async fn cover_image(&mut self) -> Result<Vec<u8>> {
-        // Use read_opf() SSOT for container.xml parsing
-        let (opf_path, opf_data) = self.read_opf().await?;
-        let mut opf_parser = OpfParser::new();
-        let metadata = opf_parser.parse_metadata(&opf_data)?;
+    /// Bytes of the cover image, found through the EPUB3 `cover-image`
+    /// manifest property or the EPUB2 `<meta name="cover">` reference.
+    /// Returns `None` when the book declares no cover.
+    pub async fn cover_image(&mut self) -> Result<Option<Vec<u8>>> {
+        let (opf_path, metadata) = self.package().await?;
 
-        let cover_id = metadata
-            .cover_image_id
-            .ok_or_else(|| LexEpubError::MissingFile("No cover image found in EPUB".to_string()))?;
+        let Some(cover_id) = metadata.cover_image_id else {
+            return Ok(None);
+        };
 
         let cover_href = metadata.manifest.get(&cover_id).ok_or_else(|| {
             LexEpubError::MissingFile(format!("Cover image item '{}' not in manifest", cover_id))
@@ -469,10 +3055,37 @@ impl LexEpub {
         let opf_base = std::path::Path::new(&opf_path)
             .parent()
             .unwrap_or(std::path::Path::new(""));
-        let full_path = opf_base.join(&cover_href.0);
-        let full_path_str = full_path.to_string_lossy();
+        let full_path = manifest_href_path(opf_base, &cover_href.0);
+
+        self.extractor.read_file(&full_path).await.map(Some)
+    }
+
+    /// Decode the cover and scale it so its longest side is `max_dim`,
+    /// preserving the aspect ratio. JPEG covers are re-encoded as JPEG and
+    /// everything else as PNG. Returns `None` when the book has no cover.
+    #[cfg(feature = "image")]
+    pub async fn cover_thumbnail(&mut self, max_dim: u32) -> Result<Option<Vec<u8>>> {
+        let Some(data) = self.cover_image().await? else {
+            return Ok(None);
+        };
+        let is_jpeg = matches!(image::guess_format(&data), Ok(image::ImageFormat::Jpeg));
+        let cover = image::load_from_memory(&data).map_err(|e| {
+            LexEpubError::InvalidFormat(format!("Cannot decode cover image: {}", e))
+        })?;
 
-        self.extractor.read_file(&full_path_str).await
+        let max_dim = max_dim.max(1);
+        let thumbnail = cover.thumbnail(max_dim, max_dim);
+        let mut encoded = std::io::Cursor::new(Vec::new());
+        let written = if is_jpeg {
+            image::DynamicImage::ImageRgb8(thumbnail.to_rgb8())
+                .write_to(&mut encoded, image::ImageFormat::Jpeg)
+        } else {
+            thumbnail.write_to(&mut encoded, image::ImageFormat::Png)
+        };
+        written.map_err(|e| {
+            LexEpubError::InvalidFormat(format!("Cannot encode cover thumbnail: {}", e))
+        })?;
+        Ok(Some(encoded.into_inner()))
     }
 
     /// Stream the cover image bytes directly to a given parameter implementing futures::AsyncWrite.
@@ -480,10 +3093,7 @@ impl LexEpub {
         &mut self,
         writer: &mut W,
     ) -> Result<u64> {
-        // Use read_opf() SSOT for container.xml parsing
-        let (opf_path, opf_data) = self.read_opf().await?;
-        let mut opf_parser = OpfParser::new();
-        let metadata = opf_parser.parse_metadata(&opf_data)?;
+        let (opf_path, metadata) = self.package().await?;
 
         let cover_id = metadata
             .cover_image_id
@@ -496,12 +3106,9 @@ impl LexEpub {
         let opf_base = std::path::Path::new(&opf_path)
             .parent()
             .unwrap_or(std::path::Path::new(""));
-        let full_path = opf_base.join(&cover_href.0);
-        let full_path_str = full_path.to_string_lossy();
+        let full_path = manifest_href_path(opf_base, &cover_href.0);
 
-        self.extractor
-            .read_file_to_writer(&full_path_str, writer)
-            .await
+        self.extractor.read_file_to_writer(&full_path, writer).await
     }
 
     pub async fn extract_with_ast(&mut self) -> Result<Vec<ParsedChapter>> {
@@ -515,39 +3122,54 @@ impl LexEpub {
     /// Text-only chapter extraction (no CSS parsing, no AST).
     /// Results are stored in `self.text_chapters` but NOT in `self.chapters`.
     async fn extract_chapters_text_only_internal(&mut self) -> Result<Vec<ParsedChapter>> {
-        // Read OPF once
-        let (opf_path, opf_data) = self.read_opf().await?;
-        let mut opf_parser = OpfParser::new();
-        // parse_metadata() already populates spine, no need for separate parse_spine()
-        let metadata = opf_parser.parse_metadata(&opf_data)?;
-        let spine = metadata.spine.clone();
+        let mut chapters = Vec::new();
+        self.for_each_text_chapter(|chapter| chapters.push(chapter))
+            .await?;
+        Ok(chapters)
+    }
 
-        let opf_base = std::path::Path::new(&opf_path)
-            .parent()
-            .unwrap_or(std::path::Path::new(""))
-            .to_path_buf();
+    /// Text-only parse of the spine, handing each chapter to `each` as soon
+    /// as it is parsed. Failing chapters go through [`Self::recover_chapter`],
+    /// so strict mode stops at the first one.
+    async fn for_each_text_chapter<F>(&mut self, mut each: F) -> Result<()>
+    where
+        F: FnMut(ParsedChapter),
+    {
+        self.ensure_not_encrypted().await?;
+        let (opf_path, metadata) = self.package().await?;
+        // Text-only parse: no AST, no CSS
+        let parser = crate::core::html_parser::ChapterParser::new()
+            .text_only()
+            .text_options(self.text_options.clone())
+            .word_counter(self.options.word_tokenizer.clone());
 
-        let mut chapters = Vec::new();
-        for item_id in spine {
-            if let Some(href) = metadata.manifest.get(&item_id) {
-                let full_path = opf_base.join(&href.0);
-                let full_path_str = full_path.to_string_lossy();
-                match self.extractor.read_file(&full_path_str).await {
-                    Ok(content) => {
-                        let chapter =
-                            Chapter::new(full_path_str.to_string(), item_id.clone(), content);
-                        // Text-only parse: no AST, no CSS
-                        let parser = crate::core::html_parser::ChapterParser::new().text_only();
-                        match parser.parse_chapter(chapter) {
-                            Ok(parsed) => chapters.push(parsed),
-                            Err(_) => continue,
-                        }
+        let this = &*self;
+        this.read_spine_with(&opf_path, &metadata, |item_id, item| {
+            let Some((full_path_str, media_type, read)) = item else {
+                let error = unresolved_spine_item(&item_id);
+                if let Some(chapter) = this.recover_chapter(&item_id, &item_id, error)? {
+                    each(chapter);
+                }
+                return Ok(());
+            };
+            let parsed = read.and_then(|content| {
+                let chapter = Chapter::new(full_path_str.clone(), item_id.clone(), content)
+                    .with_media_type(&media_type);
+                let mut parsed = parser.parse_chapter(chapter)?;
+                parsed.is_scripted |= metadata.item_has_property(&item_id, "scripted");
+                Ok(parsed)
+            });
+            match parsed {
+                Ok(parsed) => each(parsed),
+                Err(e) => {
+                    if let Some(chapter) = this.recover_chapter(&full_path_str, &item_id, e)? {
+                        each(chapter);
                     }
-                    Err(_) => continue,
                 }
             }
-        }
-        Ok(chapters)
+            Ok(())
+        })
+        .await
     }
 
     /// Full AST+CSS chapter extraction. Results cached in `self.chapters`.
@@ -556,89 +3178,375 @@ impl LexEpub {
             return Ok(chapters.clone());
         }
 
-        let (opf_path, opf_data) = self.read_opf().await?;
-        let mut opf_parser = OpfParser::new();
-        // parse_metadata() already populates spine, no need for separate parse_spine()
-        let metadata = opf_parser.parse_metadata(&opf_data)?;
-        let spine = metadata.spine.clone();
+        let mut chapters = Vec::new();
+        self.parse_spine_full(|this, item_id, href, parsed| {
+            match parsed {
+                Ok(chapter) => chapters.push(chapter),
+                // Unresolved idrefs are reported under the idref itself
+                Err(e) if href.is_empty() => {
+                    chapters.extend(this.recover_chapter(&item_id, &item_id, e)?)
+                }
+                Err(e) => chapters.extend(this.recover_chapter(&href, &item_id, e)?),
+            }
+            Ok(())
+        })
+        .await?;
+
+        // Populate derived caches from the now-available full parse
+        if self.cached_word_count.is_none() {
+            self.cached_word_count = Some(chapters.iter().map(|c| c.word_count).sum());
+        }
+        if self.cached_char_count.is_none() {
+            self.cached_char_count = Some(chapters.iter().map(|c| c.char_count).sum());
+        }
+        // Also populate text cache so extract_text_only() is free after this
+        if self.text_chapters.is_none() && !self.low_memory() {
+            self.text_chapters = Some(chapters.iter().map(|c| c.content.clone()).collect());
+        }
+
+        if !self.low_memory() && self.options.chapter_cache_size.is_none() {
+            self.chapters = Some(chapters.clone());
+        }
+
+        Ok(chapters)
+    }
 
+    /// Full AST+CSS parse of every spine item, handing each one to `sink`
+    /// as `(self, idref, archive path, result)` as soon as it is parsed. The
+    /// path is empty when the manifest has no item for the idref. An error
+    /// returned by `sink` stops the parse.
+    async fn parse_spine_full<F>(&mut self, mut sink: F) -> Result<()>
+    where
+        F: FnMut(&Self, String, String, Result<ParsedChapter>) -> Result<()>,
+    {
+        self.ensure_not_encrypted().await?;
+
+        let (opf_path, metadata) = self.package().await?;
         let opf_base = std::path::Path::new(&opf_path)
             .parent()
             .unwrap_or(std::path::Path::new(""))
             .to_path_buf();
 
         // Parse all CSS once
+        let stylesheet = self.book_stylesheet(&opf_base, &metadata).await;
+
+        let parser = crate::core::html_parser::ChapterParser::new()
+            .with_both()
+            .text_options(self.text_options.clone())
+            .word_counter(self.options.word_tokenizer.clone());
+        let this = &*self;
+        this.read_spine_with(&opf_path, &metadata, |item_id, item| {
+            let Some((full_path_str, media_type, read)) = item else {
+                let error = unresolved_spine_item(&item_id);
+                return sink(this, item_id, String::new(), Err(error));
+            };
+            let result = read.and_then(|content| {
+                let chapter = Chapter::new(full_path_str.clone(), item_id.clone(), content)
+                    .with_media_type(&media_type);
+                let mut parsed_chapter = parser.parse_chapter(chapter)?;
+                parsed_chapter.is_scripted |= metadata.item_has_property(&item_id, "scripted");
+                finish_full_chapter(&mut parsed_chapter, &full_path_str, &stylesheet);
+                Ok(parsed_chapter)
+            });
+            sink(this, item_id, full_path_str, result)
+        })
+        .await
+    }
+
+    /// Every `text/css` manifest item, concatenated and parsed once
+    async fn book_stylesheet(
+        &self,
+        opf_base: &Path,
+        metadata: &crate::core::opf_parser::OpfMetadata,
+    ) -> crate::core::css::Stylesheet {
         let mut css_text = String::new();
         for (href, media_type) in metadata.manifest.values() {
             if media_type == "text/css" {
-                let css_path = opf_base.join(href);
-                let css_path_str = css_path.to_string_lossy();
-                if let Ok(css_data) = self.extractor.read_file(&css_path_str).await {
+                let css_path = manifest_href_path(opf_base, href);
+                if let Ok(css_data) = self.extractor.read_file(&css_path).await {
                     css_text.push_str(&String::from_utf8_lossy(&css_data));
                     css_text.push('\n');
                 }
             }
         }
-        let stylesheet = crate::core::css::Stylesheet::parse(&css_text);
+        crate::core::css::Stylesheet::parse(&css_text)
+    }
+
+    /// Read every spine chapter through one opened archive, handing each
+    /// idref to `each` in spine order with its `(archive path, media type,
+    /// bytes)`, or `None` when the manifest has no such item. Chapters are
+    /// inflated one at a time, so each one's bytes are released before the
+    /// next is read; an error returned by `each` stops the read.
+    async fn read_spine_with<F>(
+        &self,
+        opf_path: &str,
+        metadata: &OpfMetadata,
+        mut each: F,
+    ) -> Result<()>
+    where
+        F: FnMut(String, Option<(String, String, Result<Vec<u8>>)>) -> Result<()>,
+    {
+        /// A spine item waiting for its turn
+        enum Pending {
+            /// Bytes arrive from the archive read
+            Read(String, String, String),
+            /// Fails without being read: `None` for an unresolved idref
+            Unread(String, Option<(String, String, LexEpubError)>),
+        }
+
+        fn emit_unread<F>(
+            each: &mut F,
+            item_id: String,
+            target: Option<(String, String, LexEpubError)>,
+        ) -> Result<()>
+        where
+            F: FnMut(String, Option<(String, String, Result<Vec<u8>>)>) -> Result<()>,
+        {
+            each(
+                item_id,
+                target.map(|(path, media_type, error)| (path, media_type, Err(error))),
+            )
+        }
+
+        let opf_base = std::path::Path::new(opf_path)
+            .parent()
+            .unwrap_or(std::path::Path::new(""));
+        let mut pending = std::collections::VecDeque::with_capacity(metadata.spine.len());
+        let mut paths = Vec::new();
+        for item_id in &metadata.spine {
+            let Some((href, media_type)) = metadata.manifest.get(item_id) else {
+                pending.push_back(Pending::Unread(item_id.clone(), None));
+                continue;
+            };
+            let path = manifest_href_path(opf_base, href);
+            // Oversized chapters fail without being read
+            match crate::core::chapter::ensure_chapter_size(
+                &self.extractor,
+                &path,
+                self.options.max_chapter_size,
+            )
+            .await
+            {
+                Ok(()) => {
+                    paths.push(path.clone());
+                    pending.push_back(Pending::Read(item_id.clone(), path, media_type.clone()));
+                }
+                Err(error) => pending.push_back(Pending::Unread(
+                    item_id.clone(),
+                    Some((path, media_type.clone(), error)),
+                )),
+            }
+        }
 
-        let mut chapters = Vec::new();
-        let parser = crate::core::html_parser::ChapterParser::new();
-        for item_id in spine {
-            if let Some(href) = metadata.manifest.get(&item_id) {
-                let full_path = opf_base.join(&href.0);
-                let full_path_str = full_path.to_string_lossy();
-                match self.extractor.read_file(&full_path_str).await {
-                    Ok(content) => {
-                        let chapter =
-                            Chapter::new(full_path_str.to_string(), item_id.clone(), content);
-                        let mut parsed_chapter = match parser.parse_chapter(chapter) {
-                            Ok(p) => p,
-                            Err(_) => continue,
-                        };
-
-                        if let Some(ref mut ast) = parsed_chapter.ast {
-                            normalize_ast_links(ast, &full_path_str);
-                            stylesheet.apply_to_ast(ast);
+        self.extractor
+            .read_files_with(&paths, |_, read| {
+                // Unread items queued ahead of this one go first
+                while let Some(item) = pending.pop_front() {
+                    match item {
+                        Pending::Unread(item_id, target) => {
+                            emit_unread(&mut each, item_id, target)?
+                        }
+                        Pending::Read(item_id, path, media_type) => {
+                            return each(item_id, Some((path, media_type, read)));
                         }
-
-                        chapters.push(parsed_chapter);
                     }
-                    Err(_) => continue,
                 }
+                Ok(())
+            })
+            .await?;
+        for item in pending {
+            if let Pending::Unread(item_id, target) = item {
+                emit_unread(&mut each, item_id, target)?;
             }
         }
+        Ok(())
+    }
 
-        // Populate derived caches from the now-available full parse
-        if self.cached_word_count.is_none() {
-            self.cached_word_count = Some(chapters.iter().map(|c| c.word_count).sum());
-        }
-        if self.cached_char_count.is_none() {
-            self.cached_char_count = Some(chapters.iter().map(|c| c.char_count).sum());
+    /// Resolve the spine to `(idref, archive path)` pairs in reading order,
+    /// skipping idrefs that have no manifest entry.
+    async fn spine_entries(&mut self) -> Result<Vec<(String, String)>> {
+        let (opf_path, metadata) = self.package().await?;
+
+        let opf_base = std::path::Path::new(&opf_path)
+            .parent()
+            .unwrap_or(std::path::Path::new(""));
+        Ok(metadata
+            .spine
+            .iter()
+            .filter_map(|item_id| {
+                metadata
+                    .manifest
+                    .get(item_id)
+                    .map(|(href, _)| (item_id.clone(), manifest_href_path(opf_base, href)))
+            })
+            .collect())
+    }
+
+    /// Read a stylesheet once per traversal; `None` for remote, missing, or
+    /// already visited sheets.
+    async fn read_stylesheet(
+        &self,
+        path: &str,
+        visited: &mut std::collections::HashSet<String>,
+    ) -> Option<String> {
+        if is_external_url(path) || !visited.insert(path.to_string()) {
+            return None;
         }
-        // Also populate text cache so extract_text_only() is free after this
-        if self.text_chapters.is_none() {
-            self.text_chapters = Some(chapters.iter().map(|c| c.content.clone()).collect());
+        let data = self.extractor.read_file(path).await.ok()?;
+        Some(String::from_utf8_lossy(&data).to_string())
+    }
+
+    /// Lazily read and cache `META-INF/encryption.xml`. A missing file simply
+    /// means nothing in the archive is encrypted or obfuscated.
+    async fn encryption_info(&mut self) -> Result<&EncryptionInfo> {
+        if self.encryption.is_none() {
+            let info = match self.extractor.read_file("META-INF/encryption.xml").await {
+                Ok(data) => EncryptionParser::new().parse_encryption(&data)?,
+                Err(LexEpubError::MissingFile(_)) => EncryptionInfo::default(),
+                Err(e) => return Err(e),
+            };
+            self.encryption = Some(info);
         }
+        Ok(self.encryption.as_ref().unwrap())
+    }
 
-        #[cfg(not(feature = "lowmem"))]
-        {
-            self.chapters = Some(chapters.clone());
+    /// Fail with [`LexEpubError::Encrypted`] when encryption.xml declares
+    /// resources encrypted with anything but font obfuscation, naming them,
+    /// instead of failing later on undecodable content.
+    async fn ensure_not_encrypted(&mut self) -> Result<()> {
+        let uris: Vec<&str> = self
+            .encryption_info()
+            .await?
+            .encrypted()
+            .map(|r| r.uri.as_str())
+            .collect();
+        if uris.is_empty() {
+            return Ok(());
+        }
+        const LISTED: usize = 5;
+        let mut message = format!(
+            "{} resource{} protected by DRM: {}",
+            uris.len(),
+            if uris.len() == 1 { " is" } else { "s are" },
+            uris[..uris.len().min(LISTED)].join(", ")
+        );
+        if uris.len() > LISTED {
+            message.push_str(&format!(" and {} more", uris.len() - LISTED));
         }
+        Err(LexEpubError::Encrypted(message))
+    }
 
-        Ok(chapters)
+    /// Undo font obfuscation for `path` if encryption.xml declares it. The
+    /// package document is only consulted, for the key, when it does.
+    async fn deobfuscate_resource(&mut self, path: &str, bytes: &mut [u8]) -> Result<()> {
+        let algorithm = match self.encryption_info().await?.get(path) {
+            Some(resource) if resource.is_font_obfuscation() => resource.algorithm.clone(),
+            _ => return Ok(()),
+        };
+        let (_, metadata) = self.package().await?;
+        let unique_identifier = metadata
+            .unique_identifier
+            .as_deref()
+            .or(metadata.identifiers.first().map(|id| id.value.as_str()))
+            .unwrap_or_default();
+        crate::core::encryption::deobfuscate_font(bytes, &algorithm, unique_identifier);
+        Ok(())
     }
 
-    /// Read and return (opf_path, opf_data), reusing the metadata cache's
-    /// knowledge of opf_path when available to avoid re-reading container.xml.
+    /// Read and return (opf_path, opf_data). container.xml and the package
+    /// document are read once per book and cached.
     async fn read_opf(&mut self) -> Result<(String, Vec<u8>)> {
+        if let Some(ref opf) = self.opf {
+            return Ok(opf.clone());
+        }
         let container_data = self.extractor.read_file("META-INF/container.xml").await?;
         let mut container_parser = ContainerParser::new();
         let opf_path = container_parser
             .parse_container(&container_data)?
             .rootfile_path;
         let opf_data = self.extractor.read_file(&opf_path).await?;
+        self.opf = Some((opf_path.clone(), opf_data.clone()));
         Ok((opf_path, opf_data))
     }
+
+    /// The package path and parsed package document, parsed once per book
+    /// and shared by metadata and chapter extraction
+    async fn package(&mut self) -> Result<(String, OpfMetadata)> {
+        if let (Some((opf_path, _)), Some(package)) = (&self.opf, &self.package) {
+            return Ok((opf_path.clone(), package.clone()));
+        }
+        let (opf_path, opf_data) = self.read_opf().await?;
+        let metadata = OpfParser::new()
+            .parse_metadata(&opf_data)
+            .in_file(&opf_path)?;
+        self.package = Some(metadata.clone());
+        Ok((opf_path, metadata))
+    }
+}
+
+/// First `http://` or `https://` URL in free text, without trailing
+/// punctuation
+fn find_url(text: &str) -> Option<String> {
+    let (start, _) = text
+        .match_indices("http")
+        .find(|(i, _)| text[*i..].starts_with("http://") || text[*i..].starts_with("https://"))?;
+    let rest = &text[start..];
+    let end = rest
+        .find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '\'' | ')'))
+        .unwrap_or(rest.len());
+    let url = rest[..end].trim_end_matches(['.', ',', ';', ':']);
+    Some(url.to_string())
+}
+
+/// Snowball stemmer for a BCP 47 language tag, English when unsupported
+#[cfg(feature = "stemming")]
+fn stemmer_for(language: Option<&str>) -> rust_stemmers::Stemmer {
+    use rust_stemmers::Algorithm;
+
+    let primary = language
+        .and_then(|tag| tag.split(['-', '_']).next())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let algorithm = match primary.as_str() {
+        "ar" => Algorithm::Arabic,
+        "da" => Algorithm::Danish,
+        "nl" => Algorithm::Dutch,
+        "fi" => Algorithm::Finnish,
+        "fr" => Algorithm::French,
+        "de" => Algorithm::German,
+        "el" => Algorithm::Greek,
+        "hu" => Algorithm::Hungarian,
+        "it" => Algorithm::Italian,
+        "no" | "nb" | "nn" => Algorithm::Norwegian,
+        "pt" => Algorithm::Portuguese,
+        "ro" => Algorithm::Romanian,
+        "ru" => Algorithm::Russian,
+        "es" => Algorithm::Spanish,
+        "sv" => Algorithm::Swedish,
+        "ta" => Algorithm::Tamil,
+        "tr" => Algorithm::Turkish,
+        _ => Algorithm::English,
+    };
+    rust_stemmers::Stemmer::create(algorithm)
+}
+
+pub(crate) fn unresolved_spine_item(id: &str) -> LexEpubError {
+    LexEpubError::MissingFile(format!("Spine item '{}' is not in the manifest", id))
+}
+
+/// Whether an href starts with a URI scheme (`http:`, `mailto:`, `tel:`...)
+fn has_uri_scheme(href: &str) -> bool {
+    href.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.len() > 1
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    })
+}
+
+/// Whether an href points outside the archive
+fn is_external_url(href: &str) -> bool {
+    href.contains("://") || href.starts_with("data:") || href.starts_with("blob:")
 }
 
 fn resolve_href_against(base_path: &str, href: &str) -> String {
@@ -683,6 +3591,85 @@ fn resolve_href_against(base_path: &str, href: &str) -> String {
     normalized
 }
 
+/// Path of a document that relative URLs under `xml:base="base"` resolve
+/// against, given the path they otherwise resolve against. `None` for remote
+/// bases, which cannot re-root archive paths.
+fn xml_base_path(document: &str, base: &str) -> Option<String> {
+    let base = base.trim();
+    if base.is_empty() || base.contains("://") || base.starts_with("data:") {
+        return None;
+    }
+    let resolved = resolve_href_against(document, base.split('#').next().unwrap_or(base));
+    if !base.ends_with('/') {
+        return Some(resolved);
+    }
+    // A directory base: keep the document name, move it into that directory
+    let name = Path::new(document).file_name()?.to_string_lossy();
+    Some(normalize_internal_path(&format!("{}/{}", resolved, name)))
+}
+
+/// Archive paths of the spine items, in reading order and parallel to the
+/// spine; empty for idrefs that are not in the manifest
+fn spine_paths(opf_path: &str, metadata: &crate::core::opf_parser::OpfMetadata) -> Vec<String> {
+    metadata
+        .spine
+        .iter()
+        .map(|id| manifest_item_path(opf_path, metadata, id).unwrap_or_default())
+        .collect()
+}
+
+/// Normalized archive path of the manifest item `id`
+fn manifest_item_path(
+    opf_path: &str,
+    metadata: &crate::core::opf_parser::OpfMetadata,
+    id: &str,
+) -> Option<String> {
+    let (href, _) = metadata.manifest.get(id)?;
+    let opf_base = std::path::Path::new(opf_path)
+        .parent()
+        .unwrap_or(std::path::Path::new(""));
+    Some(manifest_href_path(opf_base, href))
+}
+
+/// Best-effort media type for a resource that isn't declared in the manifest
+fn guess_media_type(path: &str) -> &'static str {
+    let extension = std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "xhtml" | "xht" => "application/xhtml+xml",
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "js" => "application/javascript",
+        "ncx" => "application/x-dtbncx+xml",
+        "opf" => crate::core::container::PACKAGE_MEDIA_TYPE,
+        "xml" => "application/xml",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "mp3" => "audio/mpeg",
+        "mp4" | "m4a" => "audio/mp4",
+        "smil" => "application/smil+xml",
+        "txt" => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Archive path of a manifest href, which is relative to the package
+/// document and may be percent-encoded. Separators are always `/` and
+/// `.`/`..` segments are collapsed, whatever the host platform.
+fn manifest_href_path(opf_base: &Path, href: &str) -> String {
+    normalize_internal_path(&percent_decode(&opf_base.join(href).to_string_lossy()))
+}
+
 fn normalize_internal_path(path: &str) -> String {
     let mut parts = Vec::new();
     let replaced = path.replace('\\', "/");
@@ -698,6 +3685,221 @@ fn normalize_internal_path(path: &str) -> String {
     parts.join("/")
 }
 
+/// Render NCX navPoints as nested `<ol>` lists, resolving each `src`
+/// against the NCX path
+fn nav_points_html(points: &[crate::core::ncx::NavPoint], ncx_path: &str, html: &mut String) {
+    html.push_str("<ol>");
+    for point in points {
+        html.push_str("<li><a href=\"");
+        html.push_str(&html_escape::encode_double_quoted_attribute(
+            &resolve_href_against(ncx_path, &point.src),
+        ));
+        html.push_str("\">");
+        html.push_str(&html_escape::encode_text(&point.label));
+        html.push_str("</a>");
+        if !point.children.is_empty() {
+            nav_points_html(&point.children, ncx_path, html);
+        }
+        html.push_str("</li>");
+    }
+    html.push_str("</ol>");
+}
+
+/// Reject a zero reading speed before dividing by it
+fn reading_speed(words_per_minute: u32) -> Result<f64> {
+    if words_per_minute == 0 {
+        return Err(LexEpubError::InvalidArgument(
+            "words_per_minute must be greater than zero".to_string(),
+        ));
+    }
+    Ok(f64::from(words_per_minute))
+}
+
+fn words_duration(words: usize, words_per_minute: f64) -> std::time::Duration {
+    std::time::Duration::from_secs_f64(words as f64 * 60.0 / words_per_minute)
+}
+
+/// Cut `text` to at most `max_chars` characters, ellipsis included, ending
+/// on a word boundary where there is one. Empty when not even the ellipsis
+/// fits.
+fn truncate_words(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    if max_chars == 0 {
+        return String::new();
+    }
+    let keep = max_chars.saturating_sub(1);
+    let cut = text.char_indices().nth(keep).map_or(text.len(), |(i, _)| i);
+    let head = &text[..cut];
+    // Only back off to a space when the cut lands inside a word
+    let head = if text[cut..].starts_with(char::is_whitespace) {
+        head
+    } else {
+        head.rfind(char::is_whitespace).map_or(head, |i| &head[..i])
+    };
+    format!("{}…", head.trim_end())
+}
+
+/// Pack one chapter's paragraphs into [`Chunk`]s (see [`LexEpub::chunks`])
+fn chapter_chunks(text: &str, chapter_index: usize, target: usize, overlap: usize) -> Vec<Chunk> {
+    let chars: Vec<char> = text.chars().collect();
+    // (char start, char end, paragraph) of every word, and the char range of
+    // every paragraph (line)
+    let mut words = Vec::new();
+    let mut paragraphs = Vec::new();
+    let mut base = 0;
+    for (paragraph, line) in text.split('\n').enumerate() {
+        for (offset, word) in crate::core::html_parser::word_spans(line) {
+            let start = base + offset;
+            words.push((start, start + word.chars().count(), paragraph));
+        }
+        let len = line.chars().count();
+        paragraphs.push((base, base + len));
+        base += len + 1;
+    }
+
+    let paragraph_end = |from: usize| {
+        let paragraph = words[from].2;
+        words[from..]
+            .iter()
+            .position(|word| word.2 != paragraph)
+            .map_or(words.len(), |len| from + len)
+    };
+    // Where a cut before word `at` falls: at the first whitespace after the
+    // previous word, so trailing punctuation stays with it
+    let cut = |at: usize| {
+        let (from, to) = (words[at - 1].1, words[at].0);
+        chars[from..to]
+            .iter()
+            .position(|c| c.is_whitespace())
+            .map_or(to, |len| from + len)
+    };
+
+    let mut chunks = Vec::new();
+    // First word not yet in any chunk
+    let mut fresh = 0;
+    while fresh < words.len() {
+        // Overlap counts towards the target; new words always make progress
+        let mut overlap = overlap.min(target - 1).min(fresh);
+        let budget = target - overlap;
+        let mut end = paragraph_end(fresh);
+        if end - fresh > target {
+            end = fresh + budget;
+        } else if end - fresh > budget {
+            overlap = target - (end - fresh);
+        } else {
+            while end < words.len() {
+                let next = paragraph_end(end);
+                if next - fresh > budget {
+                    break;
+                }
+                end = next;
+            }
+        }
+
+        // Chunks take in whole paragraphs where they open or close one
+        let start = fresh - overlap;
+        let char_start = if start == 0 || words[start - 1].2 != words[start].2 {
+            paragraphs[words[start].2].0
+        } else {
+            let from = cut(start);
+            from + chars[from..words[start].0]
+                .iter()
+                .take_while(|c| c.is_whitespace())
+                .count()
+        };
+        let char_end = if end == words.len() || words[end].2 != words[end - 1].2 {
+            paragraphs[words[end - 1].2].1
+        } else {
+            cut(end)
+        };
+        chunks.push(Chunk {
+            text: chars[char_start..char_end].iter().collect(),
+            chapter_index,
+            start_offset: char_start,
+            word_count: end - start,
+        });
+        fresh = end;
+    }
+    chunks
+}
+
+/// Convert parsed navPoints to [`TocNode`]s, resolving hrefs against the
+/// NCX or nav document they came from
+fn toc_nodes(points: &[crate::core::ncx::NavPoint], base_path: &str) -> Vec<TocNode> {
+    points
+        .iter()
+        .map(|point| {
+            let (href, fragment) = if point.src.trim().is_empty() {
+                (String::new(), None)
+            } else {
+                let resolved = resolve_href_against(base_path, &point.src);
+                match resolved.split_once('#') {
+                    // A bare `#id` points into the NCX/nav document itself
+                    Some(("", fragment)) => (base_path.to_string(), Some(fragment.to_string())),
+                    Some((path, fragment)) => (path.to_string(), Some(fragment.to_string())),
+                    None => (resolved, None),
+                }
+            };
+            TocNode {
+                label: point.label.clone(),
+                href,
+                fragment,
+                children: toc_nodes(&point.children, base_path),
+                play_order: point.play_order,
+            }
+        })
+        .collect()
+}
+
+/// `(char offset, char length)` of each non-overlapping occurrence of
+/// `query` in `text`, scanning left to right
+fn substring_matches(text: &[char], query: &[char], case_sensitive: bool) -> Vec<(usize, usize)> {
+    let same =
+        |a: char, b: char| a == b || (!case_sensitive && a.to_lowercase().eq(b.to_lowercase()));
+
+    let mut matches = Vec::new();
+    let mut start = 0;
+    while start + query.len() <= text.len() {
+        let window = &text[start..start + query.len()];
+        if window.iter().zip(query).all(|(&a, &b)| same(a, b)) {
+            matches.push((start, query.len()));
+            start += query.len();
+        } else {
+            start += 1;
+        }
+    }
+    matches
+}
+
+/// The match at `start..start + len` with [`SEARCH_SNIPPET_CONTEXT`] chars
+/// of context either side, on one line
+fn search_snippet(text: &[char], start: usize, len: usize) -> String {
+    let from = start.saturating_sub(SEARCH_SNIPPET_CONTEXT);
+    let to = (start + len + SEARCH_SNIPPET_CONTEXT).min(text.len());
+    text[from..to]
+        .iter()
+        .map(|&c| if c == '\n' || c == '\r' { ' ' } else { c })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// Post-process a full (AST) parse: sanitize, resolve links against the
+/// chapter path, and apply the book stylesheet
+pub(crate) fn finish_full_chapter(
+    parsed: &mut ParsedChapter,
+    path: &str,
+    stylesheet: &crate::core::css::Stylesheet,
+) {
+    if let Some(ref mut ast) = parsed.ast {
+        ast.sanitize();
+        normalize_ast_links(ast, path);
+        stylesheet.apply_to_ast(ast);
+    }
+}
+
 fn normalize_ast_links(ast: &mut crate::core::chapter::AstNode, chapter_href: &str) {
     use crate::core::chapter::AstNode;
 
@@ -705,6 +3907,12 @@ fn normalize_ast_links(ast: &mut crate::core::chapter::AstNode, chapter_href: &s
         attrs, children, ..
     } = ast
     {
+        // `xml:base` re-roots relative URLs for the element and its subtree
+        let based = attrs
+            .get("xml:base")
+            .and_then(|base| xml_base_path(chapter_href, base));
+        let chapter_href = based.as_deref().unwrap_or(chapter_href);
+
         if let Some(href) = attrs.get_mut("href") {
             let resolved = resolve_href_against(chapter_href, href);
             *href = resolved;
@@ -721,6 +3929,109 @@ fn normalize_ast_links(ast: &mut crate::core::chapter::AstNode, chapter_href: &s
     }
 }
 
+/// Whether an element is a `<link rel="stylesheet">`
+fn is_stylesheet_link(tag: &str, attrs: &std::collections::HashMap<String, String>) -> bool {
+    tag.eq_ignore_ascii_case("link")
+        && attrs.get("rel").is_some_and(|rel| {
+            rel.split_whitespace()
+                .any(|r| r.eq_ignore_ascii_case("stylesheet"))
+        })
+}
+
+/// Image reference attribute of an element: `src` on `<img>`, `href` or
+/// `xlink:href` on SVG `<image>`
+fn image_ref_attr(tag: &str) -> &'static [&'static str] {
+    if tag.eq_ignore_ascii_case("img") {
+        &["src"]
+    } else if tag.eq_ignore_ascii_case("image") {
+        &["href", "xlink:href"]
+    } else {
+        &[]
+    }
+}
+
+/// Archive paths of the stylesheets and images a (link-normalized) chapter
+/// AST references, for [`LexEpub::chapter_standalone_html`]
+fn collect_standalone_refs(
+    node: &crate::core::chapter::AstNode,
+    stylesheets: &mut Vec<String>,
+    images: &mut Vec<String>,
+) {
+    let crate::core::chapter::AstNode::Element {
+        tag,
+        attrs,
+        children,
+        ..
+    } = node
+    else {
+        return;
+    };
+    if is_stylesheet_link(tag, attrs) {
+        if let Some(href) = attrs.get("href") {
+            stylesheets.push(href.split('#').next().unwrap_or(href).to_string());
+        }
+    }
+    for attr in image_ref_attr(tag) {
+        if let Some(src) = attrs.get(*attr).filter(|src| !src.starts_with("data:")) {
+            images.push(src.clone());
+        }
+    }
+    for child in children {
+        collect_standalone_refs(child, stylesheets, images);
+    }
+}
+
+/// Drop stylesheet links and swap image references for their data URIs
+fn inline_standalone_refs(
+    node: &mut crate::core::chapter::AstNode,
+    data_uris: &std::collections::HashMap<String, String>,
+) {
+    let crate::core::chapter::AstNode::Element {
+        tag,
+        attrs,
+        children,
+        ..
+    } = node
+    else {
+        return;
+    };
+    for attr in image_ref_attr(tag) {
+        if let Some(src) = attrs.get_mut(*attr) {
+            if let Some(uri) = data_uris.get(src.as_str()) {
+                *src = uri.clone();
+            }
+        }
+    }
+    children.retain(|child| {
+        !matches!(child, crate::core::chapter::AstNode::Element { tag, attrs, .. }
+            if is_stylesheet_link(tag, attrs))
+    });
+    for child in children {
+        inline_standalone_refs(child, data_uris);
+    }
+}
+
+/// Move `element` into the first `<head>`; it stays `Some` when there is
+/// none
+fn append_to_head(
+    node: &mut crate::core::chapter::AstNode,
+    element: &mut Option<crate::core::chapter::AstNode>,
+) {
+    let crate::core::chapter::AstNode::Element { tag, children, .. } = node else {
+        return;
+    };
+    if tag.eq_ignore_ascii_case("head") {
+        children.extend(element.take());
+        return;
+    }
+    for child in children {
+        if element.is_none() {
+            return;
+        }
+        append_to_head(child, element);
+    }
+}
+
 /// Convenience function for quick text extraction
 pub async fn extract_text_only<P: AsRef<Path>>(path: P) -> Result<Vec<String>> {
     let mut epub = LexEpub::open(path).await?;
@@ -786,7 +4097,7 @@ async fn analyze_with_extractor(extractor: EpubExtractor) -> Result<AnalysisRepo
     let opf_data = extractor.read_file(&opf_path).await?;
     let mut opf_parser = OpfParser::new();
     // parse_metadata() already populates spine, no need for separate parse_spine()
-    let metadata = opf_parser.parse_metadata(&opf_data)?;
+    let metadata = opf_parser.parse_metadata(&opf_data).in_file(&opf_path)?;
     let spine = metadata.spine.clone();
 
     let mut chapters_parsed = Vec::new();
@@ -796,9 +4107,8 @@ async fn analyze_with_extractor(extractor: EpubExtractor) -> Result<AnalysisRepo
 
     for item_id in spine {
         if let Some(href) = metadata.manifest.get(&item_id) {
-            let full_path = opf_base.join(&href.0);
-            let full_path_str = full_path.to_string_lossy().to_string();
-            if let Ok(content) = extractor.read_file(&full_path_str).await {
+            let full_path = manifest_href_path(opf_base, &href.0);
+            if let Ok(content) = extractor.read_file(&full_path).await {
                 let html_content = String::from_utf8_lossy(&content);
                 let text_content = extract_text_content(&html_content)?;
                 let word_count = text_content.split_whitespace().count();
@@ -816,7 +4126,9 @@ async fn analyze_with_extractor(extractor: EpubExtractor) -> Result<AnalysisRepo
         .first()
         .map(|(s, _, _)| s.chars().take(300).collect::<String>());
 
-    let epub_metadata: EpubMetadata = metadata.into();
+    let spine_hrefs = spine_paths(&opf_path, &metadata);
+    let mut epub_metadata: EpubMetadata = metadata.into();
+    epub_metadata.spine_hrefs = spine_hrefs;
 
     Ok(AnalysisReport {
         metadata: Some(epub_metadata),
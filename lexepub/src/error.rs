@@ -13,6 +13,16 @@ pub enum LexEpubError {
     #[error("XML parsing error: {0}")]
     Xml(#[from] quick_xml::Error),
 
+    /// An XML error with the archive path of the document and the byte
+    /// offset in it where parsing failed
+    #[error("XML parsing error in '{file}' at byte {position}: {source}")]
+    XmlInContext {
+        file: String,
+        position: u64,
+        #[source]
+        source: quick_xml::Error,
+    },
+
     #[error("HTML parsing error: {0}")]
     Html(String),
 
@@ -31,6 +41,12 @@ pub enum LexEpubError {
     #[error("Chapter parsing error: {0}")]
     ChapterError(String),
 
+    #[error("Failed to extract chapter '{href}': {source}")]
+    ChapterFailed {
+        href: String,
+        source: Box<LexEpubError>,
+    },
+
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
@@ -40,9 +56,40 @@ pub enum LexEpubError {
     #[error("UTF-8 conversion error: {0}")]
     Utf8Str(#[from] std::str::Utf8Error),
 
+    #[error("EPUB is encrypted: {0}")]
+    Encrypted(String),
+
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+
+    #[error("Invalid search pattern: {0}")]
+    InvalidPattern(String),
+
     #[error("Async task error: {0}")]
     AsyncError(String),
 }
 
 /// Result type for convenience
 pub type Result<T> = std::result::Result<T, LexEpubError>;
+
+/// Attach the archive path of the document being parsed to XML errors
+pub(crate) trait XmlContext {
+    /// Name `file` as the source of an [`LexEpubError::XmlInContext`];
+    /// any other error is passed through
+    fn in_file(self, file: &str) -> Self;
+}
+
+impl<T> XmlContext for Result<T> {
+    fn in_file(self, file: &str) -> Self {
+        self.map_err(|error| match error {
+            LexEpubError::XmlInContext {
+                position, source, ..
+            } => LexEpubError::XmlInContext {
+                file: file.to_string(),
+                position,
+                source,
+            },
+            other => other,
+        })
+    }
+}
@@ -175,8 +175,9 @@ mod ffi {
         pub fn get_cover_image_len(&mut self) -> usize {
             self.0
                 .cover_image_sync()
-                .map(|bytes| bytes.len())
-                .unwrap_or(0)
+                .ok()
+                .flatten()
+                .map_or(0, |bytes| bytes.len())
         }
 
         pub fn get_cover_image_format(
@@ -196,7 +197,7 @@ mod ffi {
             &mut self,
             to: &mut diplomat_runtime::DiplomatWrite,
         ) -> Result<(), ()> {
-            let bytes = self.0.cover_image_sync().map_err(|_| ())?;
+            let bytes = self.0.cover_image_sync().ok().flatten().ok_or(())?;
             Self::write_json(to, &bytes)
         }
     }
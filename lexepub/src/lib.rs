@@ -9,26 +9,44 @@ pub mod ffi;
 pub mod wasm;
 
 // Re-export core modules for internal use
-pub use core::chapter::{AstNode, Chapter, ChapterStream, ParsedChapter};
-pub use core::container::ContainerParser;
+pub use core::chapter::{AstNode, Chapter, ChapterStream, ParsedChapter, TextBlock, TextBlockKind};
+pub use core::chunked::ChunkedReader;
+pub use core::container::{ContainerParser, RootFile};
 pub use core::extractor::EpubExtractor;
-pub use core::html_parser::ChapterParser;
-pub use core::opf_parser::OpfParser;
+pub use core::html_parser::{
+    ChapterParser, LineEnding, MarkdownOptions, TextExtractOptions, WordCounter,
+};
+pub use core::opf_parser::{Collection, Creator, Identifier, OpfParser};
 
 // Re-export main API
 pub use epub::{extract_ast, extract_text_only, get_metadata, LexEpub};
 pub use error::{LexEpubError, Result};
 
 // Re-export metadata types
-pub use epub::{EpubMetadata, TocEntry};
+pub use epub::{
+    ArchiveOverview, ChapterError, ChapterStats, Chunk, ContentKind, EpubMetadata, ErrorAction,
+    InfoCard, LexEpubOptions, MetadataHandle, ReadingDirection, SearchHit, Section,
+    SemanticSection, SerializedBook, SerializedChapter, TocEntry, TocNode, Token, ValidationReport,
+    VocabOptions, VocabStats, INFO_CARD_THUMBNAIL_DIM, SEARCH_SNIPPET_CONTEXT,
+};
 
 /// Re-export common types
 pub mod prelude {
-    pub use crate::core::chapter::{AstNode, Chapter, ChapterStream, ParsedChapter};
+    pub use crate::core::chapter::{
+        AstNode, Chapter, ChapterStream, ParsedChapter, TextBlock, TextBlockKind,
+    };
+    pub use crate::core::container::RootFile;
     pub use crate::core::extractor::EpubExtractor;
-    pub use crate::core::html_parser::ChapterParser;
+    pub use crate::core::html_parser::{
+        ChapterParser, LineEnding, MarkdownOptions, TextExtractOptions, WordCounter,
+    };
     pub use crate::epub::LexEpub;
-    pub use crate::epub::{EpubMetadata, TocEntry};
+    pub use crate::epub::{
+        ArchiveOverview, ChapterError, ChapterStats, Chunk, ContentKind, EpubMetadata, ErrorAction,
+        InfoCard, LexEpubOptions, MetadataHandle, ReadingDirection, SearchHit, Section,
+        SemanticSection, SerializedBook, SerializedChapter, TocEntry, TocNode, Token,
+        ValidationReport, VocabOptions, VocabStats,
+    };
     pub use crate::error::{LexEpubError, Result};
 }
 
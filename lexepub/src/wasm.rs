@@ -316,7 +316,8 @@ impl WasmEpubExtractor {
                 let cover_data = extractor
                     .cover_image()
                     .await
-                    .map_err(|e| JsValue::from_str(&format!("Failed to get cover: {}", e)))?;
+                    .map_err(|e| JsValue::from_str(&format!("Failed to get cover: {}", e)))?
+                    .ok_or_else(|| JsValue::from_str("No cover image found in EPUB"))?;
 
                 Ok(Uint8Array::from(&cover_data[..]))
             }
@@ -331,7 +332,7 @@ impl WasmEpubExtractor {
             Some(extractor) => extractor
                 .cover_image()
                 .await
-                .map(|bytes| bytes.len())
+                .map(|bytes| bytes.map_or(0, |bytes| bytes.len()))
                 .map_err(|e| JsValue::from_str(&format!("Failed to get cover length: {}", e))),
             None => Err(JsValue::from_str("No EPUB loaded")),
         }
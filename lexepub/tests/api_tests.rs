@@ -65,6 +65,74 @@ mod api_tests {
         });
     }
 
+    #[test]
+    fn test_lexepub_from_chunk_stream() {
+        use futures::SinkExt;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        futures::executor::block_on(async {
+            let test_epub = Path::new("examples/epubs/test-book.epub");
+            if !test_epub.exists() {
+                return;
+            }
+
+            let data = std::fs::read(test_epub).unwrap();
+            let total_chunks = data.len().div_ceil(4096);
+            let sent = AtomicUsize::new(0);
+            let (mut tx, rx) = futures::channel::mpsc::channel::<bytes::Bytes>(1);
+
+            let producer = async {
+                for chunk in data.chunks(4096) {
+                    tx.send(bytes::Bytes::copy_from_slice(chunk)).await.unwrap();
+                    sent.fetch_add(1, Ordering::SeqCst);
+                }
+                tx.close_channel();
+            };
+            let consumer = async {
+                let mut epub = LexEpub::from_chunk_stream(rx).await.unwrap();
+                let metadata = epub.get_metadata().await.unwrap();
+                (metadata, sent.load(Ordering::SeqCst))
+            };
+
+            let ((metadata, sent_when_ready), ()) = futures::join!(consumer, producer);
+            assert!(metadata.title.is_some() || !metadata.authors.is_empty());
+            // The central directory is at the end, so nothing opens early
+            assert_eq!(sent_when_ready, total_chunks);
+
+            let expected = get_metadata(test_epub).await.unwrap();
+            assert_eq!(metadata.title, expected.title);
+        });
+    }
+
+    #[test]
+    fn test_lexepub_from_stream() {
+        use futures::TryStreamExt;
+
+        futures::executor::block_on(async {
+            let test_epub = Path::new("examples/epubs/test-book.epub");
+            if !test_epub.exists() {
+                return;
+            }
+
+            // A chunked body that can only be read forward, like an HTTP response
+            let data = std::fs::read(test_epub).unwrap();
+            let chunks: Vec<std::io::Result<Vec<u8>>> =
+                data.chunks(4096).map(|chunk| Ok(chunk.to_vec())).collect();
+            let reader = futures::stream::iter(chunks).into_async_read();
+
+            let mut epub = LexEpub::from_stream(reader).await.unwrap();
+            let metadata = epub.get_metadata().await.unwrap();
+            let expected = get_metadata(test_epub).await.unwrap();
+            assert_eq!(metadata.title, expected.title);
+
+            let mut direct = LexEpub::open(test_epub).await.unwrap();
+            assert_eq!(
+                epub.extract_text_only().await.unwrap(),
+                direct.extract_text_only().await.unwrap()
+            );
+        });
+    }
+
     #[test]
     fn test_lexepub_from_sync_reader() {
         futures::executor::block_on(async {
@@ -102,6 +170,35 @@ mod api_tests {
         });
     }
 
+    #[test]
+    fn test_cached_chapter_texts_borrow_the_cache() {
+        futures::executor::block_on(async {
+            let test_epub = Path::new("examples/epubs/test-book.epub");
+            if !test_epub.exists() {
+                return;
+            }
+
+            let mut epub = LexEpub::open(test_epub).await.unwrap();
+            assert!(epub.cached_chapter_texts().is_none());
+
+            let owned = epub.extract_text_only().await.unwrap();
+            #[cfg(feature = "lowmem")]
+            {
+                // Low-memory builds never fill the text cache
+                assert!(!owned.is_empty());
+                assert!(epub.cached_chapter_texts().is_none());
+            }
+            #[cfg(not(feature = "lowmem"))]
+            {
+                let borrowed = epub.cached_chapter_texts().unwrap();
+                assert_eq!(borrowed.len(), owned.len());
+                for (borrowed, owned) in borrowed.iter().zip(&owned) {
+                    assert_eq!(*borrowed, owned.as_str());
+                }
+            }
+        });
+    }
+
     #[test]
     fn test_extract_ast() {
         futures::executor::block_on(async {
@@ -162,6 +259,25 @@ mod api_tests {
         });
     }
 
+    #[test]
+    fn test_open_metadata_only() {
+        futures::executor::block_on(async {
+            let test_epub = Path::new("examples/epubs/test-book.epub");
+            if !test_epub.exists() {
+                return;
+            }
+
+            let mut epub = LexEpub::open(test_epub).await.unwrap();
+            let full = epub.get_metadata().await.unwrap();
+
+            let handle = LexEpub::open_metadata_only(test_epub).await.unwrap();
+            assert_eq!(
+                serde_json::to_value(handle.metadata()).unwrap(),
+                serde_json::to_value(&full).unwrap()
+            );
+        });
+    }
+
     #[test]
     fn test_multiple_operations() {
         futures::executor::block_on(async {
@@ -253,6 +369,39 @@ mod api_tests {
         });
     }
 
+    #[test]
+    fn test_to_json_round_trips() {
+        futures::executor::block_on(async {
+            let test_epub = Path::new("examples/epubs/test-book.epub");
+            if !test_epub.exists() {
+                return;
+            }
+
+            let mut epub = LexEpub::open(test_epub).await.unwrap();
+            let json = epub.to_json().await.unwrap();
+            let book = lexepub::SerializedBook::from_json(&json).unwrap();
+
+            let meta = epub.get_metadata().await.unwrap();
+            assert_eq!(book.metadata.title, meta.title);
+            assert_eq!(book.metadata.spine_hrefs, meta.spine_hrefs);
+
+            let chapters = epub.extract_ast().await.unwrap();
+            assert_eq!(book.chapters.len(), chapters.len());
+            for (saved, chapter) in book.chapters.iter().zip(&chapters) {
+                assert_eq!(saved.href, chapter.chapter_info.href);
+                assert_eq!(saved.content, chapter.content);
+                assert_eq!(saved.word_count, chapter.word_count);
+                assert_eq!(saved.char_count, chapter.char_count);
+                assert_eq!(saved.ast.is_some(), chapter.ast.is_some());
+            }
+
+            assert!(matches!(
+                lexepub::SerializedBook::from_json("{\"metadata\": 1}"),
+                Err(lexepub::LexEpubError::Serialization(_))
+            ));
+        });
+    }
+
     #[test]
     fn test_analyze_reader_matches_lexepub() {
         futures::executor::block_on(async {
@@ -279,6 +428,14 @@ mod api_tests {
                 report.metadata.as_ref().and_then(|m| m.title.clone()),
                 meta.title
             );
+
+            // Both construction paths fill the derived fields the same way
+            let report_meta = report.metadata.unwrap();
+            assert_eq!(report_meta.chapter_count, meta.chapter_count);
+            assert_eq!(meta.chapter_count, meta.spine.len());
+            assert_eq!(report_meta.has_cover, meta.has_cover);
+            assert_eq!(meta.has_cover, epub.cover_image().await.unwrap().is_some());
+            assert_eq!(report_meta.spine_hrefs, meta.spine_hrefs);
         });
     }
 
@@ -301,6 +458,7 @@ mod api_tests {
             let invalid_bytes = bytes::Bytes::new();
             let epub_result = LexEpub::from_bytes(invalid_bytes).await;
             assert!(epub_result.is_ok()); // from_bytes doesn't validate immediately
+            assert!(epub_result.unwrap().validate().await.is_err()); // validate() does
         });
     }
 
@@ -0,0 +1,71 @@
+//! Shared helpers for building small in-memory EPUB fixtures.
+//!
+//! Most tests run against the bundled books in `examples/epubs`, but some
+//! features need a very specific OPF or chapter layout. These helpers build a
+//! valid archive on the fly so those cases don't need a checked-in fixture.
+#![allow(dead_code)]
+
+use async_zip::base::write::ZipFileWriter;
+use async_zip::{Compression, ZipEntryBuilder};
+use bytes::Bytes;
+
+pub const CONTAINER_XML: &str = r#"<?xml version="1.0"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>"#;
+
+/// Build a ZIP archive from raw `(path, bytes)` entries, written in order.
+///
+/// The async writer runs on a helper thread so this can be called from inside
+/// a test's `block_on` without nesting executors.
+pub fn build_archive(entries: &[(&str, &[u8])]) -> Bytes {
+    let owned: Vec<(String, Vec<u8>)> = entries
+        .iter()
+        .map(|(name, data)| (name.to_string(), data.to_vec()))
+        .collect();
+    std::thread::spawn(move || {
+        futures::executor::block_on(async {
+            let mut writer = ZipFileWriter::new(futures::io::Cursor::new(Vec::new()));
+            for (name, data) in owned {
+                let compression = if name == "mimetype" {
+                    Compression::Stored
+                } else {
+                    Compression::Deflate
+                };
+                let entry = ZipEntryBuilder::new(name.into(), compression);
+                writer.write_entry_whole(entry, &data).await.unwrap();
+            }
+            Bytes::from(writer.close().await.unwrap().into_inner())
+        })
+    })
+    .join()
+    .unwrap()
+}
+
+/// Build an EPUB with the standard `mimetype` and container entries, an OPF
+/// at `OEBPS/content.opf`, and the given extra files (paths are relative to
+/// the archive root, e.g. `OEBPS/ch1.xhtml`).
+pub fn build_epub(opf: &str, files: &[(&str, &str)]) -> Bytes {
+    let mut entries: Vec<(&str, &[u8])> = vec![
+        ("mimetype", b"application/epub+zip"),
+        ("META-INF/container.xml", CONTAINER_XML.as_bytes()),
+        ("OEBPS/content.opf", opf.as_bytes()),
+    ];
+    for (name, data) in files {
+        entries.push((name, data.as_bytes()));
+    }
+    build_archive(&entries)
+}
+
+/// Wrap body markup in a minimal XHTML document.
+pub fn xhtml(title: &str, body: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops">
+<head><title>{title}</title></head>
+<body>{body}</body>
+</html>"#
+    )
+}
@@ -37,4 +37,50 @@ mod tests {
         let result = parser.parse_container(xml.as_bytes());
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_parse_container_picks_rootfile_by_media_type() {
+        let xml = r#"<?xml version="1.0"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="print/book.pdf" media-type="application/pdf"/>
+    <rootfile full-path="OEBPS/package.xml" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>"#;
+
+        let container = ContainerParser::new()
+            .parse_container(xml.as_bytes())
+            .unwrap();
+        assert_eq!(container.rootfile_path, "OEBPS/package.xml");
+    }
+
+    #[test]
+    fn test_parse_container_lists_every_rootfile() {
+        let xml = r#"<?xml version="1.0"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="print/book.pdf" media-type="application/pdf"/>
+    <rootfile full-path="reflow/content.opf" media-type="application/oebps-package+xml"/>
+    <rootfile full-path="fixed/content.opf" media-type="application/oebps-package+xml"/>
+    <rootfile media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>"#;
+
+        let container = ContainerParser::new()
+            .parse_container(xml.as_bytes())
+            .unwrap();
+        assert_eq!(container.rootfile_path, "reflow/content.opf");
+        let paths: Vec<_> = container
+            .rootfiles
+            .iter()
+            .map(|rootfile| rootfile.full_path.as_str())
+            .collect();
+        assert_eq!(
+            paths,
+            ["print/book.pdf", "reflow/content.opf", "fixed/content.opf"]
+        );
+        assert!(!container.rootfiles[0].is_package());
+        assert_eq!(container.rootfiles[0].media_type, "application/pdf");
+        assert!(container.rootfiles[2].is_package());
+    }
 }
@@ -94,3 +94,22 @@ fn test_epub_css_parsing() {
         _ => panic!("Expected h1,h2 style"),
     }
 }
+
+#[test]
+fn test_font_face_and_import_urls() {
+    let css = r#"
+        @import url("base.css");
+        @import 'extra.css' screen;
+        @font-face { font-family: A; src: url(fonts/A.woff2) format("woff2"), url('fonts/A.ttf'); }
+        @media print {
+            @font-face { font-family: B; src: local("B"), url("fonts/B.otf"); }
+        }
+    "#;
+    let stylesheet = Stylesheet::parse(css);
+
+    assert_eq!(stylesheet.import_urls(), vec!["base.css", "extra.css"]);
+    assert_eq!(
+        stylesheet.font_face_urls(),
+        vec!["fonts/A.woff2", "fonts/A.ttf", "fonts/B.otf"]
+    );
+}
@@ -1,3 +1,5 @@
+mod common;
+
 use bytes::Bytes;
 use lexepub::epub::LexEpub;
 
@@ -20,6 +22,7 @@ mod edge_case_tests {
             let mut epub = epub_result.unwrap();
             let result = epub.get_metadata().await;
             assert!(result.is_err());
+            assert!(epub.validate().await.is_err());
         });
     }
 
@@ -55,6 +58,90 @@ mod edge_case_tests {
         });
     }
 
+    #[test]
+    fn test_percent_encoded_and_non_ascii_hrefs_resolve() {
+        use futures::StreamExt;
+
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Names</dc:title></metadata>
+  <manifest>
+    <item id="c1" href="chapter%201.xhtml" media-type="application/xhtml+xml"/>
+    <item id="c2" href="章节.xhtml" media-type="application/xhtml+xml"/>
+    <item id="c3" href="章%20two.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="c1"/><itemref idref="c2"/><itemref idref="c3"/></spine>
+</package>"#;
+            let bytes = common::build_epub(
+                opf,
+                &[
+                    (
+                        "OEBPS/chapter 1.xhtml",
+                        &common::xhtml("", "<p>Spaced.</p>"),
+                    ),
+                    ("OEBPS/章节.xhtml", &common::xhtml("", "<p>Unicode.</p>")),
+                    // Some packagers store the entry name percent-encoded
+                    (
+                        "OEBPS/%E7%AB%A0 two.xhtml",
+                        &common::xhtml("", "<p>Stored.</p>"),
+                    ),
+                ],
+            );
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let chapters = epub.extract_text_only().await.unwrap();
+            let texts: Vec<&str> = chapters.iter().map(|c| c.trim()).collect();
+            assert_eq!(texts, ["Spaced.", "Unicode.", "Stored."]);
+
+            let mut stream = epub.extract_chapters_stream().await.unwrap();
+            let mut streamed = Vec::new();
+            while let Some(chapter) = stream.next().await {
+                streamed.push(chapter.unwrap().content.trim().to_string());
+            }
+            assert_eq!(streamed, ["Spaced.", "Unicode.", "Stored."]);
+
+            let full = epub.extract_ast().await.unwrap();
+            assert_eq!(full[0].chapter_info.href, "OEBPS/chapter 1.xhtml");
+        });
+    }
+
+    #[test]
+    fn test_relative_hrefs_with_dot_segments_resolve() {
+        futures::executor::block_on(async {
+            // The OPF lives in OEBPS/, but content sits beside it at the root
+            let opf = r#"<?xml version="1.0"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Dots</dc:title></metadata>
+  <manifest>
+    <item id="c1" href="../text/./ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="c2" href="./sub/../ch2.xhtml" media-type="application/xhtml+xml"/>
+    <item id="img" href="../images/x.png" media-type="image/png" properties="cover-image"/>
+  </manifest>
+  <spine><itemref idref="c1"/><itemref idref="c2"/></spine>
+</package>"#;
+            let bytes = common::build_epub(
+                opf,
+                &[
+                    ("text/ch1.xhtml", &common::xhtml("", "<p>Up one.</p>")),
+                    ("OEBPS/ch2.xhtml", &common::xhtml("", "<p>Down and up.</p>")),
+                    ("images/x.png", "PNG"),
+                ],
+            );
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let chapters = epub.extract_ast().await.unwrap();
+            let hrefs: Vec<&str> = chapters
+                .iter()
+                .map(|c| c.chapter_info.href.as_str())
+                .collect();
+            assert_eq!(hrefs, ["text/ch1.xhtml", "OEBPS/ch2.xhtml"]);
+            assert_eq!(chapters[0].content.trim(), "Up one.");
+            assert_eq!(chapters[1].content.trim(), "Down and up.");
+            assert_eq!(epub.cover_image().await.unwrap().unwrap(), b"PNG");
+        });
+    }
+
     #[test]
     fn test_epub_with_empty_chapters() {
         futures::executor::block_on(async {
@@ -179,13 +266,85 @@ mod edge_case_tests {
     fn test_epub_with_multiple_rootfiles() {
         futures::executor::block_on(async {
             // Test EPUB with multiple rootfiles in container.xml
-            // TODO: This would require a mock EPUB
             let test_epub = std::path::Path::new("examples/epubs/test-book.epub");
             if test_epub.exists() {
                 let mut epub = LexEpub::open(test_epub).await.unwrap();
                 let result = epub.get_metadata().await;
                 assert!(result.is_ok());
             }
+
+            let container = r#"<?xml version="1.0"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="reflow/content.opf" media-type="application/oebps-package+xml"/>
+    <rootfile full-path="fixed/content.opf" media-type="application/oebps-package+xml"/>
+    <rootfile full-path="print/book.pdf" media-type="application/pdf"/>
+  </rootfiles>
+</container>"#;
+            let package = |title: &str| {
+                format!(
+                    r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>{title}</dc:title></metadata>
+  <manifest><item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/></manifest>
+  <spine><itemref idref="ch1"/></spine>
+</package>"#
+                )
+            };
+            let reflow = package("Reflowable");
+            let fixed = package("Fixed");
+            let reflow_ch = common::xhtml("", "<p>Reflowable text.</p>");
+            let fixed_ch = common::xhtml("", "<p>Fixed page.</p>");
+            let bytes = common::build_archive(&[
+                ("mimetype", b"application/epub+zip"),
+                ("META-INF/container.xml", container.as_bytes()),
+                ("reflow/content.opf", reflow.as_bytes()),
+                ("reflow/ch1.xhtml", reflow_ch.as_bytes()),
+                ("fixed/content.opf", fixed.as_bytes()),
+                ("fixed/ch1.xhtml", fixed_ch.as_bytes()),
+                ("print/book.pdf", b"%PDF-1.4"),
+            ]);
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let renditions = epub.renditions().await.unwrap();
+            assert_eq!(renditions.len(), 3);
+            assert_eq!(renditions[1].full_path, "fixed/content.opf");
+
+            // The first package rootfile is the default
+            let metadata = epub.get_metadata().await.unwrap();
+            assert_eq!(metadata.title.as_deref(), Some("Reflowable"));
+            let texts = epub.extract_text_only().await.unwrap();
+            assert!(texts[0].contains("Reflowable text."));
+
+            epub.select_rendition(1).await.unwrap();
+            let metadata = epub.get_metadata().await.unwrap();
+            assert_eq!(metadata.title.as_deref(), Some("Fixed"));
+            assert_eq!(metadata.spine_hrefs, vec!["fixed/ch1.xhtml".to_string()]);
+            let texts = epub.extract_text_only().await.unwrap();
+            assert!(texts[0].contains("Fixed page."));
+
+            assert!(matches!(
+                epub.select_rendition(2).await,
+                Err(lexepub::LexEpubError::InvalidArgument(_))
+            ));
+            assert!(matches!(
+                epub.select_rendition(3).await,
+                Err(lexepub::LexEpubError::InvalidArgument(_))
+            ));
+
+            // validate() checks the selected rendition, not the default one
+            let broken = reflow.replace(r#"idref="ch1""#, r#"idref="gone""#);
+            let bytes = common::build_archive(&[
+                ("mimetype", b"application/epub+zip"),
+                ("META-INF/container.xml", container.as_bytes()),
+                ("reflow/content.opf", broken.as_bytes()),
+                ("fixed/content.opf", fixed.as_bytes()),
+                ("fixed/ch1.xhtml", fixed_ch.as_bytes()),
+            ]);
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            assert!(epub.validate().await.is_err());
+            epub.select_rendition(1).await.unwrap();
+            epub.validate().await.unwrap();
         });
     }
 
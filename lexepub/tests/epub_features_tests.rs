@@ -1,3 +1,5 @@
+mod common;
+
 use futures::StreamExt;
 use lexepub::epub::{extract_ast, extract_text_only, get_metadata, LexEpub};
 use std::path::Path;
@@ -221,6 +223,54 @@ mod epub_feature_tests {
         });
     }
 
+    #[test]
+    fn test_chapter_stats_sum_to_totals() {
+        futures::executor::block_on(async {
+            for &epub_path in &existing_epubs() {
+                // Fresh instances so neither side reuses the other's caches
+                let mut epub = LexEpub::open(epub_path).await.unwrap();
+                let stats = epub.chapter_stats().await.unwrap();
+                let mut fresh = LexEpub::open(epub_path).await.unwrap();
+                let texts = fresh.extract_text_only().await.unwrap();
+                assert_eq!(stats.len(), texts.len(), "{}", epub_path);
+                assert!(stats.iter().all(|s| !s.href.is_empty()), "{}", epub_path);
+
+                let words: usize = stats.iter().map(|s| s.word_count).sum();
+                let chars: usize = stats.iter().map(|s| s.char_count).sum();
+                assert_eq!(
+                    words,
+                    fresh.total_word_count().await.unwrap(),
+                    "{}",
+                    epub_path
+                );
+                assert_eq!(
+                    chars,
+                    fresh.total_char_count().await.unwrap(),
+                    "{}",
+                    epub_path
+                );
+                assert_eq!(
+                    words,
+                    epub.total_word_count().await.unwrap(),
+                    "{}",
+                    epub_path
+                );
+                assert_eq!(
+                    chars,
+                    epub.total_char_count().await.unwrap(),
+                    "{}",
+                    epub_path
+                );
+                // Counting does not retain chapter text
+                assert!(epub.cached_chapter_texts().is_none(), "{}", epub_path);
+
+                let ast = epub.extract_ast().await.unwrap();
+                assert_eq!(epub.chapter_stats().await.unwrap(), stats, "{}", epub_path);
+                assert_eq!(ast.len(), stats.len(), "{}", epub_path);
+            }
+        });
+    }
+
     // AST Extraction Tests
     #[test]
     fn test_ast_extraction_all_epubs() {
@@ -430,9 +480,37 @@ mod epub_feature_tests {
                         epub_path,
                         version
                     );
+                    let expected = if version.starts_with('2') { 2 } else { 3 };
+                    assert_eq!(metadata.epub_version_major(), Some(expected));
+                } else {
+                    assert_eq!(metadata.epub_version_major(), None);
                 }
             }
         });
+
+        let package = |version: &str| {
+            format!(
+                r#"<?xml version="1.0"?>
+<package xmlns="http://www.idpf.org/2007/opf" {}>
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>V</dc:title></metadata>
+  <manifest/>
+  <spine/>
+</package>"#,
+                version
+            )
+        };
+        for (attribute, version, major) in [
+            (r#"version="2.0""#, Some("2.0"), Some(2)),
+            (r#"version="3.0""#, Some("3.0"), Some(3)),
+            (r#"version="bogus""#, Some("bogus"), None),
+            ("", None, None),
+        ] {
+            let data = common::build_epub(&package(attribute), &[]);
+            let mut epub = futures::executor::block_on(LexEpub::from_bytes(data)).unwrap();
+            let metadata = futures::executor::block_on(epub.get_metadata()).unwrap();
+            assert_eq!(metadata.version.as_deref(), version);
+            assert_eq!(metadata.epub_version_major(), major);
+        }
     }
 
     // Cover Detection Tests
@@ -609,4 +687,3386 @@ mod epub_feature_tests {
             }
         });
     }
+
+    // Scripted Content Tests
+    #[test]
+    fn test_scripted_chapter_flagged_and_script_stripped() {
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>Scripted</dc:title>
+    <dc:language>en</dc:language>
+  </metadata>
+  <manifest>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml" properties="scripted"/>
+    <item id="ch2" href="ch2.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine>
+    <itemref idref="ch1"/>
+    <itemref idref="ch2"/>
+  </spine>
+</package>"#;
+            let ch1 = common::xhtml(
+                "One",
+                "<p>Visible prose.</p><script>document.write('secret payload');</script>",
+            );
+            let ch2 = common::xhtml("Two", "<p>Plain chapter.</p>");
+            let bytes =
+                common::build_epub(opf, &[("OEBPS/ch1.xhtml", &ch1), ("OEBPS/ch2.xhtml", &ch2)]);
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let chapters = epub.extract_ast().await.unwrap();
+            assert_eq!(chapters.len(), 2);
+            assert!(chapters[0].is_scripted);
+            assert!(!chapters[1].is_scripted);
+            assert!(chapters[0].content.contains("Visible prose."));
+            assert!(!chapters[0].content.contains("secret payload"));
+
+            let texts = epub.extract_text_only().await.unwrap();
+            assert!(!texts[0].contains("secret payload"));
+
+            // The sanitizer drops script elements from the AST as well
+            let parsed = lexepub::ChapterParser::new()
+                .with_ast()
+                .parse_chapter(lexepub::Chapter::new(
+                    "ch1.xhtml".to_string(),
+                    "ch1".to_string(),
+                    ch1.into_bytes(),
+                ))
+                .unwrap();
+            let mut ast = parsed.ast.unwrap();
+            ast.sanitize();
+            let json = serde_json::to_string(&ast).unwrap();
+            assert!(!json.contains("secret payload"));
+            assert!(json.contains("Visible prose."));
+        });
+    }
+
+    #[test]
+    fn test_sanitize_drops_only_event_handlers() {
+        let parsed = lexepub::ChapterParser::new()
+            .with_ast()
+            .parse_chapter(lexepub::Chapter::new(
+                "ch1.xhtml".to_string(),
+                "ch1".to_string(),
+                common::xhtml(
+                    "",
+                    r#"<p onclick="steal()" ONLOAD="steal()" onix-id="9780000000001">Text</p>"#,
+                )
+                .into_bytes(),
+            ))
+            .unwrap();
+        let mut ast = parsed.ast.unwrap();
+        ast.sanitize();
+        let html = ast.to_html();
+        assert!(!html.contains("steal()"));
+        assert!(html.contains(r#"onix-id="9780000000001""#));
+    }
+
+    #[test]
+    fn test_streams_honour_scripted_manifest_property() {
+        use futures::StreamExt;
+
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <manifest>
+    <item id="form" href="form.xhtml" media-type="application/xhtml+xml" properties="scripted"/>
+    <item id="plain" href="plain.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="form"/><itemref idref="plain"/></spine>
+</package>"#;
+            // Scripted through an external file, so no inline <script> tag
+            let form = common::xhtml("", "<form><input type=\"text\"/></form>");
+            let plain = common::xhtml("", "<p>Plain</p>");
+            let bytes = common::build_epub(
+                opf,
+                &[("OEBPS/form.xhtml", &form), ("OEBPS/plain.xhtml", &plain)],
+            );
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+
+            let eager: Vec<bool> = epub
+                .extract_ast()
+                .await
+                .unwrap()
+                .iter()
+                .map(|c| c.is_scripted)
+                .collect();
+            assert_eq!(eager, [true, false]);
+            for stream in [
+                epub.extract_chapters_stream().await.unwrap(),
+                epub.extract_ast_stream().await.unwrap(),
+            ] {
+                let streamed: Vec<bool> = stream.map(|c| c.unwrap().is_scripted).collect().await;
+                assert_eq!(streamed, eager);
+            }
+        });
+    }
+
+    // Resource Serving Tests
+    const RESOURCE_OPF: &str = r#"<?xml version="1.0"?>
+<package version="3.0" unique-identifier="pub-id" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:identifier id="pub-id">urn:uuid:12345678-90ab-cdef-1234-567890abcdef</dc:identifier>
+    <dc:title>Resources</dc:title>
+    <dc:language>en</dc:language>
+  </metadata>
+  <manifest>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="css" href="styles/main.css" media-type="text/css"/>
+    <item id="font" href="fonts/body.otf" media-type="font/otf"/>
+  </manifest>
+  <spine>
+    <itemref idref="ch1"/>
+  </spine>
+</package>"#;
+
+    #[test]
+    fn test_serve_css_with_media_type() {
+        futures::executor::block_on(async {
+            let css = "p { color: red; }";
+            let ch1 = common::xhtml("One", "<p>Styled</p>");
+            let bytes = common::build_epub(
+                RESOURCE_OPF,
+                &[
+                    ("OEBPS/ch1.xhtml", &ch1),
+                    ("OEBPS/styles/main.css", css),
+                    ("OEBPS/notes.txt", "undeclared"),
+                ],
+            );
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let (data, media_type) = epub.serve("OEBPS/styles/main.css").await.unwrap();
+            assert_eq!(data, css.as_bytes());
+            assert_eq!(media_type, "text/css");
+
+            // Not in the manifest: falls back to the extension guess
+            let (_, media_type) = epub.serve("OEBPS/notes.txt").await.unwrap();
+            assert_eq!(media_type, "text/plain");
+        });
+    }
+
+    #[test]
+    fn test_serve_deobfuscates_fonts() {
+        futures::executor::block_on(async {
+            let original: Vec<u8> = (0..2048u32).map(|i| (i % 251) as u8).collect();
+            let mut obfuscated = original.clone();
+            assert!(lexepub::core::encryption::deobfuscate_font(
+                &mut obfuscated,
+                lexepub::core::encryption::IDPF_OBFUSCATION,
+                "urn:uuid:12345678-90ab-cdef-1234-567890abcdef",
+            ));
+            assert_ne!(obfuscated, original);
+
+            let encryption_xml = r#"<?xml version="1.0"?>
+<encryption xmlns="urn:oasis:names:tc:opendocument:xmlns:container"
+            xmlns:enc="http://www.w3.org/2001/04/xmlenc#">
+  <enc:EncryptedData>
+    <enc:EncryptionMethod Algorithm="http://www.idpf.org/2008/embedding"/>
+    <enc:CipherData>
+      <enc:CipherReference URI="OEBPS/fonts/body.otf"/>
+    </enc:CipherData>
+  </enc:EncryptedData>
+</encryption>"#;
+            let ch1 = common::xhtml("One", "<p>Styled</p>");
+            let bytes = common::build_archive(&[
+                ("mimetype", b"application/epub+zip"),
+                ("META-INF/container.xml", common::CONTAINER_XML.as_bytes()),
+                ("META-INF/encryption.xml", encryption_xml.as_bytes()),
+                ("OEBPS/content.opf", RESOURCE_OPF.as_bytes()),
+                ("OEBPS/ch1.xhtml", ch1.as_bytes()),
+                ("OEBPS/fonts/body.otf", &obfuscated),
+            ]);
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let (data, media_type) = epub.serve("OEBPS/fonts/body.otf").await.unwrap();
+            assert_eq!(media_type, "font/otf");
+            assert_eq!(data, original);
+            assert_eq!(
+                epub.resource_by_href("OEBPS/fonts/body.otf").await.unwrap(),
+                original
+            );
+            assert_eq!(epub.resource_by_id("font").await.unwrap(), original);
+
+            // Font obfuscation alone is not DRM
+            assert_eq!(epub.extract_text_only().await.unwrap(), vec!["Styled"]);
+        });
+    }
+
+    // Section Tests
+    #[test]
+    fn test_sections_span_file_boundaries() {
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>Sections</dc:title>
+    <dc:language>en</dc:language>
+  </metadata>
+  <manifest>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch2" href="ch2.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch3" href="ch3.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine>
+    <itemref idref="ch1"/>
+    <itemref idref="ch2"/>
+    <itemref idref="ch3"/>
+  </spine>
+</package>"#;
+            let ch1 = common::xhtml("One", "<h1>Beginnings</h1><p>From the first file.</p>");
+            let ch2 = common::xhtml("Two", "<p>Continued in the second file.</p>");
+            let ch3 = common::xhtml("Three", "<h2>Aftermath</h2><p>Third file.</p>");
+            let bytes = common::build_epub(
+                opf,
+                &[
+                    ("OEBPS/ch1.xhtml", &ch1),
+                    ("OEBPS/ch2.xhtml", &ch2),
+                    ("OEBPS/ch3.xhtml", &ch3),
+                ],
+            );
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let sections = epub.sections().await.unwrap();
+            assert_eq!(sections.len(), 2);
+
+            assert_eq!(sections[0].title, "Beginnings");
+            assert_eq!(sections[0].level, 1);
+            assert!(sections[0].text.contains("From the first file."));
+            assert!(sections[0].text.contains("Continued in the second file."));
+
+            assert_eq!(sections[1].title, "Aftermath");
+            assert_eq!(sections[1].level, 2);
+            assert_eq!(sections[1].text, "Third file.");
+        });
+    }
+
+    // Spine Navigation Tests
+    #[test]
+    fn test_spine_index_id_href_round_trip() {
+        futures::executor::block_on(async {
+            for &epub_path in &existing_epubs() {
+                let mut epub = LexEpub::open(epub_path).await.unwrap();
+                let spine_len = epub.get_metadata().await.unwrap().spine.len();
+
+                for index in 0..spine_len {
+                    let id = epub.spine_item_id(index).await.unwrap().unwrap();
+                    assert_eq!(epub.spine_index_of_id(&id).await.unwrap(), Some(index));
+
+                    let href = epub.item_href(&id).await.unwrap().unwrap();
+                    assert_eq!(
+                        epub.spine_index_of_href(&href).await.unwrap(),
+                        Some(index),
+                        "href {} did not map back to spine index {} in {}",
+                        href,
+                        index,
+                        epub_path
+                    );
+                }
+
+                assert_eq!(epub.spine_item_id(spine_len).await.unwrap(), None);
+            }
+        });
+    }
+
+    #[test]
+    fn test_resource_by_id_and_href() {
+        let opf = r#"<?xml version="1.0"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Assets</dc:title></metadata>
+  <manifest>
+    <item id="c1" href="text/c1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="css" href="styles/main.css" media-type="text/css"/>
+    <item id="font" href="fonts/serif%20bold.otf" media-type="font/otf"/>
+    <item id="gone" href="fonts/gone.otf" media-type="font/otf"/>
+    <item id="art" href="images/cover%20art.bin" media-type="image/png"/>
+  </manifest>
+  <spine><itemref idref="c1"/></spine>
+</package>"#;
+        let data = common::build_epub(
+            opf,
+            &[
+                ("OEBPS/text/c1.xhtml", &common::xhtml("", "<p>Body</p>")),
+                ("OEBPS/styles/main.css", "p { color: red; }"),
+                ("OEBPS/fonts/serif bold.otf", "OTTO"),
+                ("OEBPS/images/cover art.bin", "PNG"),
+            ],
+        );
+
+        futures::executor::block_on(async {
+            let mut epub = LexEpub::from_bytes(data).await.unwrap();
+            assert_eq!(
+                epub.resource_by_id("css").await.unwrap(),
+                b"p { color: red; }"
+            );
+            assert_eq!(epub.resource_by_id("font").await.unwrap(), b"OTTO");
+            assert_eq!(
+                epub.resource_by_href("OEBPS/text/../styles/main.css#x")
+                    .await
+                    .unwrap(),
+                b"p { color: red; }"
+            );
+            assert_eq!(
+                epub.resource_by_href("OEBPS/fonts/serif%20bold.otf")
+                    .await
+                    .unwrap(),
+                b"OTTO"
+            );
+            // serve decodes too, so the manifest media type still applies
+            let (bytes, media_type) = epub.serve("OEBPS/images/cover%20art.bin").await.unwrap();
+            assert_eq!(bytes, b"PNG");
+            assert_eq!(media_type, "image/png");
+
+            match epub.resource_by_id("nope").await {
+                Err(lexepub::LexEpubError::MissingFile(message)) => {
+                    assert!(message.contains("'nope'"))
+                }
+                other => panic!("expected MissingFile, got {:?}", other),
+            }
+            assert!(matches!(
+                epub.resource_by_id("gone").await,
+                Err(lexepub::LexEpubError::MissingFile(_))
+            ));
+            match epub.resource_by_href("OEBPS/missing.png").await {
+                Err(lexepub::LexEpubError::MissingFile(message)) => {
+                    assert!(message.contains("'OEBPS/missing.png'"))
+                }
+                other => panic!("expected MissingFile, got {:?}", other),
+            }
+        });
+    }
+
+    // Annotated Text Tests
+    #[test]
+    fn test_annotated_text_has_title_lines() {
+        futures::executor::block_on(async {
+            for &epub_path in &existing_epubs() {
+                let mut epub = LexEpub::open(epub_path).await.unwrap();
+                let toc = epub.get_toc().await.unwrap();
+                let text = epub.to_annotated_text().await.unwrap();
+                let lines: Vec<&str> = text.lines().collect();
+
+                for entry in &toc {
+                    assert!(
+                        lines.contains(&entry.title.as_str()),
+                        "title {:?} is not its own line in {}",
+                        entry.title,
+                        epub_path
+                    );
+                }
+            }
+        });
+    }
+
+    // Chapter Fingerprint Tests
+    #[test]
+    fn test_chapter_fingerprints_track_changes() {
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Fingerprints</dc:title></metadata>
+  <manifest>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch2" href="ch2.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch3" href="ch3.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine>
+    <itemref idref="ch1"/>
+    <itemref idref="ch2"/>
+    <itemref idref="ch3"/>
+  </spine>
+</package>"#;
+            let ch1 = common::xhtml("One", "<p>First.</p>");
+            let ch2 = common::xhtml("Two", "<p>Second.</p>");
+            let ch2_edited = common::xhtml("Two", "<p>Second, revised.</p>");
+            let ch3 = common::xhtml("Three", "<p>Third.</p>");
+            let original = common::build_epub(
+                opf,
+                &[
+                    ("OEBPS/ch1.xhtml", &ch1),
+                    ("OEBPS/ch2.xhtml", &ch2),
+                    ("OEBPS/ch3.xhtml", &ch3),
+                ],
+            );
+            let edited = common::build_epub(
+                opf,
+                &[
+                    ("OEBPS/ch1.xhtml", &ch1),
+                    ("OEBPS/ch2.xhtml", &ch2_edited),
+                    ("OEBPS/ch3.xhtml", &ch3),
+                ],
+            );
+
+            let mut first = LexEpub::from_bytes(original.clone()).await.unwrap();
+            let mut again = LexEpub::from_bytes(original).await.unwrap();
+            let mut changed = LexEpub::from_bytes(edited).await.unwrap();
+
+            let before = first.chapter_fingerprints().await.unwrap();
+            assert_eq!(before.len(), 3);
+            assert_eq!(before, again.chapter_fingerprints().await.unwrap());
+
+            let after = changed.chapter_fingerprints().await.unwrap();
+            assert_eq!(before[0], after[0]);
+            assert_ne!(before[1], after[1]);
+            assert_eq!(before[2], after[2]);
+        });
+    }
+
+    // Chapter Font Tests
+    #[test]
+    fn test_chapter_fonts_from_linked_css() {
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Fonts</dc:title></metadata>
+  <manifest>
+    <item id="ch1" href="text/ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="css" href="styles/book.css" media-type="text/css"/>
+    <item id="serif" href="fonts/Serif.otf" media-type="font/otf"/>
+  </manifest>
+  <spine>
+    <itemref idref="ch1"/>
+  </spine>
+</package>"#;
+            let css = r#"@font-face {
+  font-family: "Serif";
+  src: url("../fonts/Serif.otf") format("opentype"), local("Georgia");
+}
+@font-face {
+  font-family: "Remote";
+  src: url(https://example.com/remote.woff2);
+}
+body { font-family: "Serif"; }"#;
+            let ch1 = r#"<?xml version="1.0" encoding="UTF-8"?>
+<html xmlns="http://www.w3.org/1999/xhtml">
+<head><title>One</title><link rel="stylesheet" type="text/css" href="../styles/book.css"/></head>
+<body><p>Styled text.</p></body>
+</html>"#;
+            let bytes = common::build_epub(
+                opf,
+                &[
+                    ("OEBPS/text/ch1.xhtml", ch1),
+                    ("OEBPS/styles/book.css", css),
+                    ("OEBPS/fonts/Serif.otf", "OTTO"),
+                ],
+            );
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let fonts = epub.chapter_fonts(0).await.unwrap();
+            assert_eq!(fonts, vec!["OEBPS/fonts/Serif.otf".to_string()]);
+
+            let (data, media_type) = epub.serve(&fonts[0]).await.unwrap();
+            assert_eq!(data, b"OTTO");
+            assert_eq!(media_type, "font/otf");
+
+            assert!(epub.chapter_fonts(1).await.is_err());
+        });
+    }
+
+    // Reading Direction Tests
+    #[test]
+    fn test_rtl_fixed_layout_display_order() {
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf" prefix="rendition: http://www.idpf.org/vocab/rendition/#">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>Manga</dc:title>
+    <meta property="rendition:layout">pre-paginated</meta>
+  </metadata>
+  <manifest>
+    <item id="p1" href="p1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="p2" href="p2.xhtml" media-type="application/xhtml+xml"/>
+    <item id="p3" href="p3.xhtml" media-type="application/xhtml+xml"/>
+    <item id="p4" href="p4.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine page-progression-direction="rtl">
+    <itemref idref="p1"/>
+    <itemref idref="p2"/>
+    <itemref idref="p3"/>
+    <itemref idref="p4"/>
+  </spine>
+</package>"#;
+            let pages: Vec<(String, String)> = (1..=4)
+                .map(|n| {
+                    (
+                        format!("OEBPS/p{}.xhtml", n),
+                        common::xhtml(&format!("Page {}", n), "<p>panel</p>"),
+                    )
+                })
+                .collect();
+            let files: Vec<(&str, &str)> = pages
+                .iter()
+                .map(|(name, body)| (name.as_str(), body.as_str()))
+                .collect();
+            let mut epub = LexEpub::from_bytes(common::build_epub(opf, &files))
+                .await
+                .unwrap();
+
+            assert_eq!(
+                epub.reading_direction().await.unwrap(),
+                lexepub::ReadingDirection::Rtl
+            );
+            assert!(epub.is_fixed_layout().await.unwrap());
+
+            let logical: Vec<usize> = (0..4).collect();
+            let display = epub.spine_display_order().await.unwrap();
+            assert_eq!(display, vec![1, 0, 3, 2]);
+            assert_ne!(display, logical);
+
+            // Logical order is untouched
+            assert_eq!(epub.spine_item_id(0).await.unwrap().as_deref(), Some("p1"));
+        });
+    }
+
+    // Encoding Report Tests
+    #[test]
+    fn test_encoding_report_bundled_books_are_utf8() {
+        futures::executor::block_on(async {
+            for &epub_path in &existing_epubs() {
+                let mut epub = LexEpub::open(epub_path).await.unwrap();
+                let report = epub.encoding_report().await.unwrap();
+                assert!(!report.is_empty());
+                for (href, encoding, confidence) in &report {
+                    assert_eq!(encoding, "UTF-8", "{} in {}", href, epub_path);
+                    assert!(*confidence >= 0.9, "{} in {}", href, epub_path);
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn test_detect_encoding_flags_mislabelled_bytes() {
+        use lexepub::core::encoding::detect_encoding;
+
+        // Latin-1 bytes in a document that claims UTF-8
+        let latin1 = b"<?xml version=\"1.0\" encoding=\"utf-8\"?><p>caf\xe9</p>";
+        let guess = detect_encoding(latin1);
+        assert_eq!(guess.encoding, "WINDOWS-1252");
+        assert!(guess.confidence < 0.5);
+
+        let declared = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><p>caf\xe9</p>";
+        assert_eq!(detect_encoding(declared).encoding, "ISO-8859-1");
+    }
+
+    // Token Stream Tests
+    #[test]
+    fn test_tokens_positions_match_chapter_text() {
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Tokens</dc:title></metadata>
+  <manifest>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch2" href="ch2.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine>
+    <itemref idref="ch1"/>
+    <itemref idref="ch2"/>
+  </spine>
+</package>"#;
+            let ch1 = r#"<html xmlns="http://www.w3.org/1999/xhtml"><body><p>— “Don’t go,” she said.</p></body></html>"#;
+            let ch2 =
+                r#"<html xmlns="http://www.w3.org/1999/xhtml"><body><p>Café 42</p></body></html>"#;
+            let bytes =
+                common::build_epub(opf, &[("OEBPS/ch1.xhtml", ch1), ("OEBPS/ch2.xhtml", ch2)]);
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let texts = epub.extract_text_only().await.unwrap();
+            let tokens = epub.tokens().await.unwrap();
+
+            let first = &tokens[0];
+            assert_eq!(first.text, "Don’t");
+            assert_eq!(first.chapter_index, 0);
+            assert_eq!(
+                first.char_offset,
+                texts[0].chars().position(|c| c == 'D').unwrap()
+            );
+
+            let words: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+            assert_eq!(words, vec!["Don’t", "go", "she", "said", "Café", "42"]);
+            assert_eq!(tokens[4].chapter_index, 1);
+            assert_eq!(tokens[4].char_offset, 0);
+
+            for token in &tokens {
+                let text: String = texts[token.chapter_index]
+                    .chars()
+                    .skip(token.char_offset)
+                    .take(token.text.chars().count())
+                    .collect();
+                assert_eq!(text, token.text);
+            }
+        });
+    }
+
+    #[test]
+    fn test_tokens_split_cjk_and_keep_spine_indices() {
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <manifest>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch2" href="ch2.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch3" href="ch3.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine>
+    <itemref idref="ch1"/><itemref idref="ch2"/><itemref idref="ch3"/>
+  </spine>
+</package>"#;
+            let ch1 = common::xhtml("One", "<p>你好世界。</p>");
+            let ch3 = common::xhtml("Three", "<p>Third page</p>");
+            // ch2 is missing from the archive and gets skipped
+            let bytes =
+                common::build_epub(opf, &[("OEBPS/ch1.xhtml", &ch1), ("OEBPS/ch3.xhtml", &ch3)]);
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let tokens = epub.tokens().await.unwrap();
+
+            let words: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+            assert_eq!(words, vec!["你", "好", "世", "界", "Third", "page"]);
+            let offsets: Vec<usize> = tokens[..4].iter().map(|t| t.char_offset).collect();
+            assert_eq!(offsets, vec![0, 1, 2, 3]);
+
+            let hits = epub.search("Third", true).await.unwrap();
+            assert_eq!(tokens[4].chapter_index, 2);
+            assert_eq!(tokens[4].chapter_index, hits[0].chapter_index);
+        });
+    }
+
+    // Href Subset Extraction Tests
+    #[test]
+    fn test_extract_hrefs_matches_full_extraction() {
+        futures::executor::block_on(async {
+            for &epub_path in &existing_epubs() {
+                let mut epub = LexEpub::open(epub_path).await.unwrap();
+                let full = epub.extract_text_only().await.unwrap();
+                let toc = epub.get_toc().await.unwrap();
+                if toc.len() < 2 {
+                    continue;
+                }
+
+                // Last chapter first, to check the requested order is kept
+                let picks = [toc.len() - 1, 0];
+                let hrefs: Vec<String> =
+                    picks.iter().map(|&i| toc[i].chapter_href.clone()).collect();
+                let subset = epub.extract_hrefs(&hrefs).await.unwrap();
+
+                assert_eq!(subset.len(), picks.len());
+                for ((href, text), &index) in subset.iter().zip(picks.iter()) {
+                    assert_eq!(href, &toc[index].chapter_href);
+                    assert_eq!(text, &full[index], "{} in {}", href, epub_path);
+                }
+
+                let missing = epub
+                    .extract_hrefs(&["OEBPS/does-not-exist.xhtml".to_string()])
+                    .await;
+                assert!(matches!(
+                    missing,
+                    Err(lexepub::LexEpubError::MissingFile(_))
+                ));
+            }
+        });
+    }
+
+    #[test]
+    fn test_extract_hrefs_rejects_non_spine_items() {
+        use lexepub::LexEpubError;
+
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Subset</dc:title></metadata>
+  <manifest>
+    <item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="css" href="style.css" media-type="text/css"/>
+  </manifest>
+  <spine><itemref idref="ch1"/></spine>
+</package>"#;
+            let bytes = common::build_epub(
+                opf,
+                &[
+                    ("OEBPS/nav.xhtml", &common::xhtml("", "<p>Contents</p>")),
+                    ("OEBPS/ch1.xhtml", &common::xhtml("", "<p>Chapter one.</p>")),
+                    ("OEBPS/style.css", "p { margin: 0; }"),
+                ],
+            );
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let subset = epub
+                .extract_hrefs(&["ch1.xhtml".to_string()])
+                .await
+                .unwrap();
+            assert_eq!(subset[0].1, "Chapter one.");
+
+            for href in ["style.css", "OEBPS/nav.xhtml"] {
+                assert!(matches!(
+                    epub.extract_hrefs(&[href.to_string()]).await,
+                    Err(LexEpubError::InvalidArgument(_))
+                ));
+            }
+        });
+    }
+
+    // Content Kind Tests
+    fn picture_book(layout_meta: &str, direction: &str) -> bytes::Bytes {
+        let opf = format!(
+            r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>Pictures</dc:title>
+    {layout_meta}
+  </metadata>
+  <manifest>
+    <item id="p1" href="p1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="p2" href="p2.xhtml" media-type="application/xhtml+xml"/>
+    <item id="p3" href="p3.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine page-progression-direction="{direction}">
+    <itemref idref="p1"/>
+    <itemref idref="p2"/>
+    <itemref idref="p3"/>
+  </spine>
+</package>"#
+        );
+        let pages: Vec<(String, String)> = (1..=3)
+            .map(|n| {
+                (
+                    format!("OEBPS/p{}.xhtml", n),
+                    common::xhtml(
+                        &format!("Page {}", n),
+                        &format!(r#"<div><img src="images/p{}.jpg" alt=""/></div>"#, n),
+                    ),
+                )
+            })
+            .collect();
+        let files: Vec<(&str, &str)> = pages
+            .iter()
+            .map(|(name, body)| (name.as_str(), body.as_str()))
+            .collect();
+        common::build_epub(&opf, &files)
+    }
+
+    #[test]
+    fn test_content_kind_classification() {
+        use lexepub::ContentKind;
+
+        futures::executor::block_on(async {
+            let test_book = Path::new("examples/epubs/test-book.epub");
+            if test_book.exists() {
+                let mut epub = LexEpub::open(test_book).await.unwrap();
+                assert_eq!(epub.content_kind().await.unwrap(), ContentKind::Reflowable);
+            }
+
+            let mut images = LexEpub::from_bytes(picture_book("", "ltr")).await.unwrap();
+            assert_eq!(images.content_kind().await.unwrap(), ContentKind::ImageOnly);
+
+            let fixed = r#"<meta property="rendition:layout">pre-paginated</meta>"#;
+            let mut manga = LexEpub::from_bytes(picture_book(fixed, "rtl"))
+                .await
+                .unwrap();
+            assert_eq!(
+                manga.content_kind().await.unwrap(),
+                ContentKind::FixedLayoutComic
+            );
+        });
+    }
+
+    // License Tests
+    #[test]
+    fn test_license_url_from_rights_and_link() {
+        futures::executor::block_on(async {
+            let opf = |extra: &str| {
+                format!(
+                    r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>Licensed</dc:title>
+    <dc:rights>This work is licensed under a Creative Commons Attribution 4.0 International License (https://creativecommons.org/licenses/by/4.0/).</dc:rights>
+    {extra}
+  </metadata>
+  <manifest><item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/></manifest>
+  <spine><itemref idref="ch1"/></spine>
+</package>"#
+                )
+            };
+            let ch1 = common::xhtml("One", "<p>Text.</p>");
+
+            let bytes = common::build_epub(&opf(""), &[("OEBPS/ch1.xhtml", &ch1)]);
+            let metadata = LexEpub::from_bytes(bytes)
+                .await
+                .unwrap()
+                .get_metadata()
+                .await
+                .unwrap();
+            assert_eq!(
+                metadata.license_url().as_deref(),
+                Some("https://creativecommons.org/licenses/by/4.0/")
+            );
+
+            let link = r#"<link rel="cc:license" href="https://creativecommons.org/licenses/by-sa/4.0/"/>"#;
+            let bytes = common::build_epub(&opf(link), &[("OEBPS/ch1.xhtml", &ch1)]);
+            let metadata = LexEpub::from_bytes(bytes)
+                .await
+                .unwrap()
+                .get_metadata()
+                .await
+                .unwrap();
+            assert_eq!(
+                metadata.license_url().as_deref(),
+                Some("https://creativecommons.org/licenses/by-sa/4.0/")
+            );
+        });
+    }
+
+    // Media Type Routing Tests
+    #[test]
+    fn test_text_html_and_untyped_spine_items_extract() {
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Loose Types</dc:title></metadata>
+  <manifest>
+    <item id="ch1" href="ch1.html" media-type="text/html"/>
+    <item id="ch2" href="ch2.xhtml"/>
+  </manifest>
+  <spine>
+    <itemref idref="ch1"/>
+    <itemref idref="ch2"/>
+  </spine>
+</package>"#;
+            let ch1 = "<!DOCTYPE html><html><body><p>Plain HTML chapter.</p></body></html>";
+            let ch2 = common::xhtml("Two", "<p>No media type at all.</p>");
+            let bytes =
+                common::build_epub(opf, &[("OEBPS/ch1.html", ch1), ("OEBPS/ch2.xhtml", &ch2)]);
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let texts = epub.extract_text_only().await.unwrap();
+            assert_eq!(texts.len(), 2);
+            assert_eq!(texts[0], "Plain HTML chapter.");
+            assert!(texts[1].contains("No media type at all."));
+
+            let chapters = epub.extract_ast().await.unwrap();
+            assert_eq!(chapters[0].chapter_info.media_type, "text/html");
+            assert_eq!(chapters[1].chapter_info.media_type, "application/xhtml+xml");
+        });
+    }
+
+    // Reading Start Tests
+    fn front_matter_book(landmarks: bool) -> bytes::Bytes {
+        let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Front Matter</dc:title></metadata>
+  <manifest>
+    <item id="cover" href="cover.xhtml" media-type="application/xhtml+xml"/>
+    <item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch2" href="ch2.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine>
+    <itemref idref="cover"/>
+    <itemref idref="nav"/>
+    <itemref idref="ch1"/>
+    <itemref idref="ch2"/>
+  </spine>
+</package>"#;
+        let landmark_nav = if landmarks {
+            r#"<nav epub:type="landmarks"><ol><li><a epub:type="bodymatter" href="ch2.xhtml#start">Start</a></li></ol></nav>"#
+        } else {
+            ""
+        };
+        let nav = common::xhtml(
+            "Contents",
+            &format!(
+                r#"<nav epub:type="toc"><ol><li><a href="ch1.xhtml">One</a></li><li><a href="ch2.xhtml">Two</a></li></ol></nav>{}"#,
+                landmark_nav
+            ),
+        );
+        let cover = common::xhtml("Cover", r#"<img src="cover.jpg" alt="Cover"/>"#);
+        let prose = "It was a bright cold day in April, and the clocks were striking thirteen. \
+                     Nobody on the platform looked up from their newspapers.";
+        let ch1 = common::xhtml("One", &format!("<h1>One</h1><p>{}</p>", prose));
+        let ch2 = common::xhtml("Two", &format!("<h1>Two</h1><p>{}</p>", prose));
+        common::build_epub(
+            opf,
+            &[
+                ("OEBPS/cover.xhtml", &cover),
+                ("OEBPS/nav.xhtml", &nav),
+                ("OEBPS/ch1.xhtml", &ch1),
+                ("OEBPS/ch2.xhtml", &ch2),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_reading_start_skips_front_matter() {
+        futures::executor::block_on(async {
+            let mut with_landmarks = LexEpub::from_bytes(front_matter_book(true)).await.unwrap();
+            assert_eq!(with_landmarks.reading_start().await.unwrap(), 3);
+
+            let mut heuristic = LexEpub::from_bytes(front_matter_book(false)).await.unwrap();
+            assert_eq!(heuristic.reading_start().await.unwrap(), 2);
+        });
+    }
+
+    #[test]
+    fn test_reading_start_matches_front_matter_names_as_whole_words() {
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <manifest>
+    <item id="titlepage" href="titlepage.xhtml" media-type="application/xhtml+xml"/>
+    <item id="toc01" href="toc01.xhtml" media-type="application/xhtml+xml"/>
+    <item id="c1" href="tocqueville.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="titlepage"/><itemref idref="toc01"/><itemref idref="c1"/></spine>
+</package>"#;
+            let prose = format!("<p>{}</p>", "Democracy in America, volume one. ".repeat(5));
+            let bytes = common::build_epub(
+                opf,
+                &[
+                    ("OEBPS/titlepage.xhtml", &common::xhtml("", &prose)),
+                    ("OEBPS/toc01.xhtml", &common::xhtml("", &prose)),
+                    ("OEBPS/tocqueville.xhtml", &common::xhtml("", &prose)),
+                ],
+            );
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            assert_eq!(epub.reading_start().await.unwrap(), 2);
+        });
+    }
+
+    #[test]
+    fn test_validate_report_collects_every_issue() {
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>Broken</dc:title>
+    <dc:language>en</dc:language>
+  </metadata>
+  <manifest>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch2" href="ch2.xhtml" media-type="application/xhtml+xml"/>
+    <item id="css" href="style.css" media-type="text/css"/>
+  </manifest>
+  <spine>
+    <itemref idref="ch1"/>
+    <itemref idref="ch2"/>
+    <itemref idref="ghost"/>
+  </spine>
+</package>"#;
+            let ch1 = common::xhtml(
+                "One",
+                r##"<p><a href="missing.xhtml#top">Gone</a> <a href="#local">Here</a> <a href="https://example.com">Web</a></p>"##,
+            );
+            let bytes = common::build_archive(&[
+                ("META-INF/container.xml", common::CONTAINER_XML.as_bytes()),
+                ("mimetype", b"application/epub+zip"),
+                ("OEBPS/content.opf", opf.as_bytes()),
+                ("OEBPS/ch1.xhtml", ch1.as_bytes()),
+            ]);
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let report = epub.validate_report().await.unwrap();
+            assert!(!report.is_valid());
+
+            let has_error = |needle: &str| report.errors.iter().any(|e| e.contains(needle));
+            assert!(has_error("mimetype is not the first entry"));
+            assert!(has_error("identifier"));
+            assert!(has_error("'ghost' is not in the manifest"));
+            assert!(has_error("OEBPS/ch2.xhtml"));
+            assert_eq!(report.errors.len(), 4, "{:?}", report.errors);
+
+            assert!(report.warnings.iter().any(|w| w.contains("style.css")));
+            assert!(report.warnings.iter().any(|w| w.contains("missing.xhtml")));
+            assert_eq!(report.warnings.len(), 2, "{:?}", report.warnings);
+        });
+    }
+
+    #[test]
+    fn test_validate_report_clean_book() {
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" unique-identifier="uid" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:identifier id="uid">urn:uuid:clean</dc:identifier>
+    <dc:title>Clean</dc:title>
+    <dc:language>en</dc:language>
+  </metadata>
+  <manifest>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch2" href="text/ch2.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine>
+    <itemref idref="ch1"/>
+    <itemref idref="ch2"/>
+  </spine>
+</package>"#;
+            let ch1 = common::xhtml("One", r#"<p><a href="text/ch2.xhtml#s1">Next</a></p>"#);
+            let ch2 = common::xhtml("Two", r#"<p><a href="../ch1.xhtml">Back</a></p>"#);
+            let bytes = common::build_epub(
+                opf,
+                &[("OEBPS/ch1.xhtml", &ch1), ("OEBPS/text/ch2.xhtml", &ch2)],
+            );
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let report = epub.validate_report().await.unwrap();
+            assert!(report.is_valid(), "{:?}", report.errors);
+            assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+        });
+    }
+
+    #[test]
+    fn test_percent_encoded_links_resolve_in_validation() {
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" unique-identifier="uid" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:identifier id="uid">urn:uuid:encoded</dc:identifier>
+    <dc:title>Encoded</dc:title>
+    <dc:language>en</dc:language>
+  </metadata>
+  <manifest>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="notes" href="my%20notes.xhtml" media-type="application/xhtml+xml"/>
+    <item id="pic" href="images/my%20pic.png" media-type="image/png"/>
+  </manifest>
+  <spine>
+    <itemref idref="ch1"/>
+    <itemref idref="notes"/>
+  </spine>
+</package>"#;
+            let ch1 = common::xhtml(
+                "One",
+                // `%+5` is not an escape, so that link names the file as is
+                r#"<p><a href="my%20notes.xhtml#n1">Notes</a><img src="images/my%20pic.png" alt=""/><a href="50%+5.xhtml">Odd</a></p>"#,
+            );
+            let notes = common::xhtml("Notes", r#"<p id="n1">A note.</p>"#);
+            let bytes = common::build_epub(
+                opf,
+                &[
+                    ("OEBPS/ch1.xhtml", &ch1),
+                    ("OEBPS/my notes.xhtml", &notes),
+                    ("OEBPS/images/my pic.png", "PNG"),
+                    ("OEBPS/50%+5.xhtml", &notes),
+                ],
+            );
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let report = epub.validate_report().await.unwrap();
+            assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+            assert!(epub.orphaned_resources().await.unwrap().is_empty());
+        });
+    }
+
+    #[test]
+    fn test_chapter_html_minify() {
+        use lexepub::core::html_parser::extract_text_content;
+
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Minify</dc:title></metadata>
+  <manifest><item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/></manifest>
+  <spine><itemref idref="ch1"/></spine>
+</package>"#;
+            let pre = "<pre>fn main() {\n    println!(\"hi\");\n}</pre>";
+            let body = format!(
+                "\n    <div>\n        <h1>  Title  </h1>\n\n        <p>Some   <em>spaced</em> <strong>words</strong>\n            here.</p>\n        {}\n    </div>\n",
+                pre
+            );
+            let ch1 = common::xhtml("Minify", &body);
+            let bytes = common::build_epub(opf, &[("OEBPS/ch1.xhtml", &ch1)]);
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let raw = epub.chapter_html(0, false).await.unwrap();
+            let minified = epub.chapter_html(0, true).await.unwrap();
+            assert_eq!(raw, ch1);
+            assert!(minified.len() < raw.len());
+            assert!(minified.contains(pre));
+            assert!(minified.contains("<em>spaced</em> <strong>words</strong>"));
+            assert!(!minified.contains("\n    <div>"));
+
+            let words = |html: &str| {
+                extract_text_content(html)
+                    .unwrap()
+                    .split_whitespace()
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(words(&minified), words(&raw));
+        });
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_cover_thumbnail_respects_max_dim() {
+        futures::executor::block_on(async {
+            let mut png = std::io::Cursor::new(Vec::new());
+            image::DynamicImage::new_rgb8(400, 250)
+                .write_to(&mut png, image::ImageFormat::Png)
+                .unwrap();
+            let png = png.into_inner();
+
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Cover</dc:title></metadata>
+  <manifest>
+    <item id="cover" href="cover.png" media-type="image/png" properties="cover-image"/>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="ch1"/></spine>
+</package>"#;
+            let ch1 = common::xhtml("One", "<p>Text</p>");
+            let bytes = common::build_archive(&[
+                ("mimetype", b"application/epub+zip"),
+                ("META-INF/container.xml", common::CONTAINER_XML.as_bytes()),
+                ("OEBPS/content.opf", opf.as_bytes()),
+                ("OEBPS/cover.png", &png),
+                ("OEBPS/ch1.xhtml", ch1.as_bytes()),
+            ]);
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let thumbnail = epub.cover_thumbnail(100).await.unwrap().unwrap();
+            let decoded = image::load_from_memory(&thumbnail).unwrap();
+            assert_eq!(decoded.width(), 100);
+            assert!(decoded.height() <= 100);
+            assert_eq!(decoded.height(), 63);
+
+            let ch1 = common::xhtml("One", "<p>Text</p>");
+            let no_cover = common::build_epub(
+                r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <manifest><item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/></manifest>
+  <spine><itemref idref="ch1"/></spine>
+</package>"#,
+                &[("OEBPS/ch1.xhtml", &ch1)],
+            );
+            let mut epub = LexEpub::from_bytes(no_cover).await.unwrap();
+            assert!(epub.cover_thumbnail(100).await.unwrap().is_none());
+
+            // An undecodable cover fails the thumbnail but not the info card
+            let bytes = common::build_archive(&[
+                ("mimetype", b"application/epub+zip"),
+                ("META-INF/container.xml", common::CONTAINER_XML.as_bytes()),
+                ("OEBPS/content.opf", opf.as_bytes()),
+                ("OEBPS/cover.png", b"GIF89a not really a png"),
+                ("OEBPS/ch1.xhtml", ch1.as_bytes()),
+            ]);
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            assert!(epub.cover_thumbnail(100).await.is_err());
+            let card = epub.info_card().await.unwrap();
+            assert_eq!(card.title.as_deref(), Some("Cover"));
+            assert!(card.cover_thumbnail.is_none());
+        });
+    }
+
+    #[test]
+    fn test_orphaned_resources_reports_unreferenced_image() {
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Orphans</dc:title></metadata>
+  <manifest>
+    <item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+    <item id="ch1" href="text/ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="appendix" href="text/appendix.xhtml" media-type="application/xhtml+xml"/>
+    <item id="css" href="styles/book.css" media-type="text/css"/>
+    <item id="font" href="fonts/serif.otf" media-type="font/otf"/>
+    <item id="used" href="images/used.png" media-type="image/png"/>
+    <item id="unused" href="images/unused.png" media-type="image/png"/>
+  </manifest>
+  <spine><itemref idref="ch1"/></spine>
+</package>"#;
+            let nav = common::xhtml(
+                "Contents",
+                r#"<nav epub:type="toc"><ol><li><a href="text/ch1.xhtml">One</a></li></ol></nav>"#,
+            );
+            let ch1 = r#"<?xml version="1.0" encoding="UTF-8"?>
+<html xmlns="http://www.w3.org/1999/xhtml">
+<head><title>One</title><link rel="stylesheet" href="../styles/book.css"/></head>
+<body><p><img src="../images/used.png" alt=""/> See the <a href="appendix.xhtml#notes">notes</a>.</p></body>
+</html>"#;
+            let appendix = common::xhtml("Appendix", r#"<p id="notes">Notes</p>"#);
+            let css = r#"@font-face { font-family: "Serif"; src: url("../fonts/serif.otf"); }"#;
+            let bytes = common::build_epub(
+                opf,
+                &[
+                    ("OEBPS/nav.xhtml", &nav),
+                    ("OEBPS/text/ch1.xhtml", ch1),
+                    ("OEBPS/text/appendix.xhtml", &appendix),
+                    ("OEBPS/styles/book.css", css),
+                    ("OEBPS/fonts/serif.otf", "font"),
+                    ("OEBPS/images/used.png", "png"),
+                    ("OEBPS/images/unused.png", "png"),
+                ],
+            );
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let orphans = epub.orphaned_resources().await.unwrap();
+            assert_eq!(orphans, vec!["images/unused.png"]);
+        });
+    }
+
+    #[cfg(feature = "unicode-segmentation")]
+    #[test]
+    fn test_grapheme_count_below_scalar_count() {
+        use lexepub::core::chapter::grapheme_count;
+
+        // "e" + combining acute, and a family emoji joined with ZWJs
+        let text = "Cafe\u{301} \u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        assert_eq!(text.chars().count(), 11);
+        assert_eq!(grapheme_count(text), 6);
+
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <manifest><item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/></manifest>
+  <spine><itemref idref="ch1"/></spine>
+</package>"#;
+            let ch1 = common::xhtml("One", &format!("<p>{}</p>", text));
+            let bytes = common::build_epub(opf, &[("OEBPS/ch1.xhtml", &ch1)]);
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let chapters = epub.extract_ast().await.unwrap();
+            assert!(chapters[0].grapheme_count() < chapters[0].char_count);
+            let total = epub.total_grapheme_count().await.unwrap();
+            assert!(total < epub.total_char_count().await.unwrap());
+        });
+    }
+
+    #[test]
+    fn test_copyright_page_from_landmark() {
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Rights</dc:title></metadata>
+  <manifest>
+    <item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+    <item id="legal" href="legal.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="legal"/><itemref idref="ch1"/></spine>
+</package>"#;
+            let nav = common::xhtml(
+                "Contents",
+                r#"<nav epub:type="landmarks"><ol><li><a epub:type="copyright-page" href="legal.xhtml">Legal</a></li></ol></nav>"#,
+            );
+            let legal = common::xhtml(
+                "Legal",
+                "<p>Published 2024 by Example Press. All rights reserved.</p>",
+            );
+            let ch1 = common::xhtml("One", "<p>Once upon a time.</p>");
+            let bytes = common::build_epub(
+                opf,
+                &[
+                    ("OEBPS/nav.xhtml", &nav),
+                    ("OEBPS/legal.xhtml", &legal),
+                    ("OEBPS/ch1.xhtml", &ch1),
+                ],
+            );
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let page = epub.copyright_page().await.unwrap().unwrap();
+            assert!(page.contains("All rights reserved"));
+
+            let mut without = LexEpub::from_bytes(front_matter_book(false)).await.unwrap();
+            assert!(without.copyright_page().await.unwrap().is_none());
+        });
+    }
+
+    #[test]
+    fn test_word_tokenizer_option_drives_word_counts() {
+        use futures::StreamExt;
+        use lexepub::LexEpubOptions;
+
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <manifest><item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/></manifest>
+  <spine><itemref idref="ch1"/></spine>
+</package>"#;
+            let ch1 = common::xhtml("One", "<p>吾輩は猫である。</p>");
+            let bytes = common::build_epub(opf, &[("OEBPS/ch1.xhtml", &ch1)]);
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let default_count = epub.total_word_count().await.unwrap();
+
+            // Count every non-space, non-punctuation character
+            epub.set_options(LexEpubOptions::new().word_tokenizer(Box::new(|text: &str| {
+                text.chars().filter(|c| c.is_alphabetic()).count()
+            })));
+            let custom_count = epub.total_word_count().await.unwrap();
+            assert!(custom_count > default_count);
+            // The head `<title>` is not part of the text
+            assert_eq!(custom_count, 7);
+
+            let chapters = epub.extract_ast().await.unwrap();
+            let mut stream = epub.extract_chapters_stream().await.unwrap();
+            let streamed = stream.next().await.unwrap().unwrap();
+            assert_eq!(streamed.word_count, chapters[0].word_count);
+            assert_eq!(chapters[0].word_count, custom_count);
+        });
+    }
+
+    #[test]
+    fn test_accessibility_conformance_claims() {
+        futures::executor::block_on(async {
+            let bundled = Path::new(
+                "examples/epubs/Fundamental-Accessibility-Tests-Basic-Functionality-v2.0.0.epub",
+            );
+            if bundled.exists() {
+                let metadata = get_metadata(bundled).await.unwrap();
+                assert_eq!(
+                    metadata.conforms_to,
+                    vec!["EPUB Accessibility 1.1 - WCAG 2.2 Level AA"]
+                );
+            }
+
+            let opf = r##"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>Certified</dc:title>
+    <link rel="dcterms:conformsTo" href="http://www.idpf.org/epub/a11y/accessibility-20170105.html#wcag-aa"/>
+    <meta property="a11y:certifiedBy" id="certifier">Example Accessibility Lab</meta>
+    <link rel="a11y:certifierReport" refines="#certifier" href="https://example.com/report.html"/>
+  </metadata>
+  <manifest><item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/></manifest>
+  <spine><itemref idref="ch1"/></spine>
+</package>"##;
+            let ch1 = common::xhtml("One", "<p>Text</p>");
+            let bytes = common::build_epub(opf, &[("OEBPS/ch1.xhtml", &ch1)]);
+            let metadata = LexEpub::from_bytes(bytes)
+                .await
+                .unwrap()
+                .get_metadata()
+                .await
+                .unwrap();
+            assert_eq!(
+                metadata.conforms_to,
+                vec!["http://www.idpf.org/epub/a11y/accessibility-20170105.html#wcag-aa"]
+            );
+            assert_eq!(
+                metadata.certified_by.as_deref(),
+                Some("Example Accessibility Lab")
+            );
+            assert_eq!(
+                metadata.certifier_report.as_deref(),
+                Some("https://example.com/report.html")
+            );
+        });
+    }
+
+    #[test]
+    fn test_extract_text_reversed_keeps_spine_indices() {
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <manifest>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch2" href="ch2.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch3" href="ch3.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch4" href="ch4.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine>
+    <itemref idref="ch1"/><itemref idref="ch2"/><itemref idref="ch3"/><itemref idref="ch4"/>
+  </spine>
+</package>"#;
+            let ch1 = common::xhtml("One", "<p>First</p>");
+            let ch3 = common::xhtml("Three", "<p>Third</p>");
+            let ch4 = common::xhtml("Four", "<p>Last</p>");
+            // ch2 is missing from the archive and gets skipped
+            let bytes = common::build_epub(
+                opf,
+                &[
+                    ("OEBPS/ch1.xhtml", &ch1),
+                    ("OEBPS/ch3.xhtml", &ch3),
+                    ("OEBPS/ch4.xhtml", &ch4),
+                ],
+            );
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let reversed = epub.extract_text_reversed().await.unwrap();
+            let indices: Vec<usize> = reversed.iter().map(|(index, _)| *index).collect();
+            assert_eq!(indices, vec![3, 2, 0]);
+            assert!(reversed[0].1.contains("Last"));
+            assert!(reversed[2].1.contains("First"));
+        });
+    }
+
+    #[test]
+    fn test_search_returns_char_offsets_and_snippets() {
+        futures::executor::block_on(async {
+            let bytes = vocabulary_book(
+                "fr",
+                &[
+                    "<p>Déjà vu: the café opened. Café au lait, CAFÉ noir.</p>",
+                    "<p>Nothing here.</p>",
+                    "<p>aaaa</p>",
+                ],
+            );
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+
+            let hits = epub.search("café", false).await.unwrap();
+            let offsets: Vec<(usize, usize)> = hits
+                .iter()
+                .map(|hit| (hit.chapter_index, hit.char_offset))
+                .collect();
+            assert_eq!(offsets, vec![(0, 13), (0, 26), (0, 40)]);
+            assert_eq!(hits[0].href, "OEBPS/c0.xhtml");
+            let text = &epub.extract_text_only().await.unwrap()[0];
+            let found: String = text.chars().skip(hits[2].char_offset).take(4).collect();
+            assert_eq!(found, "CAFÉ");
+            assert!(hits[0].snippet.contains("Déjà vu: the café opened."));
+
+            let hits = epub.search("Café", true).await.unwrap();
+            assert_eq!(hits.len(), 1);
+            assert_eq!(hits[0].char_offset, 26);
+
+            // Matches do not overlap
+            let hits = epub.search("aa", true).await.unwrap();
+            assert_eq!(hits.len(), 2);
+            assert_eq!(hits[1].chapter_index, 2);
+            assert_eq!(hits[1].char_offset, 2);
+
+            assert!(epub.search("missing", false).await.unwrap().is_empty());
+            assert!(matches!(
+                epub.search("", false).await,
+                Err(lexepub::LexEpubError::InvalidArgument(_))
+            ));
+        });
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_search_regex() {
+        futures::executor::block_on(async {
+            let bytes = vocabulary_book(
+                "en",
+                &[
+                    "<p>Café at 9 o'clock, tea at 10.</p>",
+                    "<p>No numbers.</p>",
+                    "<p>Chapter 42 ends.</p>",
+                ],
+            );
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+
+            let hits = epub.search_regex(r"\d+").await.unwrap();
+            let found: Vec<(usize, usize)> = hits
+                .iter()
+                .map(|hit| (hit.chapter_index, hit.char_offset))
+                .collect();
+            assert_eq!(found, vec![(0, 8), (0, 26), (2, 8)]);
+            assert_eq!(hits[2].href, "OEBPS/c2.xhtml");
+            assert_eq!(hits[2].snippet, "Chapter 42 ends.");
+
+            assert!(epub.search_regex(r"\bcoffee\b").await.unwrap().is_empty());
+            // Empty matches are not hits
+            assert!(epub.search_regex("x*").await.unwrap().is_empty());
+            assert!(matches!(
+                epub.search_regex("(unclosed").await,
+                Err(lexepub::LexEpubError::InvalidPattern(_))
+            ));
+        });
+    }
+
+    fn vocabulary_book(language: &str, chapters: &[&str]) -> bytes::Bytes {
+        let items: String = (0..chapters.len())
+            .map(|i| {
+                format!(r#"<item id="c{i}" href="c{i}.xhtml" media-type="application/xhtml+xml"/>"#)
+            })
+            .collect();
+        let refs: String = (0..chapters.len())
+            .map(|i| format!(r#"<itemref idref="c{i}"/>"#))
+            .collect();
+        let opf = format!(
+            r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:language>{language}</dc:language></metadata>
+  <manifest>{items}</manifest>
+  <spine>{refs}</spine>
+</package>"#
+        );
+        let files: Vec<(String, String)> = chapters
+            .iter()
+            .enumerate()
+            .map(|(i, body)| (format!("OEBPS/c{i}.xhtml"), common::xhtml("", body)))
+            .collect();
+        let files: Vec<(&str, &str)> = files
+            .iter()
+            .map(|(path, content)| (path.as_str(), content.as_str()))
+            .collect();
+        common::build_epub(&opf, &files)
+    }
+
+    #[test]
+    fn test_vocabulary_counts_distinct_words() {
+        use lexepub::VocabOptions;
+
+        futures::executor::block_on(async {
+            let bytes = vocabulary_book(
+                "en",
+                &["<p>The cat saw the Cat.</p>", "<p>A dog saw the cat!</p>"],
+            );
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+
+            let exact = epub.vocabulary(VocabOptions::new()).await.unwrap();
+            assert_eq!(exact.total_tokens, 10);
+            // The, cat, saw, the, Cat, A, dog
+            assert_eq!(exact.unique_tokens, 7);
+            assert_eq!(exact.per_chapter_unique, vec![5, 5]);
+
+            let folded = epub
+                .vocabulary(VocabOptions::new().case_fold(true))
+                .await
+                .unwrap();
+            assert_eq!(folded.total_tokens, 10);
+            // the, cat, saw, a, dog
+            assert_eq!(folded.unique_tokens, 5);
+            assert_eq!(folded.per_chapter_unique, vec![3, 5]);
+        });
+    }
+
+    #[cfg(feature = "stemming")]
+    #[test]
+    fn test_vocabulary_stemming_merges_inflections() {
+        use lexepub::VocabOptions;
+
+        futures::executor::block_on(async {
+            let bytes = vocabulary_book("en-GB", &["<p>Run, running, runs. Jumped jumps.</p>"]);
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let stats = epub
+                .vocabulary(VocabOptions::new().case_fold(true).stem(true))
+                .await
+                .unwrap();
+            assert_eq!(stats.total_tokens, 5);
+            assert_eq!(stats.unique_tokens, 2);
+        });
+    }
+
+    #[test]
+    fn test_toc_html_from_nav_and_ncx() {
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Nav</dc:title></metadata>
+  <manifest>
+    <item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+    <item id="ch1" href="text/ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch2" href="text/ch2.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="ch1"/><itemref idref="ch2"/></spine>
+</package>"#;
+            let nav = common::xhtml(
+                "Contents",
+                r##"<nav epub:type="toc"><ol><li><a href="text/ch1.xhtml">One</a></li><li><a href="text/ch2.xhtml#part">Two</a></li></ol></nav><nav epub:type="landmarks"><ol><li><a epub:type="bodymatter" href="text/ch1.xhtml">Start</a></li></ol></nav>"##,
+            );
+            let ch1 = common::xhtml("One", "<p>First.</p>");
+            let ch2 = common::xhtml("Two", "<p>Second.</p>");
+            let bytes = common::build_epub(
+                opf,
+                &[
+                    ("OEBPS/nav.xhtml", &nav),
+                    ("OEBPS/text/ch1.xhtml", &ch1),
+                    ("OEBPS/text/ch2.xhtml", &ch2),
+                ],
+            );
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let html = epub.toc_html().await.unwrap().unwrap();
+            assert!(html.contains(r#"<a href="OEBPS/text/ch1.xhtml">One</a>"#));
+            assert!(html.contains(r#"<a href="OEBPS/text/ch2.xhtml#part">Two</a>"#));
+            assert!(!html.contains("Start"));
+
+            let opf = r#"<?xml version="1.0"?>
+<package version="2.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>NCX</dc:title></metadata>
+  <manifest>
+    <item id="ncx" href="toc.ncx" media-type="application/x-dtbncx+xml"/>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine toc="ncx"><itemref idref="ch1"/></spine>
+</package>"#;
+            let ncx = r##"<?xml version="1.0"?>
+<ncx xmlns="http://www.daisy.org/z3986/2005/ncx/" version="2005-1">
+  <navMap>
+    <navPoint id="p1" playOrder="1">
+      <navLabel><text>Part &amp; Parcel</text></navLabel>
+      <content src="ch1.xhtml"/>
+      <navPoint id="p2" playOrder="2">
+        <navLabel><text>Section</text></navLabel>
+        <content src="ch1.xhtml#s1"/>
+      </navPoint>
+    </navPoint>
+  </navMap>
+</ncx>"##;
+            let ch1 = common::xhtml("One", "<p>First.</p>");
+            let bytes =
+                common::build_epub(opf, &[("OEBPS/toc.ncx", ncx), ("OEBPS/ch1.xhtml", &ch1)]);
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let html = epub.toc_html().await.unwrap().unwrap();
+            assert_eq!(
+                html,
+                r##"<ol><li><a href="OEBPS/ch1.xhtml">Part &amp; Parcel</a><ol><li><a href="OEBPS/ch1.xhtml#s1">Section</a></li></ol></li></ol>"##
+            );
+        });
+    }
+
+    #[test]
+    fn test_chapter_cache_size_bounds_parsed_chapters() {
+        use lexepub::LexEpubOptions;
+
+        futures::executor::block_on(async {
+            let ids: Vec<String> = (0..6).map(|i| format!("ch{}", i)).collect();
+            let manifest: String = ids
+                .iter()
+                .map(|id| {
+                    format!(
+                        r#"<item id="{id}" href="{id}.xhtml" media-type="application/xhtml+xml"/>"#
+                    )
+                })
+                .collect();
+            let spine: String = ids
+                .iter()
+                .map(|id| format!(r#"<itemref idref="{id}"/>"#))
+                .collect();
+            let opf = format!(
+                r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Cache</dc:title></metadata>
+  <manifest>{manifest}</manifest>
+  <spine>{spine}</spine>
+</package>"#
+            );
+            let bodies: Vec<(String, String)> = ids
+                .iter()
+                .enumerate()
+                .map(|(i, id)| {
+                    (
+                        format!("OEBPS/{}.xhtml", id),
+                        common::xhtml("", &format!("<p>Chapter number {}</p>", i)),
+                    )
+                })
+                .collect();
+            let files: Vec<(&str, &str)> = bodies
+                .iter()
+                .map(|(path, body)| (path.as_str(), body.as_str()))
+                .collect();
+            let bytes = common::build_epub(&opf, &files);
+
+            let mut unbounded = LexEpub::from_bytes(bytes.clone()).await.unwrap();
+            unbounded.extract_ast().await.unwrap();
+            #[cfg(not(feature = "lowmem"))]
+            assert_eq!(unbounded.cached_chapter_count(), 6);
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            epub.set_options(LexEpubOptions::new().chapter_cache_size(2));
+            assert_eq!(epub.extract_ast().await.unwrap().len(), 6);
+            assert_eq!(epub.cached_chapter_count(), 0);
+
+            for round in 0..2 {
+                for index in 0..6 {
+                    let chapter = epub.chapter(index).await.unwrap();
+                    assert!(chapter
+                        .content
+                        .contains(&format!("Chapter number {}", index)));
+                    assert!(epub.cached_chapter_count() <= 2, "round {}", round);
+                }
+            }
+            assert!(epub.chapter(6).await.is_err());
+        });
+    }
+
+    #[test]
+    fn test_dropcap_detection() {
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Caps</dc:title></metadata>
+  <manifest>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch2" href="ch2.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch3" href="ch3.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="ch1"/><itemref idref="ch2"/><itemref idref="ch3"/></spine>
+</package>"#;
+            let ch1 = common::xhtml(
+                "One",
+                r#"<p><span class="first dropcap">W</span>hen the morning came.</p>"#,
+            );
+            let ch2 = r#"<html><head><style>p.opening::first-letter { font-size: 3em; }</style></head>
+<body><p class="opening">"Indeed," she said.</p></body></html>"#;
+            let ch3 = common::xhtml("Three", "<p>No ornament here.</p>");
+            let bytes = common::build_epub(
+                opf,
+                &[
+                    ("OEBPS/ch1.xhtml", &ch1),
+                    ("OEBPS/ch2.xhtml", ch2),
+                    ("OEBPS/ch3.xhtml", &ch3),
+                ],
+            );
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let chapters = epub.extract_ast().await.unwrap();
+            assert_eq!(chapters[0].dropcap, Some('W'));
+            assert_eq!(chapters[1].dropcap, Some('I'));
+            assert_eq!(chapters[2].dropcap, None);
+        });
+    }
+
+    #[test]
+    fn test_rootfile_without_opf_extension() {
+        futures::executor::block_on(async {
+            let container = r#"<?xml version="1.0"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/book.xml" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>"#;
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf" unique-identifier="uid">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:identifier id="uid">urn:uuid:1234</dc:identifier>
+    <dc:title>Odd Package</dc:title>
+    <dc:language>en</dc:language>
+  </metadata>
+  <manifest><item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/></manifest>
+  <spine><itemref idref="ch1"/></spine>
+</package>"#;
+            let ch1 = common::xhtml("", "<p>Opened anyway.</p>");
+            let bytes = common::build_archive(&[
+                ("mimetype", b"application/epub+zip"),
+                ("META-INF/container.xml", container.as_bytes()),
+                ("OEBPS/book.xml", opf.as_bytes()),
+                ("OEBPS/ch1.xhtml", ch1.as_bytes()),
+            ]);
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let metadata = epub.get_metadata().await.unwrap();
+            assert_eq!(metadata.title.as_deref(), Some("Odd Package"));
+            let texts = epub.extract_text_only().await.unwrap();
+            assert!(texts[0].contains("Opened anyway."));
+
+            let (_, media_type) = epub.serve("OEBPS/book.xml").await.unwrap();
+            assert_eq!(media_type, "application/oebps-package+xml");
+            let report = epub.validate_report().await.unwrap();
+            assert!(report.errors.is_empty(), "{:?}", report.errors);
+        });
+    }
+
+    /// Seekable reader that tallies how many bytes were read through it
+    struct CountingReader {
+        inner: std::io::Cursor<Vec<u8>>,
+        read: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    impl std::io::Read for CountingReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = self.inner.read(buf)?;
+            self.read.fetch_add(n, std::sync::atomic::Ordering::SeqCst);
+            Ok(n)
+        }
+    }
+
+    impl std::io::Seek for CountingReader {
+        fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    #[test]
+    fn test_info_card_skips_chapter_bodies() {
+        use std::sync::atomic::Ordering;
+
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>Card Book</dc:title>
+    <dc:creator>A. Writer</dc:creator>
+  </metadata>
+  <manifest>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch2" href="ch2.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="ch1"/><itemref idref="ch2"/></spine>
+</package>"#;
+            // About a megabyte of poorly compressible prose
+            let mut seed: u32 = 7;
+            let noise: String = (0..1_000_000)
+                .map(|_| {
+                    seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                    match (seed >> 16) % 27 {
+                        26 => ' ',
+                        n => (b'a' + n as u8) as char,
+                    }
+                })
+                .collect();
+            let ch1 = common::xhtml("", &format!("<p>{}</p>", noise));
+            let ch2 = common::xhtml("", "<p>Short.</p>");
+            let bytes =
+                common::build_epub(opf, &[("OEBPS/ch1.xhtml", &ch1), ("OEBPS/ch2.xhtml", &ch2)]);
+            let archive_len = bytes.len();
+
+            let read = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+            let reader = CountingReader {
+                inner: std::io::Cursor::new(bytes.to_vec()),
+                read: read.clone(),
+            };
+            let mut epub = LexEpub::from_sync_reader(reader).unwrap();
+
+            let card = epub.info_card().await.unwrap();
+            assert_eq!(card.title.as_deref(), Some("Card Book"));
+            assert_eq!(card.authors, vec!["A. Writer".to_string()]);
+            assert_eq!(card.chapter_count, 2);
+            assert!(card.total_size > ch1.len() as u64);
+            assert!(card.cover_thumbnail.is_none());
+            let card_read = read.load(Ordering::SeqCst);
+            assert!(
+                card_read < archive_len / 4,
+                "read {} of {} bytes",
+                card_read,
+                archive_len
+            );
+
+            // The counter does see chapter bodies once they are extracted
+            epub.extract_text_only().await.unwrap();
+            assert!(read.load(Ordering::SeqCst) - card_read > archive_len / 2);
+        });
+    }
+
+    #[test]
+    fn test_annotation_asides_excluded_from_text() {
+        use lexepub::TextExtractOptions;
+
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Notes</dc:title></metadata>
+  <manifest><item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/></manifest>
+  <spine><itemref idref="ch1"/></spine>
+</package>"#;
+            let ch1 = common::xhtml(
+                "",
+                r#"<p>The main argument.</p>
+<aside epub:type="annotation"><p>Reader note: compare chapter two.</p></aside>
+<aside epub:type="footnote"><p>A real footnote.</p></aside>
+<p>The argument continues.</p>"#,
+            );
+            let bytes = common::build_epub(opf, &[("OEBPS/ch1.xhtml", &ch1)]);
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let text = epub.extract_text_only().await.unwrap().join("\n");
+            assert!(text.contains("The main argument."));
+            assert!(text.contains("The argument continues."));
+            assert!(text.contains("A real footnote."));
+            assert!(!text.contains("Reader note"));
+
+            assert_eq!(
+                epub.annotations(0).await.unwrap(),
+                vec!["Reader note: compare chapter two.".to_string()]
+            );
+
+            epub.set_text_options(TextExtractOptions::new().include_annotations(true));
+            let text = epub.extract_text_only().await.unwrap().join("\n");
+            assert!(text.contains("Reader note: compare chapter two."));
+        });
+    }
+
+    #[test]
+    fn test_assume_language_drives_sentence_splitting() {
+        use lexepub::LexEpubOptions;
+
+        futures::executor::block_on(async {
+            let opf = |language: &str| {
+                format!(
+                    r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Ερωτήσεις</dc:title>{language}</metadata>
+  <manifest><item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/></manifest>
+  <spine><itemref idref="ch1"/></spine>
+</package>"#
+                )
+            };
+            // `;` is the Greek question mark
+            let ch1 = common::xhtml("", "<p>Τι κάνεις; Καλά είμαι.</p>");
+
+            let undeclared = common::build_epub(&opf(""), &[("OEBPS/ch1.xhtml", &ch1)]);
+            let mut epub = LexEpub::from_bytes(undeclared.clone()).await.unwrap();
+            assert_eq!(epub.text_language().await.unwrap(), None);
+            assert_eq!(epub.sentences(0).await.unwrap().len(), 1);
+
+            let mut epub = LexEpub::from_bytes(undeclared).await.unwrap();
+            epub.set_options(LexEpubOptions::new().assume_language("el"));
+            assert_eq!(epub.text_language().await.unwrap().as_deref(), Some("el"));
+            assert_eq!(
+                epub.sentences(0).await.unwrap(),
+                vec!["Τι κάνεις;".to_string(), "Καλά είμαι.".to_string()]
+            );
+
+            // A declared language wins over the assumed one
+            let declared = common::build_epub(
+                &opf("<dc:language>en</dc:language>"),
+                &[("OEBPS/ch1.xhtml", &ch1)],
+            );
+            let mut epub = LexEpub::from_bytes(declared).await.unwrap();
+            epub.set_options(LexEpubOptions::new().assume_language("el"));
+            assert_eq!(epub.text_language().await.unwrap().as_deref(), Some("en"));
+            assert_eq!(epub.sentences(0).await.unwrap().len(), 1);
+        });
+    }
+
+    #[test]
+    fn test_semantic_sections_capture_bibliography() {
+        use lexepub::SemanticSection;
+
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Thesis</dc:title></metadata>
+  <manifest>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="refs" href="refs.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="ch1"/><itemref idref="refs"/></spine>
+</package>"#;
+            let ch1 = common::xhtml(
+                "",
+                r##"<section epub:type="chapter" id="c1"><h1>Method</h1>
+<p>As shown<a epub:type="noteref" href="#n1">1</a>.</p>
+<figure epub:type="figure"><img src="f.png" alt=""/></figure></section>"##,
+            );
+            let refs = common::xhtml(
+                "",
+                r#"<section epub:type="backmatter  bibliography" id="bib"><h2>Works &amp; Sources</h2><ul><li>Smith 2001</li></ul></section>"#,
+            );
+            let bytes = common::build_epub(
+                opf,
+                &[("OEBPS/ch1.xhtml", &ch1), ("OEBPS/refs.xhtml", &refs)],
+            );
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let sections = epub.semantic_sections().await.unwrap();
+            assert_eq!(
+                sections,
+                vec![
+                    SemanticSection {
+                        epub_type: "chapter".to_string(),
+                        href: "OEBPS/ch1.xhtml#c1".to_string(),
+                        title: Some("Method".to_string()),
+                    },
+                    SemanticSection {
+                        epub_type: "figure".to_string(),
+                        href: "OEBPS/ch1.xhtml".to_string(),
+                        title: None,
+                    },
+                    SemanticSection {
+                        epub_type: "backmatter bibliography".to_string(),
+                        href: "OEBPS/refs.xhtml#bib".to_string(),
+                        title: Some("Works & Sources".to_string()),
+                    },
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn test_extract_ast_after_text_only_populates_ast() {
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Trees</dc:title></metadata>
+  <manifest><item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/></manifest>
+  <spine><itemref idref="ch1"/></spine>
+</package>"#;
+            let ch1 = common::xhtml("", r#"<p>Some <a href="ch1.xhtml#x">linked</a> text.</p>"#);
+            let bytes = common::build_epub(opf, &[("OEBPS/ch1.xhtml", &ch1)]);
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let texts = epub.extract_text_only().await.unwrap();
+            assert_eq!(texts, vec!["Some linked text.".to_string()]);
+
+            let chapters = epub.extract_ast().await.unwrap();
+            assert_eq!(chapters[0].content, "Some linked text.");
+            let ast = chapters[0].ast.as_ref().expect("AST should be populated");
+            assert!(ast
+                .to_html()
+                .contains(r#"<a href="OEBPS/ch1.xhtml#x">linked</a>"#));
+
+            let chapter = epub.chapter(0).await.unwrap();
+            assert!(chapter.ast.is_some());
+        });
+    }
+
+    #[test]
+    fn test_cover_image_from_meta_or_properties() {
+        futures::executor::block_on(async {
+            let opf = |meta: &str, properties: &str| {
+                format!(
+                    r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Covers</dc:title>{meta}</metadata>
+  <manifest>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="img" href="images/front.png" media-type="image/png"{properties}/>
+  </manifest>
+  <spine><itemref idref="ch1"/></spine>
+</package>"#
+                )
+            };
+            let ch1 = common::xhtml("One", "<p>Text.</p>");
+            let cover_of = |opf: String| {
+                let ch1 = ch1.clone();
+                async move {
+                    let bytes = common::build_epub(
+                        &opf,
+                        &[
+                            ("OEBPS/ch1.xhtml", &ch1),
+                            ("OEBPS/images/front.png", "PNGDATA"),
+                        ],
+                    );
+                    let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+                    let cover = epub.cover_image().await.unwrap();
+                    assert_eq!(epub.has_cover().await.unwrap(), cover.is_some());
+                    cover
+                }
+            };
+
+            let epub2 = opf(r#"<meta name="cover" content="img"/>"#, "");
+            assert_eq!(cover_of(epub2).await.as_deref(), Some(&b"PNGDATA"[..]));
+
+            let epub3 = opf("", r#" properties="cover-image""#);
+            assert_eq!(cover_of(epub3).await.as_deref(), Some(&b"PNGDATA"[..]));
+
+            assert_eq!(cover_of(opf("", "")).await, None);
+        });
+    }
+
+    #[test]
+    fn test_table_of_contents_from_ncx_and_nav() {
+        use lexepub::TocNode;
+
+        futures::executor::block_on(async {
+            // The spine's toc attribute names the NCX even with a sloppy media type
+            let opf = r#"<?xml version="1.0"?>
+<package version="2.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>NCX</dc:title></metadata>
+  <manifest>
+    <item id="toc" href="nav/toc.ncx" media-type="text/xml"/>
+    <item id="ch1" href="text/ch1.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine toc="toc"><itemref idref="ch1"/></spine>
+</package>"#;
+            let ncx = r##"<?xml version="1.0"?>
+<ncx xmlns="http://www.daisy.org/z3986/2005/ncx/" version="2005-1">
+  <navMap>
+    <navPoint id="p1" playOrder="1">
+      <navLabel><text>Part One</text></navLabel>
+      <content src="../text/ch1.xhtml"/>
+      <navPoint id="p2" playOrder="2">
+        <navLabel><text>Section</text></navLabel>
+        <content src="../text/ch1.xhtml#s1"/>
+      </navPoint>
+    </navPoint>
+  </navMap>
+</ncx>"##;
+            let ch1 = common::xhtml("One", "<p>First.</p>");
+            let bytes = common::build_epub(
+                opf,
+                &[("OEBPS/nav/toc.ncx", ncx), ("OEBPS/text/ch1.xhtml", &ch1)],
+            );
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            assert_eq!(
+                epub.table_of_contents().await.unwrap(),
+                vec![TocNode {
+                    label: "Part One".to_string(),
+                    href: "OEBPS/text/ch1.xhtml".to_string(),
+                    fragment: None,
+                    children: vec![TocNode {
+                        label: "Section".to_string(),
+                        href: "OEBPS/text/ch1.xhtml".to_string(),
+                        fragment: Some("s1".to_string()),
+                        children: Vec::new(),
+                        play_order: Some(2),
+                    }],
+                    play_order: Some(1),
+                }]
+            );
+
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Nav</dc:title></metadata>
+  <manifest>
+    <item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+    <item id="ch1" href="text/ch1.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="ch1"/></spine>
+</package>"#;
+            let nav = common::xhtml(
+                "Contents",
+                r##"<nav epub:type="toc"><h1>Contents</h1><ol>
+                    <li><span>Part   I</span><ol><li><a href="text/ch1.xhtml#c1">Chapter &amp; Verse</a></li></ol></li>
+                </ol></nav>"##,
+            );
+            let bytes = common::build_epub(
+                opf,
+                &[("OEBPS/nav.xhtml", &nav), ("OEBPS/text/ch1.xhtml", &ch1)],
+            );
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let toc = epub.table_of_contents().await.unwrap();
+            assert_eq!(toc.len(), 1);
+            assert_eq!(toc[0].label, "Part I");
+            assert_eq!(toc[0].href, "");
+            assert_eq!(toc[0].play_order, None);
+            let chapter = &toc[0].children[0];
+            assert_eq!(chapter.label, "Chapter & Verse");
+            assert_eq!(chapter.href, "OEBPS/text/ch1.xhtml");
+            assert_eq!(chapter.fragment.as_deref(), Some("c1"));
+        });
+    }
+
+    #[test]
+    fn test_chunks_pack_paragraphs_near_target() {
+        use lexepub::core::html_parser::{extract_text_blocks, word_spans};
+
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Chunks</dc:title></metadata>
+  <manifest>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="lost" href="lost.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch2" href="ch2.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="ch1"/><itemref idref="lost"/><itemref idref="ch2"/></spine>
+</package>"#;
+            let paragraph = |n: usize, words: usize| {
+                let words: Vec<String> = (0..words).map(|w| format!("p{}w{}", n, w)).collect();
+                format!("<p>{}</p>", words.join(" "))
+            };
+            let mut body: String = (0..10).map(|n| paragraph(n, 6)).collect();
+            // Punctuation inside the oversized paragraph stays with its word
+            body.push_str(&paragraph(10, 45).replace(' ', ", "));
+            let ch1 = common::xhtml("", &body);
+            let ch2 = common::xhtml("", &(20..23).map(|n| paragraph(n, 5)).collect::<String>());
+            // The lost chapter is missing from the archive and gets skipped
+            let bytes =
+                common::build_epub(opf, &[("OEBPS/ch1.xhtml", &ch1), ("OEBPS/ch2.xhtml", &ch2)]);
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let chunks = epub.chunks(20, 4).await.unwrap();
+            // Offsets are into each chapter's text blocks, by spine position
+            let paragraphs = |html: &str| {
+                extract_text_blocks(html)
+                    .unwrap()
+                    .into_iter()
+                    .map(|block| block.text)
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            };
+            let texts = [paragraphs(&ch1), String::new(), paragraphs(&ch2)];
+
+            for chunk in &chunks {
+                assert!(chunk.word_count <= 20, "{:?}", chunk);
+                assert_eq!(chunk.word_count, word_spans(&chunk.text).len());
+                let source: String = texts[chunk.chapter_index]
+                    .chars()
+                    .skip(chunk.start_offset)
+                    .take(chunk.text.chars().count())
+                    .collect();
+                assert_eq!(source, chunk.text);
+            }
+            // Short paragraphs are packed whole: three 6-word paragraphs fit
+            assert_eq!(chunks[0].word_count, 18);
+            assert!(chunks[0].text.ends_with("p2w5"));
+            assert!(chunks[1].text.starts_with("p2w2"));
+            // Only the oversized paragraph is cut, into target-sized pieces
+            assert!(chunks
+                .iter()
+                .filter(|c| c.text.contains("p10w"))
+                .any(|c| c.word_count == 20 && !c.text.contains('\n')));
+            assert!(chunks
+                .iter()
+                .any(|c| c.text.starts_with("p10w") && c.text.ends_with(',')));
+
+            // Every word of every chapter lands in some chunk
+            for (index, text) in texts.iter().enumerate().filter(|(index, _)| *index != 1) {
+                let chapter: Vec<_> = chunks.iter().filter(|c| c.chapter_index == index).collect();
+                assert_eq!(chapter[0].start_offset, 0);
+                for pair in chapter.windows(2) {
+                    let end = pair[0].start_offset + pair[0].text.chars().count();
+                    let gap: String = text
+                        .chars()
+                        .skip(end)
+                        .take(pair[1].start_offset.saturating_sub(end))
+                        .collect();
+                    assert!(gap.trim().is_empty());
+                }
+                let last = chapter.last().unwrap();
+                assert_eq!(
+                    last.start_offset + last.text.chars().count(),
+                    text.chars().count()
+                );
+            }
+            assert!(chunks.iter().all(|c| c.chapter_index != 1));
+            assert_eq!(chunks.last().unwrap().chapter_index, 2);
+            assert_eq!(chunks.last().unwrap().word_count, 15);
+            // Overlap repeats the tail of the previous chunk
+            assert!(chunks[1].text.starts_with("p2w2 p2w3 p2w4 p2w5\n"));
+        });
+    }
+
+    #[test]
+    fn test_detect_duplicate_chapters() {
+        futures::executor::block_on(async {
+            // Three items resolve to the TOC file, one to a real chapter
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Broken</dc:title></metadata>
+  <manifest>
+    <item id="toc" href="toc.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch1" href="toc.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch2" href="ch2.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch3" href="toc.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine>
+    <itemref idref="toc"/>
+    <itemref idref="ch1"/>
+    <itemref idref="ch2"/>
+    <itemref idref="ch3"/>
+  </spine>
+</package>"#;
+            let toc = common::xhtml("Contents", "<p>Chapter list.</p>");
+            let ch2 = common::xhtml("Two", "<p>Real text.</p>");
+            let bytes =
+                common::build_epub(opf, &[("OEBPS/toc.xhtml", &toc), ("OEBPS/ch2.xhtml", &ch2)]);
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            assert_eq!(
+                epub.detect_duplicate_chapters().await.unwrap(),
+                vec![(0, 1), (0, 3), (1, 3)]
+            );
+        });
+    }
+
+    #[test]
+    fn test_chapter_reads_only_its_own_file() {
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Partial</dc:title></metadata>
+  <manifest>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch2" href="ch2.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="ch1"/><itemref idref="ch2"/></spine>
+</package>"#;
+            // ch1.xhtml is missing from the archive
+            let ch2 = common::xhtml("", "<p>Second.</p>");
+            let bytes = common::build_epub(opf, &[("OEBPS/ch2.xhtml", &ch2)]);
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let chapter = epub.chapter(1).await.unwrap();
+            assert_eq!(chapter.content, "Second.");
+            assert!(epub.chapter(0).await.is_err());
+            assert!(matches!(
+                epub.chapter(2).await,
+                Err(lexepub::LexEpubError::ChapterError(_))
+            ));
+            assert_eq!(epub.chapter_at(1).await.unwrap().content, "Second.");
+            assert!(matches!(
+                epub.chapter_at(2).await,
+                Err(lexepub::LexEpubError::ChapterError(_))
+            ));
+        });
+    }
+
+    #[test]
+    fn test_chapter_by_href() {
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Links</dc:title></metadata>
+  <manifest>
+    <item id="ch1" href="text/ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch2" href="text/chapter two.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="ch1"/><itemref idref="ch2"/></spine>
+</package>"#;
+            let ch1 = common::xhtml("", "<p>First.</p>");
+            let ch2 = common::xhtml("", "<p>Second.</p>");
+            let bytes = common::build_epub(
+                opf,
+                &[
+                    ("OEBPS/text/ch1.xhtml", &ch1),
+                    ("OEBPS/text/chapter two.xhtml", &ch2),
+                ],
+            );
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let chapter = epub
+                .chapter_by_href("./OEBPS/text/ch1.xhtml#section2")
+                .await
+                .unwrap();
+            assert_eq!(chapter.content, "First.");
+            for href in [
+                "OEBPS/text/chapter%20two.xhtml",
+                "OEBPS/text/chapter two.xhtml#x",
+            ] {
+                let chapter = epub.chapter_by_href(href).await.unwrap();
+                assert_eq!(chapter.content, "Second.");
+            }
+            assert!(matches!(
+                epub.chapter_by_href("OEBPS/text/ch3.xhtml").await,
+                Err(lexepub::LexEpubError::MissingFile(_))
+            ));
+        });
+    }
+
+    #[test]
+    fn test_section_by_href() {
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Notes</dc:title></metadata>
+  <manifest>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="notes" href="notes.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="ch1"/><itemref idref="notes"/></spine>
+</package>"#;
+            let ch1 = common::xhtml("", "<p>See the note.</p>");
+            let notes = common::xhtml(
+                "",
+                r#"<p>Notes</p>
+<aside epub:type="footnote" id="note1"><p>First <em>note</em>.</p></aside>
+<aside epub:type="annotation" id="note 2"><p>Second note.</p></aside>"#,
+            );
+            let bytes = common::build_epub(
+                opf,
+                &[("OEBPS/ch1.xhtml", &ch1), ("OEBPS/notes.xhtml", &notes)],
+            );
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            assert_eq!(
+                epub.section_by_href("OEBPS/notes.xhtml#note1")
+                    .await
+                    .unwrap(),
+                "First note."
+            );
+            // A targeted annotation is returned even though annotations are
+            // left out of chapter text
+            assert_eq!(
+                epub.section_by_href("./OEBPS/notes.xhtml#note%202")
+                    .await
+                    .unwrap(),
+                "Second note."
+            );
+            assert_eq!(
+                epub.section_by_href("OEBPS/notes.xhtml").await.unwrap(),
+                "Notes\nFirst note."
+            );
+            assert!(matches!(
+                epub.section_by_href("OEBPS/notes.xhtml#note3").await,
+                Err(lexepub::LexEpubError::MissingFile(message)) if message.contains("note3")
+            ));
+            assert!(matches!(
+                epub.section_by_href("OEBPS/missing.xhtml#note1").await,
+                Err(lexepub::LexEpubError::MissingFile(_))
+            ));
+        });
+    }
+
+    #[test]
+    fn test_archive_overview() {
+        futures::executor::block_on(async {
+            for &epub_path in &existing_epubs() {
+                let mut epub = LexEpub::open(epub_path).await.unwrap();
+                let overview = epub.archive_overview().await.unwrap();
+                assert!(overview.has_mimetype, "{}", epub_path);
+                assert!(overview.has_container, "{}", epub_path);
+                assert!(overview.opf_count >= 1, "{}", epub_path);
+                assert!(overview.entry_count >= 4, "{}", epub_path);
+            }
+
+            // No mimetype, no container: still reported, not an error. An
+            // `.opf` file nothing declares is not a package document.
+            let bytes = common::build_archive(&[("OEBPS/content.opf", b"<package/>")]);
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let overview = epub.archive_overview().await.unwrap();
+            assert_eq!(
+                overview,
+                lexepub::ArchiveOverview {
+                    entry_count: 1,
+                    has_mimetype: false,
+                    has_container: false,
+                    opf_count: 0,
+                    ncx_count: 0,
+                    nav_count: 0,
+                }
+            );
+
+            // The package is whatever container.xml declares, whatever its
+            // extension
+            let container = r#"<?xml version="1.0"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/package.xml" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>"#;
+            let bytes = common::build_archive(&[
+                ("mimetype", b"application/epub+zip"),
+                ("META-INF/container.xml", container.as_bytes()),
+                ("OEBPS/package.xml", b"<package/>"),
+                ("OEBPS/old.opf", b"<package/>"),
+            ]);
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            assert_eq!(epub.archive_overview().await.unwrap().opf_count, 1);
+        });
+    }
+
+    #[test]
+    fn test_xml_base_rebases_relative_links() {
+        fn srcs(node: &lexepub::AstNode, out: &mut Vec<String>) {
+            if let lexepub::AstNode::Element {
+                attrs, children, ..
+            } = node
+            {
+                if let Some(src) = attrs.get("src") {
+                    out.push(src.clone());
+                }
+                for child in children {
+                    srcs(child, out);
+                }
+            }
+        }
+
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Bases</dc:title></metadata>
+  <manifest><item id="ch1" href="text/ch1.xhtml" media-type="application/xhtml+xml"/></manifest>
+  <spine><itemref idref="ch1"/></spine>
+</package>"#;
+            let ch1 = common::xhtml(
+                "",
+                r#"<p><img src="local.png" alt="" /></p>
+                <section xml:base="../media/"><img src="fig.png" alt="" />
+                  <div xml:base="plates/extra.xhtml"><img src="plate.png" alt="" /></div>
+                </section>"#,
+            );
+            let bytes = common::build_epub(
+                opf,
+                &[
+                    ("OEBPS/text/ch1.xhtml", &ch1),
+                    ("OEBPS/media/fig.png", "PNG"),
+                ],
+            );
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let chapter = epub.chapter(0).await.unwrap();
+            let mut found = Vec::new();
+            srcs(chapter.ast.as_ref().unwrap(), &mut found);
+            assert_eq!(
+                found,
+                vec![
+                    "OEBPS/text/local.png",
+                    "OEBPS/media/fig.png",
+                    "OEBPS/media/plates/plate.png",
+                ]
+            );
+
+            // Standalone export inlines the image the base points at
+            let html = epub.chapter_standalone_html(0).await.unwrap();
+            assert!(html.contains(r#"src="data:image/png;base64,UE5H""#));
+        });
+    }
+
+    #[test]
+    fn test_validate_fails_fast_on_structure() {
+        use lexepub::LexEpubError;
+
+        futures::executor::block_on(async {
+            let opf = |spine: &str| {
+                format!(
+                    r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Valid</dc:title></metadata>
+  <manifest><item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/></manifest>
+  <spine>{spine}</spine>
+</package>"#
+                )
+            };
+            let ch1 = common::xhtml("One", "<p>Text.</p>");
+            let validate = |bytes: bytes::Bytes| async move {
+                LexEpub::from_bytes(bytes).await.unwrap().validate().await
+            };
+
+            let good = common::build_epub(
+                &opf(r#"<itemref idref="ch1"/>"#),
+                &[("OEBPS/ch1.xhtml", &ch1)],
+            );
+            assert!(validate(good).await.is_ok());
+
+            let dangling = common::build_epub(
+                &opf(r#"<itemref idref="ch1"/><itemref idref="ghost"/>"#),
+                &[("OEBPS/ch1.xhtml", &ch1)],
+            );
+            match validate(dangling).await {
+                Err(LexEpubError::MissingFile(message)) => assert!(message.contains("ghost")),
+                other => panic!("unexpected {:?}", other),
+            }
+
+            let wrong_mimetype = common::build_archive(&[
+                ("mimetype", b"application/zip"),
+                ("META-INF/container.xml", common::CONTAINER_XML.as_bytes()),
+            ]);
+            assert!(matches!(
+                validate(wrong_mimetype).await,
+                Err(LexEpubError::InvalidFormat(_))
+            ));
+
+            let no_container = common::build_archive(&[("mimetype", b"application/epub+zip")]);
+            match validate(no_container).await {
+                Err(LexEpubError::MissingFile(message)) => {
+                    assert!(message.contains("META-INF/container.xml"))
+                }
+                other => panic!("unexpected {:?}", other),
+            }
+
+            let no_opf = common::build_archive(&[
+                ("mimetype", b"application/epub+zip"),
+                ("META-INF/container.xml", common::CONTAINER_XML.as_bytes()),
+            ]);
+            match validate(no_opf).await {
+                Err(LexEpubError::MissingFile(message)) => {
+                    assert!(message.contains("OEBPS/content.opf"))
+                }
+                other => panic!("unexpected {:?}", other),
+            }
+        });
+    }
+
+    #[test]
+    fn test_metadata_spine_hrefs_in_reading_order() {
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Order</dc:title></metadata>
+  <manifest>
+    <item id="a" href="text/a.xhtml" media-type="application/xhtml+xml"/>
+    <item id="b" href="./text/b.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="b"/><itemref idref="lost"/><itemref idref="a"/></spine>
+</package>"#;
+            let a = common::xhtml("A", "<p>A.</p>");
+            let b = common::xhtml("B", "<p>B.</p>");
+            let bytes = common::build_epub(
+                opf,
+                &[("OEBPS/text/a.xhtml", &a), ("OEBPS/text/b.xhtml", &b)],
+            );
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let metadata = epub.get_metadata().await.unwrap();
+            assert_eq!(metadata.spine, vec!["b", "lost", "a"]);
+            // Unresolved idrefs keep their slot so indices match `spine`
+            assert_eq!(
+                metadata.spine_hrefs,
+                vec!["OEBPS/text/b.xhtml", "", "OEBPS/text/a.xhtml"]
+            );
+
+            let json = serde_json::to_string(&metadata).unwrap();
+            let restored: lexepub::EpubMetadata = serde_json::from_str(&json).unwrap();
+            assert_eq!(restored.spine_hrefs, metadata.spine_hrefs);
+            assert_eq!(restored.spine, metadata.spine);
+        });
+    }
+
+    #[test]
+    fn test_book_ast_is_a_forest_in_spine_order() {
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Forest</dc:title></metadata>
+  <manifest>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch2" href="ch2.xhtml" media-type="application/xhtml+xml"/>
+    <item id="art" href="art.png" media-type="image/png"/>
+  </manifest>
+  <spine><itemref idref="ch2"/><itemref idref="ch1"/><itemref idref="art"/></spine>
+</package>"#;
+            let ch1 = common::xhtml("", "<p>First.</p>");
+            let ch2 = common::xhtml("", "<p>Second.</p>");
+            let bytes = common::build_epub(
+                opf,
+                &[
+                    ("OEBPS/ch1.xhtml", &ch1),
+                    ("OEBPS/ch2.xhtml", &ch2),
+                    ("OEBPS/art.png", "PNG"),
+                ],
+            );
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let chapter_count = epub.extract_ast().await.unwrap().len();
+            let forest = epub.book_ast().await.unwrap();
+            assert_eq!(forest.len(), chapter_count);
+            assert_eq!(forest.len(), 3);
+            assert!(forest
+                .iter()
+                .all(|root| matches!(root, lexepub::AstNode::Element { .. })));
+            assert!(forest[0].to_html().contains("<p>Second.</p>"));
+            assert!(forest[1].to_html().contains("<p>First.</p>"));
+            assert_eq!(forest[2].to_html(), "");
+        });
+    }
+
+    #[test]
+    fn test_on_chapter_error_substitutes_placeholder() {
+        use lexepub::{ErrorAction, LexEpubOptions};
+
+        let opf = r#"<?xml version="1.0"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Gaps</dc:title></metadata>
+  <manifest>
+    <item id="c1" href="c1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="c2" href="missing.xhtml" media-type="application/xhtml+xml"/>
+    <item id="c3" href="c3.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="c1"/><itemref idref="c2"/><itemref idref="c3"/></spine>
+</package>"#;
+        let data = common::build_epub(
+            opf,
+            &[
+                ("OEBPS/c1.xhtml", &common::xhtml("", "<p>Before</p>")),
+                ("OEBPS/c3.xhtml", &common::xhtml("", "<p>After</p>")),
+            ],
+        );
+
+        let mut epub = futures::executor::block_on(LexEpub::from_bytes(data.clone())).unwrap();
+        epub.set_options(
+            LexEpubOptions::new()
+                .strict(true)
+                .on_chapter_error(Box::new(|href, _| {
+                    ErrorAction::Substitute(format!("[{href} unavailable]"))
+                })),
+        );
+        let texts = futures::executor::block_on(epub.extract_text_only()).unwrap();
+        assert_eq!(texts.len(), 3);
+        assert_eq!(texts[0], "Before");
+        assert_eq!(texts[1], "[OEBPS/missing.xhtml unavailable]");
+        assert_eq!(texts[2], "After");
+
+        let chapters = futures::executor::block_on(epub.extract_ast()).unwrap();
+        assert_eq!(chapters[1].content, "[OEBPS/missing.xhtml unavailable]");
+        assert_eq!(chapters[1].word_count, 2);
+
+        // chapter_stats consults the handler too, in both memory modes
+        for low_memory in [false, true] {
+            let mut epub = futures::executor::block_on(LexEpub::from_bytes(data.clone())).unwrap();
+            epub.set_options(
+                LexEpubOptions::new()
+                    .low_memory(low_memory)
+                    .on_chapter_error(Box::new(|href, _| {
+                        ErrorAction::Substitute(format!("[{href} unavailable]"))
+                    })),
+            );
+            let stats = futures::executor::block_on(epub.chapter_stats()).unwrap();
+            assert_eq!(
+                stats.iter().map(|s| s.word_count).collect::<Vec<_>>(),
+                vec![1, 2, 1]
+            );
+        }
+
+        let mut epub = futures::executor::block_on(LexEpub::from_bytes(data)).unwrap();
+        epub.set_options(
+            LexEpubOptions::new().on_chapter_error(Box::new(|_, _| ErrorAction::Abort)),
+        );
+        assert!(matches!(
+            futures::executor::block_on(epub.extract_text_only()),
+            Err(lexepub::LexEpubError::ChapterFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_strict_extraction_stops_parsing_at_first_failure() {
+        use lexepub::LexEpubOptions;
+        use std::sync::{Arc, Mutex};
+
+        let opf = r#"<?xml version="1.0"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Gaps</dc:title></metadata>
+  <manifest>
+    <item id="c1" href="c1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="c2" href="missing.xhtml" media-type="application/xhtml+xml"/>
+    <item id="c3" href="c3.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="c1"/><itemref idref="c2"/><itemref idref="c3"/></spine>
+</package>"#;
+        let data = common::build_epub(
+            opf,
+            &[
+                ("OEBPS/c1.xhtml", &common::xhtml("", "<p>Before</p>")),
+                ("OEBPS/c3.xhtml", &common::xhtml("", "<p>After</p>")),
+            ],
+        );
+
+        // The tokenizer sees the text of every chapter that gets parsed
+        let counted = Arc::new(Mutex::new(Vec::new()));
+        let seen = counted.clone();
+        let mut epub = futures::executor::block_on(LexEpub::from_bytes(data)).unwrap();
+        epub.set_options(LexEpubOptions::new().strict(true).word_tokenizer(Box::new(
+            move |text| {
+                seen.lock().unwrap().push(text.to_string());
+                text.split_whitespace().count()
+            },
+        )));
+        assert!(matches!(
+            futures::executor::block_on(epub.extract_ast()),
+            Err(lexepub::LexEpubError::ChapterFailed { .. })
+        ));
+        let counted = counted.lock().unwrap();
+        assert!(counted.iter().any(|text| text.contains("Before")));
+        assert!(!counted.iter().any(|text| text.contains("After")));
+    }
+
+    #[test]
+    fn test_options_configure_extraction() {
+        use futures::StreamExt;
+        use lexepub::core::html_parser::TextExtractOptions;
+        use lexepub::LexEpubOptions;
+
+        let opf = r#"<?xml version="1.0"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Opts</dc:title></metadata>
+  <manifest>
+    <item id="c1" href="c1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="big" href="big.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="c1"/><itemref idref="big"/></spine>
+</package>"#;
+        let small = common::xhtml(
+            "",
+            "<style>p { color: red; }</style><p>Salt &amp; pepper</p>",
+        );
+        let big = common::xhtml("", &"<p>Filler text.</p>".repeat(200));
+        let data = common::build_epub(
+            opf,
+            &[("OEBPS/c1.xhtml", &small), ("OEBPS/big.xhtml", &big)],
+        );
+
+        futures::executor::block_on(async {
+            // Defaults: styles skipped, entities decoded, nothing too large
+            let mut epub = LexEpub::from_bytes(data.clone()).await.unwrap();
+            let text = epub.extract_text_only().await.unwrap();
+            assert_eq!(text.len(), 2);
+            assert_eq!(text[0].trim(), "Salt & pepper");
+
+            let limit = (small.len() + 100) as u64;
+            let options = || {
+                LexEpubOptions::new().max_chapter_size(limit).text_options(
+                    TextExtractOptions::new()
+                        .include_scripts(true)
+                        .keep_entities(true),
+                )
+            };
+            let mut epub = LexEpub::from_bytes_with(data.clone(), options())
+                .await
+                .unwrap();
+            let text = epub.extract_text_only().await.unwrap();
+            assert_eq!(text.len(), 1, "the oversized chapter is skipped");
+            assert!(text[0].contains("p { color: red; }"));
+            assert!(text[0].contains("Salt &amp; pepper"));
+            assert_eq!(epub.extract_ast().await.unwrap().len(), 1);
+
+            let streamed: Vec<_> = epub
+                .extract_chapters_stream()
+                .await
+                .unwrap()
+                .collect()
+                .await;
+            assert_eq!(streamed.len(), 2);
+            assert!(streamed[0].is_ok());
+            assert!(matches!(
+                streamed[1],
+                Err(lexepub::LexEpubError::ChapterFailed { .. })
+            ));
+
+            let (_, errors) = epub.extract_chapters_verbose().await.unwrap();
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].href, "OEBPS/big.xhtml");
+            assert!(matches!(
+                errors[0].error,
+                lexepub::LexEpubError::ChapterError(_)
+            ));
+
+            // Single-chapter accessors enforce the limit too
+            assert!(epub.chapter(0).await.is_ok());
+            let too_large = |result: lexepub::Result<_>| {
+                matches!(result, Err(lexepub::LexEpubError::ChapterError(_)))
+            };
+            assert!(too_large(epub.chapter(1).await.map(|_| ())));
+            assert!(too_large(epub.chapter_standalone_html(1).await.map(|_| ())));
+            assert!(too_large(
+                epub.section_by_href("OEBPS/big.xhtml").await.map(|_| ())
+            ));
+            assert!(too_large(
+                epub.extract_hrefs(&["big.xhtml".to_string()])
+                    .await
+                    .map(|_| ())
+            ));
+
+            let mut epub = LexEpub::from_bytes_with(data.clone(), options().strict(true))
+                .await
+                .unwrap();
+            assert!(matches!(
+                epub.extract_text_only().await,
+                Err(lexepub::LexEpubError::ChapterFailed { .. })
+            ));
+
+            // Low-memory mode keeps no parsed chapters around
+            let low = LexEpubOptions::new().low_memory(true);
+            let mut epub = LexEpub::from_bytes_with(data, low).await.unwrap();
+            assert_eq!(epub.extract_ast().await.unwrap().len(), 2);
+            epub.chapter(0).await.unwrap();
+            assert_eq!(epub.cached_chapter_count(), 0);
+            assert!(epub.cached_chapter_texts().is_none());
+            epub.extract_text_only().await.unwrap();
+            assert!(epub.cached_chapter_texts().is_none());
+
+            // set_text_options is reflected in options()
+            epub.set_text_options(TextExtractOptions::new().include_scripts(true));
+            assert!(
+                epub.options()
+                    .text_options
+                    .as_ref()
+                    .unwrap()
+                    .include_scripts
+            );
+        });
+    }
+
+    #[test]
+    fn test_extract_chapters_verbose_reports_failures() {
+        let opf = r#"<?xml version="1.0"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Gaps</dc:title></metadata>
+  <manifest>
+    <item id="c1" href="c1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="c2" href="missing.xhtml" media-type="application/xhtml+xml"/>
+    <item id="c3" href="c3.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="c1"/><itemref idref="c2"/><itemref idref="ghost"/><itemref idref="c3"/></spine>
+</package>"#;
+        let data = common::build_epub(
+            opf,
+            &[
+                ("OEBPS/c1.xhtml", &common::xhtml("", "<p>Before</p>")),
+                ("OEBPS/c3.xhtml", &common::xhtml("", "<p>After</p>")),
+            ],
+        );
+
+        let mut epub = futures::executor::block_on(LexEpub::from_bytes(data)).unwrap();
+        let (chapters, errors) =
+            futures::executor::block_on(epub.extract_chapters_verbose()).unwrap();
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[0].content.trim(), "Before");
+        assert_eq!(chapters[1].content.trim(), "After");
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].id, "c2");
+        assert_eq!(errors[0].href, "OEBPS/missing.xhtml");
+        assert!(matches!(
+            errors[0].error,
+            lexepub::LexEpubError::MissingFile(_)
+        ));
+        assert_eq!(errors[1].id, "ghost");
+        assert_eq!(errors[1].href, "");
+
+        let text = futures::executor::block_on(epub.extract_text_only()).unwrap();
+        assert_eq!(text.len(), 2);
+    }
+
+    #[test]
+    fn test_extract_markdown_per_chapter() {
+        let opf = r#"<?xml version="1.0"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Md</dc:title></metadata>
+  <manifest>
+    <item id="c1" href="c1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="c2" href="c2.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="c1"/><itemref idref="c2"/></spine>
+</package>"#;
+        let data = common::build_epub(
+            opf,
+            &[
+                (
+                    "OEBPS/c1.xhtml",
+                    &common::xhtml("", "<h2>One</h2><p>It <em>was</em> dark.</p>"),
+                ),
+                ("OEBPS/c2.xhtml", &common::xhtml("", "<p>Two</p>")),
+            ],
+        );
+        let mut epub = futures::executor::block_on(LexEpub::from_bytes(data)).unwrap();
+        let markdown = futures::executor::block_on(epub.extract_markdown()).unwrap();
+        assert_eq!(markdown, vec!["## One\n\nIt *was* dark.", "Two"]);
+    }
+
+    #[test]
+    fn test_blurb_from_description_or_first_body_paragraph() {
+        let package = |description: &str| {
+            format!(
+                r#"<?xml version="1.0"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Teaser</dc:title>{description}</metadata>
+  <manifest>
+    <item id="cover" href="cover.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="cover"/><itemref idref="ch1"/></spine>
+</package>"#
+            )
+        };
+        let body = "<h1>Chapter One</h1><p>A short line.</p>\
+            <p>The lighthouse keeper had not spoken to anyone in eleven days, \
+            and the gulls had started to answer him back.</p>";
+        let files = [
+            (
+                "OEBPS/cover.xhtml",
+                common::xhtml("", "<p>Front cover of this edition, with a long enough caption to count as a paragraph if it were body matter.</p>"),
+            ),
+            ("OEBPS/ch1.xhtml", common::xhtml("", body)),
+        ];
+        let files: Vec<(&str, &str)> = files.iter().map(|(p, c)| (*p, c.as_str())).collect();
+
+        let data = common::build_epub(&package(""), &files);
+        let mut epub = futures::executor::block_on(LexEpub::from_bytes(data)).unwrap();
+        let full = futures::executor::block_on(epub.blurb(1000))
+            .unwrap()
+            .unwrap();
+        assert!(full.starts_with("The lighthouse keeper"));
+        assert!(full.ends_with("answer him back."));
+        let short = futures::executor::block_on(epub.blurb(40))
+            .unwrap()
+            .unwrap();
+        assert_eq!(short, "The lighthouse keeper had not spoken to…");
+        assert!(short.chars().count() <= 40);
+        assert_eq!(
+            futures::executor::block_on(epub.blurb(0))
+                .unwrap()
+                .as_deref(),
+            Some("")
+        );
+
+        let data = common::build_epub(
+            &package("<dc:description>  A tale\n  of gulls.</dc:description>"),
+            &files,
+        );
+        let mut epub = futures::executor::block_on(LexEpub::from_bytes(data)).unwrap();
+        assert_eq!(
+            futures::executor::block_on(epub.blurb(100))
+                .unwrap()
+                .as_deref(),
+            Some("A tale of gulls.")
+        );
+    }
+
+    #[test]
+    fn test_reading_time_from_word_counts() {
+        use std::time::Duration;
+
+        let opf = r#"<?xml version="1.0"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Timed</dc:title></metadata>
+  <manifest>
+    <item id="c1" href="c1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="c2" href="c2.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="c1"/><itemref idref="c2"/></spine>
+</package>"#;
+        let words = |n: usize| format!("<p>{}</p>", vec!["word"; n].join(" "));
+        let data = common::build_epub(
+            opf,
+            &[
+                ("OEBPS/c1.xhtml", &common::xhtml("", &words(300))),
+                ("OEBPS/c2.xhtml", &common::xhtml("", &words(100))),
+            ],
+        );
+        let mut epub = futures::executor::block_on(LexEpub::from_bytes(data)).unwrap();
+
+        assert_eq!(
+            futures::executor::block_on(epub.chapter_reading_times(200)).unwrap(),
+            vec![Duration::from_secs(90), Duration::from_secs(30)]
+        );
+        assert_eq!(
+            futures::executor::block_on(epub.reading_time(100)).unwrap(),
+            Duration::from_secs(240)
+        );
+        assert_eq!(
+            futures::executor::block_on(epub.reading_time_default()).unwrap(),
+            Duration::from_secs(120)
+        );
+        assert!(matches!(
+            futures::executor::block_on(epub.reading_time(0)),
+            Err(lexepub::LexEpubError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_images_lists_manifest_images_with_media_types() {
+        let opf = r#"<?xml version="1.0"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Pictures</dc:title></metadata>
+  <manifest>
+    <item id="c1" href="text/c1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="photo" href="images/photo.jpg" media-type="image/jpeg"/>
+    <item id="map" href="images/map.svg" media-type="image/svg+xml"/>
+    <item id="gone" href="images/gone.png" media-type="image/png"/>
+  </manifest>
+  <spine><itemref idref="c1"/></spine>
+</package>"#;
+        let data = common::build_epub(
+            opf,
+            &[
+                ("OEBPS/text/c1.xhtml", &common::xhtml("", "<p>Hi</p>")),
+                ("OEBPS/images/photo.jpg", "jpeg bytes"),
+                ("OEBPS/images/map.svg", "<svg/>"),
+            ],
+        );
+        let mut epub = futures::executor::block_on(LexEpub::from_bytes(data)).unwrap();
+        let images = futures::executor::block_on(epub.images()).unwrap();
+        assert_eq!(
+            images,
+            vec![
+                (
+                    "OEBPS/images/map.svg".to_string(),
+                    "image/svg+xml".to_string(),
+                    b"<svg/>".to_vec()
+                ),
+                (
+                    "OEBPS/images/photo.jpg".to_string(),
+                    "image/jpeg".to_string(),
+                    b"jpeg bytes".to_vec()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chapter_standalone_html_inlines_css_and_images() {
+        let opf = r#"<?xml version="1.0"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Export</dc:title></metadata>
+  <manifest>
+    <item id="c1" href="text/c1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="css" href="styles/book.css" media-type="text/css"/>
+    <item id="pic" href="images/pic.png" media-type="image/png"/>
+  </manifest>
+  <spine><itemref idref="c1"/></spine>
+</package>"#;
+        let chapter = r#"<?xml version="1.0" encoding="UTF-8"?>
+<html xmlns="http://www.w3.org/1999/xhtml"><head><title>One</title>
+<link rel="stylesheet" type="text/css" href="../styles/book.css" />
+</head><body><script>alert("hi")</script><p onclick="steal()">Look:</p><img src="../images/pic.png" alt="A picture" /></body></html>"#;
+        let data = common::build_epub(
+            opf,
+            &[
+                ("OEBPS/text/c1.xhtml", chapter),
+                ("OEBPS/styles/book.css", "p { color: teal; }"),
+                ("OEBPS/images/pic.png", "PNG"),
+            ],
+        );
+        let mut epub = futures::executor::block_on(LexEpub::from_bytes(data)).unwrap();
+        let html = futures::executor::block_on(epub.chapter_standalone_html(0)).unwrap();
+
+        assert!(html.starts_with("<!DOCTYPE html>\n<html"));
+        assert!(html.ends_with("</html>"));
+        assert!(html.contains("<style>p { color: teal; }\n</style></head>"));
+        assert!(html.contains(r#"src="data:image/png;base64,UE5H""#));
+        assert!(!html.contains("<link"));
+        assert!(!html.contains("../images/pic.png"));
+        assert!(html.contains("<p>Look:</p>"));
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("onclick"));
+
+        assert!(futures::executor::block_on(epub.chapter_standalone_html(1)).is_err());
+    }
+
+    #[test]
+    fn test_non_markup_spine_items_are_empty_in_every_path() {
+        use futures::StreamExt;
+
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <manifest>
+    <item id="plate" href="plate.png" media-type="image/png"/>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="plate"/><itemref idref="ch1"/></spine>
+</package>"#;
+            let ch1 = common::xhtml("", "<p>Text</p>");
+            let bytes = common::build_epub(
+                opf,
+                &[
+                    ("OEBPS/plate.png", "<p>not markup</p>"),
+                    ("OEBPS/ch1.xhtml", &ch1),
+                ],
+            );
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+
+            let eager = epub.extract_text_only().await.unwrap();
+            assert_eq!(eager[0], "");
+            for mut stream in [
+                epub.extract_chapters_stream().await.unwrap(),
+                epub.extract_ast_stream().await.unwrap(),
+            ] {
+                let plate = stream.next().await.unwrap().unwrap();
+                assert_eq!(plate.chapter_info.media_type, "image/png");
+                assert_eq!(plate.content, "");
+                assert_eq!(plate.word_count, 0);
+                assert_eq!(stream.next().await.unwrap().unwrap().content, eager[1]);
+            }
+        });
+    }
+
+    #[test]
+    fn test_chapters_report_manifest_media_type() {
+        use futures::StreamExt;
+
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <manifest>
+    <item id="map" href="map.svg" media-type="image/svg+xml"/>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="map"/><itemref idref="ch1"/></spine>
+</package>"#;
+            let svg = r#"<svg xmlns="http://www.w3.org/2000/svg"><text>Harbour map</text></svg>"#;
+            let ch1 = common::xhtml("", "<p>Text</p>");
+            let bytes =
+                common::build_epub(opf, &[("OEBPS/map.svg", svg), ("OEBPS/ch1.xhtml", &ch1)]);
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+
+            let media_types = |chapters: &[lexepub::ParsedChapter]| {
+                chapters
+                    .iter()
+                    .map(|c| (c.chapter_info.id.clone(), c.chapter_info.media_type.clone()))
+                    .collect::<Vec<_>>()
+            };
+            let expected = vec![
+                ("map".to_string(), "image/svg+xml".to_string()),
+                ("ch1".to_string(), "application/xhtml+xml".to_string()),
+            ];
+
+            let streamed: Vec<_> = epub
+                .extract_chapters_stream()
+                .await
+                .unwrap()
+                .map(|chapter| chapter.unwrap())
+                .collect()
+                .await;
+            assert_eq!(media_types(&streamed), expected);
+            assert_eq!(streamed[0].content, "Harbour map");
+
+            let chapters = epub.extract_ast().await.unwrap();
+            assert_eq!(media_types(&chapters), expected);
+            let first = epub.chapter(0).await.unwrap();
+            assert_eq!(first.chapter_info.media_type, "image/svg+xml");
+        });
+    }
+
+    #[test]
+    fn test_drm_encrypted_book_reports_encrypted_resources() {
+        futures::executor::block_on(async {
+            let encryption_xml = r#"<?xml version="1.0"?>
+<encryption xmlns="urn:oasis:names:tc:opendocument:xmlns:container"
+            xmlns:enc="http://www.w3.org/2001/04/xmlenc#">
+  <enc:EncryptedData>
+    <enc:EncryptionMethod Algorithm="http://www.w3.org/2001/04/xmlenc#aes128-cbc"/>
+    <enc:CipherData><enc:CipherReference URI="OEBPS/ch1.xhtml"/></enc:CipherData>
+  </enc:EncryptedData>
+  <enc:EncryptedData>
+    <enc:EncryptionMethod Algorithm="http://www.idpf.org/2008/embedding"/>
+    <enc:CipherData><enc:CipherReference URI="OEBPS/fonts/body.otf"/></enc:CipherData>
+  </enc:EncryptedData>
+</encryption>"#;
+            let bytes = common::build_archive(&[
+                ("mimetype", b"application/epub+zip"),
+                ("META-INF/container.xml", common::CONTAINER_XML.as_bytes()),
+                ("META-INF/encryption.xml", encryption_xml.as_bytes()),
+                ("OEBPS/content.opf", RESOURCE_OPF.as_bytes()),
+                ("OEBPS/ch1.xhtml", &[0x8f, 0x02, 0xff, 0x13]),
+            ]);
+
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            match epub.get_metadata().await {
+                Err(lexepub::LexEpubError::Encrypted(message)) => {
+                    assert_eq!(message, "1 resource is protected by DRM: OEBPS/ch1.xhtml");
+                }
+                other => panic!("expected Encrypted, got {:?}", other),
+            }
+            assert!(matches!(
+                epub.extract_text_only().await,
+                Err(lexepub::LexEpubError::Encrypted(_))
+            ));
+            assert!(matches!(
+                epub.extract_ast().await,
+                Err(lexepub::LexEpubError::Encrypted(_))
+            ));
+        });
+    }
+
+    #[test]
+    fn test_unhandled_report_names_vendor_meta() {
+        let opf = r#"<?xml version="1.0"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>Vendor</dc:title>
+    <meta property="vendor:foo">bar</meta>
+  </metadata>
+  <manifest><item id="c1" href="c1.xhtml" media-type="application/xhtml+xml"/></manifest>
+  <spine><itemref idref="c1"/></spine>
+</package>"#;
+        let data = common::build_epub(opf, &[("OEBPS/c1.xhtml", &common::xhtml("", "<p>x</p>"))]);
+        let mut epub = futures::executor::block_on(LexEpub::from_bytes(data)).unwrap();
+        assert_eq!(
+            futures::executor::block_on(epub.unhandled_report()).unwrap(),
+            vec!["meta property vendor:foo"]
+        );
+    }
+
+    /// Seekable reader that records every absolute seek target
+    struct SeekLog {
+        inner: std::io::Cursor<Vec<u8>>,
+        seeks: std::sync::Arc<std::sync::Mutex<Vec<u64>>>,
+    }
+
+    impl std::io::Read for SeekLog {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.inner.read(buf)
+        }
+    }
+
+    impl std::io::Seek for SeekLog {
+        fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+            let offset = self.inner.seek(pos)?;
+            self.seeks.lock().unwrap().push(offset);
+            Ok(offset)
+        }
+    }
+
+    #[test]
+    fn test_container_read_once_across_metadata_and_text() {
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Once</dc:title></metadata>
+  <manifest>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch2" href="ch2.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="ch1"/><itemref idref="ch2"/></spine>
+</package>"#;
+            let ch1 = common::xhtml("One", "<p>First.</p>");
+            let ch2 = common::xhtml("Two", "<p>Second.</p>");
+            let bytes =
+                common::build_epub(opf, &[("OEBPS/ch1.xhtml", &ch1), ("OEBPS/ch2.xhtml", &ch2)]);
+            // Local file header of container.xml: the first occurrence of
+            // its name follows the header's fixed 30 bytes
+            let name = b"META-INF/container.xml";
+            let header = bytes
+                .windows(name.len())
+                .position(|window| window == name)
+                .unwrap() as u64
+                - 30;
+
+            let seeks = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+            let reader = SeekLog {
+                inner: std::io::Cursor::new(bytes.to_vec()),
+                seeks: seeks.clone(),
+            };
+            let mut epub = LexEpub::from_sync_reader(reader).unwrap();
+
+            let metadata = epub.get_metadata().await.unwrap();
+            assert_eq!(metadata.title.as_deref(), Some("Once"));
+            let texts = epub.extract_text_only().await.unwrap();
+            assert_eq!(texts.len(), 2);
+            let chapters = epub.extract_ast().await.unwrap();
+            assert_eq!(chapters.len(), 2);
+            let mut stream = epub.extract_chapters_stream().await.unwrap();
+            let mut streamed = 0;
+            while let Some(chapter) = futures::StreamExt::next(&mut stream).await {
+                chapter.unwrap();
+                streamed += 1;
+            }
+            assert_eq!(streamed, 2);
+
+            let container_reads = seeks
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|&&offset| offset == header)
+                .count();
+            assert_eq!(container_reads, 1);
+        });
+    }
+
+    #[test]
+    fn test_full_extraction_opens_the_archive_once() {
+        futures::executor::block_on(async {
+            let items: String = (1..=6)
+                .map(|i| {
+                    format!(
+                        r#"<item id="ch{i}" href="ch{i}.xhtml" media-type="application/xhtml+xml"/>"#
+                    )
+                })
+                .collect();
+            let refs: String = (1..=6)
+                .map(|i| format!(r#"<itemref idref="ch{i}"/>"#))
+                .collect();
+            let opf = format!(
+                r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Six</dc:title></metadata>
+  <manifest>{items}</manifest>
+  <spine>{refs}</spine>
+</package>"#
+            );
+            let chapters: Vec<(String, String)> = (1..=6)
+                .map(|i| {
+                    (
+                        format!("OEBPS/ch{i}.xhtml"),
+                        common::xhtml("", &format!("<p>Chapter {i}.</p>")),
+                    )
+                })
+                .collect();
+            let files: Vec<(&str, &str)> = chapters
+                .iter()
+                .map(|(path, body)| (path.as_str(), body.as_str()))
+                .collect();
+            let bytes = common::build_epub(&opf, &files);
+            let archive_len = bytes.len() as u64;
+
+            let seeks = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+            let reader = SeekLog {
+                inner: std::io::Cursor::new(bytes.to_vec()),
+                seeks: seeks.clone(),
+            };
+            let mut epub = LexEpub::from_sync_reader(reader).unwrap();
+            let parsed = epub.extract_ast().await.unwrap();
+            assert_eq!(parsed.len(), 6);
+            assert!(parsed[5].content.contains("Chapter 6."));
+
+            // Opening the archive locates the end of central directory by
+            // seeking to the end of the file
+            let opens = seeks
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|&&offset| offset == archive_len)
+                .count();
+            assert_eq!(opens, 1);
+        });
+    }
 }
@@ -1,3 +1,5 @@
+mod common;
+
 use lexepub::epub::LexEpub;
 use lexepub::error::LexEpubError;
 use std::path::Path;
@@ -55,14 +57,42 @@ mod error_tests {
     #[test]
     fn test_invalid_xml_parsing() {
         futures::executor::block_on(async {
-            // Test with malformed XML would require mocking
-            // For now, test that valid XML works
             let test_epub = Path::new("examples/epubs/test-book.epub");
             if test_epub.exists() {
                 let mut epub = LexEpub::open(test_epub).await.unwrap();
                 let result = epub.get_metadata().await;
                 assert!(result.is_ok(), "Valid EPUB should parse correctly");
             }
+
+            // The mismatched `</package>` starts at byte 30
+            let opf = r#"<package><metadata><dc:title/></package>"#;
+            let bytes = common::build_epub(opf, &[]);
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            let error = epub.get_metadata().await.unwrap_err();
+            match &error {
+                LexEpubError::XmlInContext { file, position, .. } => {
+                    assert_eq!(file, "OEBPS/content.opf");
+                    assert_eq!(*position, 30);
+                }
+                other => panic!("expected XmlInContext, got {:?}", other),
+            }
+            let display = error.to_string();
+            assert!(display.contains("OEBPS/content.opf"), "{}", display);
+            assert!(display.contains("byte 30"), "{}", display);
+
+            let bytes = common::build_archive(&[
+                ("mimetype", b"application/epub+zip"),
+                (
+                    "META-INF/container.xml",
+                    b"<container><rootfiles></container>",
+                ),
+            ]);
+            let mut epub = LexEpub::from_bytes(bytes).await.unwrap();
+            assert!(matches!(
+                epub.get_metadata().await,
+                Err(LexEpubError::XmlInContext { file, position: 22, .. })
+                    if file == "META-INF/container.xml"
+            ));
         });
     }
 
@@ -122,6 +152,11 @@ mod error_tests {
         // TODO: quick_xml::Error variants depend on version, using a simple test
         let xml_err = quick_xml::Error::Io(std::sync::Arc::new(std::io::Error::other("test")));
         let _xml = LexEpubError::Xml(xml_err);
+        let _xml_in_context = LexEpubError::XmlInContext {
+            file: "OEBPS/content.opf".to_string(),
+            position: 0,
+            source: quick_xml::Error::Io(std::sync::Arc::new(std::io::Error::other("test"))),
+        };
         let _invalid = LexEpubError::InvalidFormat("test".to_string());
         let _missing = LexEpubError::MissingFile("test".to_string());
         let _html = LexEpubError::Html("test".to_string());
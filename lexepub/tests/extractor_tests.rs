@@ -1,5 +1,8 @@
+mod common;
+
 #[cfg(test)]
 mod tests {
+    use crate::common;
     use bytes::Bytes;
     use lexepub::core::extractor::EpubExtractor;
     use std::path::Path;
@@ -43,4 +46,135 @@ mod tests {
             }
         });
     }
+
+    #[test]
+    fn test_read_entry_by_index() {
+        futures::executor::block_on(async {
+            let opf = r#"<?xml version="1.0"?><package version="3.0" xmlns="http://www.idpf.org/2007/opf"/>"#;
+            let bytes = common::build_epub(opf, &[]);
+            let extractor = EpubExtractor::from_bytes(bytes.clone()).await.unwrap();
+
+            let (name, data) = extractor.read_entry_by_index(0).await.unwrap();
+            assert_eq!(name, "mimetype");
+            assert_eq!(data, b"application/epub+zip");
+
+            let (name, data) = extractor.read_entry_by_index(2).await.unwrap();
+            assert_eq!(name, "OEBPS/content.opf");
+            assert_eq!(data, opf.as_bytes());
+
+            assert!(extractor.read_entry_by_index(3).await.is_err());
+
+            let mut epub = lexepub::LexEpub::from_bytes(bytes).await.unwrap();
+            let (name, _) = epub.read_entry_by_index(1).await.unwrap();
+            assert_eq!(name, "META-INF/container.xml");
+        });
+    }
+
+    #[test]
+    fn test_repeated_reads_share_the_central_directory() {
+        futures::executor::block_on(async {
+            let ch1 = common::xhtml("One", "<p>First</p>");
+            let bytes = common::build_epub(
+                r#"<package version="3.0" xmlns="http://www.idpf.org/2007/opf"/>"#,
+                &[("OEBPS/ch1.xhtml", &ch1), ("OEBPS/style.css", "p {}")],
+            );
+            let path = std::env::temp_dir().join("lexepub_extractor_cache_test.epub");
+            std::fs::write(&path, &bytes).unwrap();
+
+            for extractor in [
+                EpubExtractor::from_bytes(bytes.clone()).await.unwrap(),
+                EpubExtractor::open(path.clone()).await.unwrap(),
+            ] {
+                let clone = extractor.clone();
+                for _ in 0..3 {
+                    assert_eq!(
+                        extractor.read_file("OEBPS/ch1.xhtml").await.unwrap(),
+                        ch1.as_bytes()
+                    );
+                    assert_eq!(clone.read_file("OEBPS/style.css").await.unwrap(), b"p {}");
+                }
+                assert!(matches!(
+                    clone.read_file("OEBPS/missing.xhtml").await,
+                    Err(lexepub::LexEpubError::MissingFile(_))
+                ));
+                let mut sink = Vec::new();
+                let written = clone
+                    .read_file_to_writer(
+                        "OEBPS/ch1.xhtml",
+                        &mut futures::io::Cursor::new(&mut sink),
+                    )
+                    .await
+                    .unwrap();
+                assert_eq!(written as usize, ch1.len());
+            }
+            std::fs::remove_file(&path).ok();
+        });
+    }
+
+    #[test]
+    fn test_read_files_batches_through_one_archive() {
+        futures::executor::block_on(async {
+            let ch1 = common::xhtml("One", "<p>First</p>");
+            let ch2 = common::xhtml("Two", "<p>Second</p>");
+            let bytes = common::build_epub(
+                r#"<package version="3.0" xmlns="http://www.idpf.org/2007/opf"/>"#,
+                &[("OEBPS/ch1.xhtml", &ch1), ("OEBPS/ch2.xhtml", &ch2)],
+            );
+            let path = std::env::temp_dir().join("lexepub_extractor_batch_test.epub");
+            std::fs::write(&path, &bytes).unwrap();
+            let paths = [
+                "OEBPS/ch2.xhtml".to_string(),
+                "OEBPS/missing.xhtml".to_string(),
+                "OEBPS/ch1.xhtml".to_string(),
+            ];
+
+            for extractor in [
+                EpubExtractor::from_bytes(bytes.clone()).await.unwrap(),
+                EpubExtractor::open(path.clone()).await.unwrap(),
+                EpubExtractor::from_sync_reader(std::io::Cursor::new(bytes.to_vec())).unwrap(),
+            ] {
+                let files = extractor.read_files(&paths).await.unwrap();
+                assert_eq!(files.len(), 3);
+                assert_eq!(files[0].as_ref().unwrap(), ch2.as_bytes());
+                assert!(matches!(
+                    files[1],
+                    Err(lexepub::LexEpubError::MissingFile(_))
+                ));
+                assert_eq!(files[2].as_ref().unwrap(), ch1.as_bytes());
+            }
+            std::fs::remove_file(&path).ok();
+
+            let unreadable = EpubExtractor::from_bytes(Bytes::from("not a zip"))
+                .await
+                .unwrap();
+            assert!(unreadable.read_files(&paths).await.is_err());
+        });
+    }
+
+    #[test]
+    fn test_read_files_with_stops_when_the_callback_fails() {
+        futures::executor::block_on(async {
+            let ch1 = common::xhtml("One", "<p>First</p>");
+            let ch2 = common::xhtml("Two", "<p>Second</p>");
+            let bytes = common::build_epub(
+                r#"<package version="3.0" xmlns="http://www.idpf.org/2007/opf"/>"#,
+                &[("OEBPS/ch1.xhtml", &ch1), ("OEBPS/ch2.xhtml", &ch2)],
+            );
+            let extractor = EpubExtractor::from_bytes(bytes).await.unwrap();
+            let paths = ["OEBPS/ch1.xhtml".to_string(), "OEBPS/ch2.xhtml".to_string()];
+
+            let mut seen = Vec::new();
+            let result = extractor
+                .read_files_with(&paths, |index, file| {
+                    seen.push((index, file.unwrap().len()));
+                    Err(lexepub::LexEpubError::ChapterError("stop".to_string()))
+                })
+                .await;
+            assert!(matches!(
+                result,
+                Err(lexepub::LexEpubError::ChapterError(_))
+            ));
+            assert_eq!(seen, vec![(0, ch1.len())]);
+        });
+    }
 }
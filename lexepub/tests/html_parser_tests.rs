@@ -98,4 +98,353 @@ mod tests {
         let deserialized: Result<AstNode, _> = serde_json::from_str(&serialized.unwrap());
         assert!(deserialized.is_ok());
     }
+
+    #[test]
+    fn test_extract_text_blocks() {
+        use lexepub::core::chapter::TextBlockKind;
+
+        let html = r#"
+            <html>
+                <head><title>Ignored</title></head>
+                <body>
+                    <h2>Heading</h2>
+                    <div><p>First   paragraph.</p><p>Second.</p></div>
+                    <ul><li>Item</li></ul>
+                </body>
+            </html>
+        "#;
+
+        let blocks = lexepub::core::html_parser::extract_text_blocks(html).unwrap();
+        let kinds: Vec<_> = blocks.iter().map(|b| b.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TextBlockKind::Heading(2),
+                TextBlockKind::Paragraph,
+                TextBlockKind::Paragraph,
+                TextBlockKind::ListItem,
+            ]
+        );
+        assert_eq!(blocks[1].text, "First paragraph.");
+    }
+
+    #[test]
+    fn test_inline_elements_stay_in_one_block() {
+        use lexepub::core::chapter::TextBlockKind;
+        use lexepub::core::html_parser::extract_text_blocks;
+
+        let blocks = extract_text_blocks(r#"<p>a <em>b</em> <a href="x">c</a> d</p>"#).unwrap();
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].kind, TextBlockKind::Paragraph);
+        assert_eq!(blocks[0].text, "a b c d");
+    }
+
+    #[test]
+    fn test_normalize_punctuation_option() {
+        use lexepub::core::html_parser::{extract_text_content_with, TextExtractOptions};
+
+        let html = "<p>\u{201C}Wait\u{201D}\u{2014}she said\u{2026} it\u{2019}s fine</p>";
+
+        let faithful = extract_text_content_with(html, &TextExtractOptions::default()).unwrap();
+        assert_eq!(
+            faithful,
+            "\u{201C}Wait\u{201D}\u{2014}she said\u{2026} it\u{2019}s fine"
+        );
+
+        let options = TextExtractOptions::new().normalize_punctuation(true);
+        let normalized = extract_text_content_with(html, &options).unwrap();
+        assert_eq!(normalized, "\"Wait\"--she said... it's fine");
+    }
+
+    #[test]
+    fn test_hr_marker_option() {
+        use lexepub::core::html_parser::{extract_text_content_with, TextExtractOptions};
+
+        let html = "<p>The door closed.</p><hr/><p>Morning came.</p><hr class=\"scene\">";
+
+        let dropped = extract_text_content_with(html, &TextExtractOptions::default()).unwrap();
+        assert_eq!(dropped, "The door closed.\nMorning came.");
+
+        let options = TextExtractOptions::new().hr_marker("* * *");
+        let marked = extract_text_content_with(html, &options).unwrap();
+        assert_eq!(marked, "The door closed.\n* * *\nMorning came.\n* * *");
+    }
+
+    #[cfg(feature = "lowmem")]
+    #[test]
+    fn test_lowmem_extractor_drops_annotations_by_epub_type_token() {
+        use lexepub::core::html_parser::{extract_text_content_with, TextExtractOptions};
+
+        let html = r#"<p>Body.</p>
+<section epub:type="annotation"><section><p>Nested note.</p></section><p>Still the note.</p></section>
+<div epub:type="note annotation"><p>Tagged note.</p></div>
+<section epub:type="footnote"><p>A footnote.</p></section>
+<div epub:type="rearnote"><p>A rearnote.</p></div>
+<aside epub:type="footnote" class="annotation"><p>Classed aside.</p></aside>
+<p>After.</p>"#;
+
+        let text = extract_text_content_with(html, &TextExtractOptions::default()).unwrap();
+        assert!(text.contains("Body."));
+        assert!(!text.contains("Nested note."));
+        assert!(!text.contains("Still the note."));
+        assert!(!text.contains("Tagged note."));
+        assert!(text.contains("A footnote."));
+        assert!(text.contains("A rearnote."));
+        assert!(text.contains("Classed aside."));
+        assert!(text.contains("After."));
+
+        let options = TextExtractOptions::new().include_annotations(true);
+        let text = extract_text_content_with(html, &options).unwrap();
+        assert!(text.contains("Nested note."));
+        assert!(text.contains("Tagged note."));
+    }
+
+    #[test]
+    fn test_crlf_line_ending_option() {
+        use lexepub::core::html_parser::{
+            extract_text_content_with, LineEnding, TextExtractOptions,
+        };
+
+        let html = "<p>First paragraph.</p><p>Second paragraph.</p>";
+
+        let lf = extract_text_content_with(html, &TextExtractOptions::default()).unwrap();
+        assert_eq!(lf, "First paragraph.\nSecond paragraph.");
+
+        let options = TextExtractOptions::new().line_ending(LineEnding::CrLf);
+        let crlf = extract_text_content_with(html, &options).unwrap();
+        assert_eq!(crlf, "First paragraph.\r\nSecond paragraph.");
+    }
+
+    #[test]
+    fn test_markdown_heading_ids() {
+        use lexepub::core::html_parser::{extract_markdown_content_with, MarkdownOptions};
+
+        let html = r##"<html><head><title>Guide</title></head><body>
+            <h2 id="intro">Getting   started</h2>
+            <p>Read <a href="#setup">setup</a> first.</p>
+            <h3>Untagged</h3>
+            <ul><li>One</li><li>Two</li></ul>
+        </body></html>"##;
+
+        let plain = extract_markdown_content_with(html, &MarkdownOptions::default()).unwrap();
+        assert_eq!(
+            plain,
+            "## Getting started\n\nRead setup first.\n\n### Untagged\n\n- One\n\n- Two"
+        );
+
+        let options = MarkdownOptions::new().heading_ids(true);
+        let anchored = extract_markdown_content_with(html, &options).unwrap();
+        assert!(anchored.starts_with("## Getting started {#intro}\n\n"));
+        assert!(anchored.contains("\n### Untagged\n"));
+    }
+
+    #[cfg(feature = "cjk")]
+    #[test]
+    fn test_cjk_word_count_counts_ideographs() {
+        use lexepub::core::html_parser::cjk_word_count;
+
+        let japanese = "吾輩は猫である。名前はまだ無い。";
+        assert_eq!(japanese.split_whitespace().count(), 1);
+        // Every ideograph and kana is a word; 。 only separates
+        assert_eq!(cjk_word_count(japanese), 14);
+        assert_eq!(cjk_word_count("Rust は 速い"), 4);
+        assert_eq!(cjk_word_count("plain English text"), 3);
+    }
+
+    #[test]
+    fn test_wbr_and_zero_width_space_produce_no_output() {
+        use lexepub::core::html_parser::{extract_text_blocks, extract_text_content};
+
+        let html = "<html><body><p>long<wbr>word</p><p>super<wbr/>cali\u{200B}fragilistic<wbr>expialidocious</p></body></html>";
+        let text = extract_text_content(html).unwrap();
+        assert_eq!(text, "longword\nsupercalifragilisticexpialidocious");
+        let first_line = text.lines().next().unwrap();
+        assert_eq!(first_line.split_whitespace().count(), 1);
+
+        let blocks = extract_text_blocks(html).unwrap();
+        assert_eq!(blocks[0].text, "longword");
+        assert_eq!(blocks[1].text, "supercalifragilisticexpialidocious");
+
+        let chapter = Chapter {
+            href: "wbr.xhtml".to_string(),
+            id: "wbr".to_string(),
+            media_type: "application/xhtml+xml".to_string(),
+            content: "<html><body><p>long<wbr>word</p></body></html>"
+                .as_bytes()
+                .to_vec(),
+        };
+        let parsed = ChapterParser::new()
+            .text_only()
+            .parse_chapter(chapter)
+            .unwrap();
+        assert_eq!(parsed.content, "longword");
+        assert_eq!(parsed.word_count, 1);
+    }
+
+    #[test]
+    fn test_entities_are_decoded() {
+        use lexepub::core::html_parser::{extract_text_blocks, extract_text_content};
+
+        let text = extract_text_content("<p>Tom &amp; Jerry said &#8220;hi&#8221;</p>").unwrap();
+        assert_eq!(text, "Tom & Jerry said \u{201C}hi\u{201D}");
+
+        let text = extract_text_content(
+            "<p>It&#x2019;s&nbsp;late, &lt;b&gt; is not a tag&hellip;</p><p>a&#8203;b</p>",
+        )
+        .unwrap();
+        assert_eq!(text, "It\u{2019}s late, <b> is not a tag\u{2026}\nab");
+
+        let blocks = extract_text_blocks("<p>x&nbsp;&amp;&nbsp;y</p>").unwrap();
+        assert_eq!(blocks[0].text, "x & y");
+    }
+
+    #[test]
+    fn test_head_style_and_script_are_skipped() {
+        use lexepub::core::html_parser::{
+            extract_text_content, extract_text_content_with, TextExtractOptions,
+        };
+
+        let html = "<html><head><style>p{color:red}</style></head><body><p>Hi</p></body></html>";
+        assert_eq!(extract_text_content(html).unwrap(), "Hi");
+
+        let html = r#"<html><head><title>Chapter 1</title><style>
+p > a { color: red }</style><script type="text/javascript">var x = 1;</script></head>
+<body><style>.x{}</style><p>Body <script>alert(1)</script>text</p></body></html>"#;
+        assert_eq!(extract_text_content(html).unwrap(), "Body text");
+
+        let options = TextExtractOptions::new().include_title(true);
+        assert_eq!(
+            extract_text_content_with(html, &options).unwrap(),
+            "Chapter 1\nBody text"
+        );
+    }
+
+    #[test]
+    fn test_split_sentences_by_language() {
+        use lexepub::core::html_parser::split_sentences;
+
+        let english = "Mr. Smith met J. Doe at 5 p.m. today. Did it rain? Yes!\nNew paragraph";
+        assert_eq!(
+            split_sentences(english, Some("en-GB")),
+            vec![
+                "Mr. Smith met J. Doe at 5 p.m. today.",
+                "Did it rain?",
+                "Yes!",
+                "New paragraph"
+            ]
+        );
+
+        let german = "Sie sprach mit Hr. Weber, z.B. über Bücher. Er lachte.";
+        assert_eq!(split_sentences(german, Some("de")).len(), 2);
+        assert_eq!(split_sentences(german, None).len(), 3);
+
+        assert_eq!(
+            split_sentences("第一句。第二句。", None),
+            vec!["第一句。", "第二句。"]
+        );
+    }
+
+    #[test]
+    fn test_ast_to_html_round_trip() {
+        let ast_of = |html: &str| {
+            let chapter = Chapter::new("c.xhtml".to_string(), "c".to_string(), html.into());
+            ChapterParser::new()
+                .with_ast()
+                .parse_chapter(chapter)
+                .unwrap()
+                .ast
+                .unwrap()
+        };
+
+        let html = r#"<div id="a" class="b"><p title='x &amp; y'>A &lt;b&gt; &amp; c<br>d</p><img src="i.png" alt=""><style>p > a { color: red }</style></div>"#;
+        let serialized = ast_of(html).to_html();
+        assert_eq!(
+            serialized,
+            r#"<div class="b" id="a"><p title="x &amp; y">A &lt;b&gt; &amp; c<br />d</p><img alt="" src="i.png" /><style>p > a { color: red }</style></div>"#
+        );
+
+        // Re-parsing the output yields the same tree, and attribute order
+        // never depends on hash order
+        assert_eq!(ast_of(&serialized).to_html(), serialized);
+        let reordered = r#"<div id="a" class="b"><p title="x &amp; y">A &lt;b&gt; &amp; c<br />d</p><img src="i.png" alt="" /><style>p > a { color: red }</style></div>"#;
+        assert_eq!(ast_of(reordered).to_html(), serialized);
+    }
+
+    #[test]
+    fn test_max_ast_depth_collapses_deep_elements() {
+        fn depth(node: &AstNode) -> usize {
+            match node {
+                AstNode::Element { children, .. } => {
+                    1 + children.iter().map(depth).max().unwrap_or(0)
+                }
+                _ => 0,
+            }
+        }
+        fn text(node: &AstNode) -> String {
+            match node {
+                AstNode::Element { children, .. } => children.iter().map(text).collect(),
+                AstNode::Text { content } => content.clone(),
+                AstNode::Comment { .. } => String::new(),
+            }
+        }
+
+        let html = "<html><body><div><section><p>Deep <em>and <b>deeper</b></em> &amp; done</p></section></div><p>Top</p></body></html>";
+        let chapter = Chapter::new("c.xhtml".to_string(), "c".to_string(), html.into());
+        let parsed = ChapterParser::new()
+            .with_ast()
+            .max_ast_depth(3)
+            .parse_chapter(chapter)
+            .unwrap();
+        let ast = parsed.ast.unwrap();
+
+        // The synthetic root is not counted
+        assert_eq!(depth(&ast) - 1, 3);
+        assert_eq!(text(&ast), "Deep and deeper & doneTop");
+        assert_eq!(
+            ast.to_html(),
+            "<html><body><div>Deep and deeper &amp; done</div><p>Top</p></body></html>"
+        );
+
+        // Collapsed subtrees leave out script and style source like text
+        // extraction does
+        let html = "<html><body><div><section><p>Shown<span><script>stealCookies()</script><style>p { color: red }</style></span></p><script>alert(1)</script></section></div></body></html>";
+        let chapter = Chapter::new("c.xhtml".to_string(), "c".to_string(), html.into());
+        let ast = ChapterParser::new()
+            .with_ast()
+            .max_ast_depth(3)
+            .parse_chapter(chapter)
+            .unwrap()
+            .ast
+            .unwrap();
+        assert_eq!(text(&ast), "Shown");
+        let serialized = ast.to_html();
+        assert!(!serialized.contains("stealCookies"));
+        assert!(!serialized.contains("alert"));
+        assert!(!serialized.contains("color"));
+    }
+
+    #[test]
+    fn test_markdown_keeps_structure_and_emphasis() {
+        use lexepub::core::html_parser::extract_markdown_content;
+
+        let html = r#"<html><body>
+            <h1>Part &amp; Parcel</h1>
+            <p>Plain, <em>emphasised</em>, <i>italic</i> and <strong>strong <em>nested</em></strong> text.</p>
+            <p>An <em> padded </em> word.</p>
+            <blockquote><p>Quoted <b>line</b></p></blockquote>
+            <ul><li>First</li><li><i>Second</i></li></ul>
+            <h6>Small</h6>
+        </body></html>"#;
+
+        assert_eq!(
+            extract_markdown_content(html).unwrap(),
+            "# Part & Parcel\n\n\
+             Plain, *emphasised*, *italic* and **strong *nested*** text.\n\n\
+             An *padded* word.\n\n\
+             > Quoted **line**\n\n\
+             - First\n\n\
+             - *Second*\n\n\
+             ###### Small"
+        );
+    }
 }
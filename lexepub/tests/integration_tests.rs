@@ -142,8 +142,9 @@ mod integration_tests {
             let cover_result = epub.cover_image().await;
 
             if has_cover {
-                assert!(cover_result.is_ok());
-                let cover_data = cover_result.unwrap();
+                let cover_data = cover_result
+                    .unwrap()
+                    .expect("declared cover should be read");
                 assert!(
                     !cover_data.is_empty(),
                     "Should have cover data if has_cover is true"
@@ -162,7 +163,7 @@ mod integration_tests {
                     "Stream content differs from full vector extraction"
                 );
             } else {
-                assert!(cover_result.is_err());
+                assert!(cover_result.unwrap().is_none());
             }
         });
     }
@@ -205,6 +206,7 @@ mod integration_tests {
             assert!(!ast_chapters.is_empty());
 
             // Verify AST structure
+            assert!(ast_chapters.iter().any(|c| c.ast.is_some()));
             for chapter in ast_chapters {
                 if let Some(ast) = chapter.ast {
                     assert_ast_structure(&ast);
@@ -59,4 +59,415 @@ mod tests {
         let spine = result.unwrap();
         assert_eq!(spine, vec!["chapter1", "chapter2"]);
     }
+
+    #[test]
+    fn test_parse_metadata_after_stylesheet_pi() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<?xml-stylesheet type="text/xsl" href="opf.xsl"?>
+<!-- generated by a build tool -->
+<opf:package version="3.0" unique-identifier="uid" xmlns:opf="http://www.idpf.org/2007/opf" xmlns:dc="http://purl.org/dc/elements/1.1/">
+  <opf:metadata>
+    <dc:identifier id="uid">urn:uuid:1234</dc:identifier>
+    <dc:title>Styled Package</dc:title>
+  </opf:metadata>
+  <opf:manifest>
+    <opf:item id="chapter1" href="chapter1.xhtml" media-type="application/xhtml+xml"/>
+  </opf:manifest>
+  <opf:spine>
+    <opf:itemref idref="chapter1"/>
+  </opf:spine>
+</opf:package>"#;
+
+        let mut parser = OpfParser::new();
+        let metadata = parser.parse_metadata(xml.as_bytes()).unwrap();
+        assert_eq!(metadata.version, Some("3.0".to_string()));
+        assert_eq!(
+            metadata.unique_identifier,
+            Some("urn:uuid:1234".to_string())
+        );
+        assert_eq!(metadata.title, Some("Styled Package".to_string()));
+        assert_eq!(metadata.spine, vec!["chapter1"]);
+    }
+
+    #[test]
+    fn test_creator_alternate_script() {
+        let xml = r##"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>Kokoro</dc:title>
+    <dc:creator id="creator">Natsume Soseki</dc:creator>
+    <meta refines="#creator" property="alternate-script" xml:lang="ja">夏目 漱石</meta>
+    <dc:creator>Edwin McClellan</dc:creator>
+  </metadata>
+</package>"##;
+
+        let mut parser = OpfParser::new();
+        let metadata = parser.parse_metadata(xml.as_bytes()).unwrap();
+        assert_eq!(metadata.creators, vec!["Natsume Soseki", "Edwin McClellan"]);
+
+        let soseki = &metadata.creator_details[0];
+        assert_eq!(soseki.name, "Natsume Soseki");
+        assert_eq!(
+            soseki.alternates,
+            vec![("ja".to_string(), "夏目 漱石".to_string())]
+        );
+        assert!(metadata.creator_details[1].alternates.is_empty());
+    }
+
+    #[test]
+    fn test_creator_role_and_file_as() {
+        let xml = r##"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>Roles</dc:title>
+    <dc:creator id="author">Mark Twain</dc:creator>
+    <meta refines="#author" property="role" scheme="marc:relators">aut</meta>
+    <meta refines="#author" property="file-as">Twain, Mark</meta>
+    <dc:creator id="illustrator">E. W. Kemble</dc:creator>
+    <meta refines="#illustrator" property="role" scheme="marc:relators">ill</meta>
+    <dc:creator>Anonymous</dc:creator>
+  </metadata>
+</package>"##;
+        let metadata: lexepub::EpubMetadata = OpfParser::new()
+            .parse_metadata(xml.as_bytes())
+            .unwrap()
+            .into();
+        assert_eq!(
+            metadata.authors,
+            vec!["Mark Twain", "E. W. Kemble", "Anonymous"]
+        );
+        let roles: Vec<(Option<&str>, Option<&str>)> = metadata
+            .creators
+            .iter()
+            .map(|c| (c.role.as_deref(), c.file_as.as_deref()))
+            .collect();
+        assert_eq!(
+            roles,
+            [
+                (Some("aut"), Some("Twain, Mark")),
+                (Some("ill"), None),
+                (None, None)
+            ]
+        );
+
+        // EPUB2 puts the same information in opf: attributes
+        let xml = r#"<?xml version="1.0"?>
+<package version="2.0" xmlns="http://www.idpf.org/2007/opf" xmlns:opf="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>Roles</dc:title>
+    <dc:creator opf:role="aut" opf:file-as="Austen, Jane">Jane Austen</dc:creator>
+  </metadata>
+</package>"#;
+        let metadata = OpfParser::new().parse_metadata(xml.as_bytes()).unwrap();
+        assert_eq!(metadata.creator_details[0].role.as_deref(), Some("aut"));
+        assert_eq!(
+            metadata.creator_details[0].file_as.as_deref(),
+            Some("Austen, Jane")
+        );
+    }
+
+    #[test]
+    fn test_primary_language() {
+        use lexepub::EpubMetadata;
+
+        let unmarked = r##"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:language>en</dc:language>
+    <dc:language id="lang-fr">fr</dc:language>
+    <meta refines="#lang-fr" property="primary">false</meta>
+  </metadata>
+</package>"##;
+        let metadata: EpubMetadata = OpfParser::new()
+            .parse_metadata(unmarked.as_bytes())
+            .unwrap()
+            .into();
+        assert_eq!(metadata.languages, vec!["en", "fr"]);
+        assert_eq!(metadata.primary_language(), Some("en"));
+
+        let marked = r##"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:language>en</dc:language>
+    <dc:language id="lang-ja">ja</dc:language>
+    <meta refines="#lang-ja" property="primary">true</meta>
+  </metadata>
+</package>"##;
+        let metadata: EpubMetadata = OpfParser::new()
+            .parse_metadata(marked.as_bytes())
+            .unwrap()
+            .into();
+        assert_eq!(metadata.primary_language(), Some("ja"));
+    }
+
+    #[test]
+    fn test_display_seq_orders_titles_and_creators() {
+        let xml = r##"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title id="sub">A Novel</dc:title>
+    <meta refines="#sub" property="display-seq">2</meta>
+    <dc:title id="series">The Sea Trilogy</dc:title>
+    <dc:title id="main">The Tide</dc:title>
+    <meta refines="#main" property="display-seq">1</meta>
+    <dc:creator id="ill">Illustrator Name</dc:creator>
+    <meta refines="#ill" property="display-seq">2</meta>
+    <dc:creator id="aut">Author Name</dc:creator>
+    <meta refines="#aut" property="display-seq">1</meta>
+  </metadata>
+</package>"##;
+
+        let metadata = OpfParser::new().parse_metadata(xml.as_bytes()).unwrap();
+        assert_eq!(
+            metadata.titles,
+            vec!["The Tide", "A Novel", "The Sea Trilogy"]
+        );
+        assert_eq!(metadata.creators, vec!["Author Name", "Illustrator Name"]);
+        assert_eq!(metadata.creator_details[0].id.as_deref(), Some("aut"));
+
+        let metadata: lexepub::EpubMetadata = metadata.into();
+        assert_eq!(metadata.title_parts[0], "The Tide");
+        assert_eq!(metadata.authors[0], "Author Name");
+    }
+
+    #[test]
+    fn test_nested_collections() {
+        use lexepub::Collection;
+
+        let xml = r##"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>The Tide</dc:title>
+    <meta property="belongs-to-collection" id="c01">The Sea Trilogy</meta>
+    <meta refines="#c01" property="collection-type">series</meta>
+    <meta refines="#c01" property="group-position">2</meta>
+    <meta refines="#c01" property="belongs-to-collection" id="c02">Collected Sea Stories</meta>
+    <meta refines="#c02" property="collection-type">set</meta>
+    <meta refines="#c02" property="group-position">1.5</meta>
+  </metadata>
+</package>"##;
+
+        let metadata: lexepub::EpubMetadata = OpfParser::new()
+            .parse_metadata(xml.as_bytes())
+            .unwrap()
+            .into();
+        assert_eq!(
+            metadata.collections,
+            vec![
+                Collection {
+                    name: "The Sea Trilogy".to_string(),
+                    collection_type: Some("series".to_string()),
+                    position: Some(2.0),
+                    parent: Some(1),
+                },
+                Collection {
+                    name: "Collected Sea Stories".to_string(),
+                    collection_type: Some("set".to_string()),
+                    position: Some(1.5),
+                    parent: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collection_refines_chain_builds_hierarchy() {
+        // Parents may be declared after the collections they contain
+        let xml = r##"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>Book Two</dc:title>
+    <meta property="belongs-to-collection" id="arc">The Northern Arc</meta>
+    <meta refines="#arc" property="collection-type">series</meta>
+    <meta refines="#series" property="belongs-to-collection" id="universe">Skyward Universe</meta>
+    <meta refines="#arc" property="belongs-to-collection" id="series">Skyward Saga</meta>
+    <meta refines="#series" property="group-position">3</meta>
+    <meta property="belongs-to-collection" id="other">Award Winners</meta>
+  </metadata>
+</package>"##;
+
+        let metadata = OpfParser::new().parse_metadata(xml.as_bytes()).unwrap();
+        let names: Vec<_> = metadata
+            .collections
+            .iter()
+            .map(|c| (c.name.as_str(), c.parent))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("The Northern Arc", Some(2)),
+                ("Skyward Universe", None),
+                ("Skyward Saga", Some(1)),
+                ("Award Winners", None),
+            ]
+        );
+        assert_eq!(metadata.collections[2].position, Some(3.0));
+    }
+
+    #[test]
+    fn test_parse_unhandled_lists_unmapped_metadata() {
+        let xml = r##"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title id="t">Vendor Book</dc:title>
+    <meta refines="#t" property="display-seq">1</meta>
+    <meta property="vendor:foo">bar</meta>
+    <meta property="vendor:foo">baz</meta>
+    <meta name="calibre:series" content="Tales"/>
+    <meta name="cover" content="img"/>
+    <dc:coverage>Europe</dc:coverage>
+    <link rel="record" href="meta.xml"/>
+  </metadata>
+  <manifest>
+    <item id="c1" href="c1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="img" href="c.jpg" media-type="image/jpeg"/>
+    <item id="mo" href="c1.smil" media-type="application/smil+xml"/>
+    <item id="a" href="a.mp3" media-type="audio/mpeg"/>
+  </manifest>
+  <spine><itemref idref="c1"/></spine>
+</package>"##;
+
+        let unhandled = OpfParser::new().parse_unhandled(xml.as_bytes()).unwrap();
+        assert_eq!(
+            unhandled,
+            vec![
+                "meta property vendor:foo",
+                "meta name calibre:series",
+                "element dc:coverage",
+                "link rel record",
+                "media-type application/smil+xml",
+                "media-type audio/mpeg",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_identifiers_keep_scheme_and_unique_id() {
+        let xml = r#"<?xml version="1.0"?>
+<package version="2.0" unique-identifier="BookId" xmlns="http://www.idpf.org/2007/opf" xmlns:opf="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>Identified</dc:title>
+    <dc:identifier opf:scheme="UUID" id="BookId">urn:uuid:0b5e7d1a-1111-4c3b-9f00-123456789abc</dc:identifier>
+    <dc:identifier opf:scheme="ISBN">9780141439600</dc:identifier>
+    <dc:identifier>local-42</dc:identifier>
+  </metadata>
+</package>"#;
+        let metadata: lexepub::EpubMetadata = OpfParser::new()
+            .parse_metadata(xml.as_bytes())
+            .unwrap()
+            .into();
+        let schemes: Vec<(Option<&str>, bool)> = metadata
+            .identifiers
+            .iter()
+            .map(|id| (id.scheme.as_deref(), id.is_unique_id))
+            .collect();
+        assert_eq!(
+            schemes,
+            [(Some("UUID"), true), (Some("ISBN"), false), (None, false)]
+        );
+        assert_eq!(metadata.identifiers[2].value, "local-42");
+        assert_eq!(metadata.isbn(), Some("9780141439600"));
+
+        // EPUB3 drops opf:scheme; URN values still say what they are
+        let xml = r#"<?xml version="1.0"?>
+<package version="3.0" unique-identifier="pub-id" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>Identified</dc:title>
+    <dc:identifier id="pub-id">urn:isbn:9780000000002</dc:identifier>
+  </metadata>
+</package>"#;
+        let metadata: lexepub::EpubMetadata = OpfParser::new()
+            .parse_metadata(xml.as_bytes())
+            .unwrap()
+            .into();
+        assert_eq!(metadata.identifiers[0].scheme.as_deref(), Some("ISBN"));
+        assert!(metadata.identifiers[0].is_unique_id);
+        assert_eq!(metadata.isbn(), Some("9780000000002"));
+    }
+
+    #[test]
+    fn test_dates_keep_their_events() {
+        let xml = r#"<?xml version="1.0"?>
+<package version="2.0" xmlns="http://www.idpf.org/2007/opf" xmlns:opf="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>Dated</dc:title>
+    <dc:date opf:event="publication">1851-10-18</dc:date>
+    <dc:date opf:event="modification">2020-01-02</dc:date>
+  </metadata>
+</package>"#;
+        let opf = OpfParser::new().parse_metadata(xml.as_bytes()).unwrap();
+        assert_eq!(
+            opf.dates,
+            vec![
+                (Some("publication".to_string()), "1851-10-18".to_string()),
+                (Some("modification".to_string()), "2020-01-02".to_string()),
+            ]
+        );
+        assert_eq!(opf.date.as_deref(), Some("1851-10-18"));
+
+        let metadata: lexepub::EpubMetadata = opf.into();
+        assert_eq!(metadata.publication_date.as_deref(), Some("1851-10-18"));
+        assert_eq!(metadata.modified_date(), Some("2020-01-02"));
+        assert_eq!(metadata.dates.len(), 2);
+
+        // EPUB3: a single event-less dc:date plus dcterms:modified
+        let xml = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>Dated</dc:title>
+    <meta property="dcterms:modified">2024-05-06T07:08:09Z</meta>
+    <dc:date>2001</dc:date>
+  </metadata>
+</package>"#;
+        let metadata: lexepub::EpubMetadata = OpfParser::new()
+            .parse_metadata(xml.as_bytes())
+            .unwrap()
+            .into();
+        assert_eq!(metadata.dates, vec![(None, "2001".to_string())]);
+        assert_eq!(metadata.publication_date.as_deref(), Some("2001"));
+        assert_eq!(metadata.modified_date(), Some("2024-05-06T07:08:09Z"));
+    }
+
+    #[test]
+    fn test_package_level_refinements() {
+        let package = |metas: &str| {
+            format!(
+                r##"<?xml version="1.0"?>
+<package version="3.0" unique-identifier="pub-id" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:identifier id="pub-id">urn:isbn:9780000000000</dc:identifier>
+    <dc:title id="t">Paged</dc:title>
+    {metas}
+  </metadata>
+</package>"##
+            )
+        };
+        let parse = |metas: &str| {
+            let metadata: lexepub::EpubMetadata = OpfParser::new()
+                .parse_metadata(package(metas).as_bytes())
+                .unwrap()
+                .into();
+            (metadata.number_of_pages, metadata.copyright_date)
+        };
+
+        assert_eq!(
+            parse(
+                r#"<meta property="schema:numberOfPages"> 352 </meta><meta property="dcterms:dateCopyrighted">1999</meta>"#
+            ),
+            (Some(352), Some("1999".to_string()))
+        );
+        assert_eq!(
+            parse(r##"<meta refines="#pub-id" property="schema:numberOfPages">128</meta>"##),
+            (Some(128), None)
+        );
+        // A refinement of another element is not about the whole book
+        assert_eq!(
+            parse(r##"<meta refines="#t" property="schema:numberOfPages">9</meta>"##),
+            (None, None)
+        );
+        assert_eq!(
+            parse(r#"<meta property="schema:numberOfPages">about 300</meta>"#),
+            (None, None)
+        );
+    }
 }
@@ -1,3 +1,5 @@
+mod common;
+
 use futures::StreamExt;
 use lexepub::epub::extract_text_only;
 use lexepub::prelude::*;
@@ -62,9 +64,183 @@ fn test_partial_stream_consumption_then_full_extract() {
     });
 }
 
+#[test]
+fn test_ast_stream_matches_eager_ast() {
+    futures::executor::block_on(async {
+        let path = Path::new("examples/epubs/test-book.epub");
+        if !path.exists() {
+            return;
+        }
+
+        let mut epub = LexEpub::open(path).await.unwrap();
+        let mut stream = epub.extract_ast_stream().await.unwrap();
+        let mut streamed = Vec::new();
+        while let Some(chapter) = stream.next().await {
+            let chapter = chapter.unwrap();
+            assert!(chapter.ast.is_some(), "{}", chapter.chapter_info.href);
+            streamed.push(chapter);
+        }
+        assert!(!streamed.is_empty());
+
+        let eager = epub.extract_ast().await.unwrap();
+        assert_eq!(streamed.len(), eager.len());
+        for (streamed, eager) in streamed.iter().zip(&eager) {
+            assert_eq!(streamed.content, eager.content);
+            assert_eq!(
+                serde_json::to_value(&streamed.ast).unwrap(),
+                serde_json::to_value(&eager.ast).unwrap(),
+                "{}",
+                eager.chapter_info.href
+            );
+        }
+
+        // Plain streams stay text-only
+        let mut stream = epub.extract_chapters_stream().await.unwrap();
+        assert!(stream.next().await.unwrap().unwrap().ast.is_none());
+    });
+}
+
 #[test]
 fn test_chapterstream_type_is_stream() {
     // compile-time trait check
     fn _assert_stream<S: futures::Stream<Item = lexepub::Result<lexepub::ParsedChapter>>>() {}
     _assert_stream::<lexepub::ChapterStream>();
 }
+
+#[test]
+fn test_write_jsonl_one_line_per_chapter() {
+    futures::executor::block_on(async {
+        let path = Path::new("examples/epubs/test-book.epub");
+        if !path.exists() {
+            return;
+        }
+
+        let mut epub = LexEpub::open(path).await.unwrap();
+        let chapter_count = epub.extract_text_only().await.unwrap().len();
+
+        let mut buffer = futures::io::Cursor::new(Vec::new());
+        epub.write_jsonl(&mut buffer).await.unwrap();
+        let output = String::from_utf8(buffer.into_inner()).unwrap();
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), chapter_count);
+        for (index, line) in lines.iter().enumerate() {
+            let value: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(value["index"], index);
+            assert!(value["href"].is_string());
+            assert!(value["text"].is_string());
+        }
+    });
+}
+
+/// Three-chapter book whose middle chapter is in the manifest but missing
+/// from the archive
+fn book_with_missing_chapter() -> bytes::Bytes {
+    let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Gap</dc:title></metadata>
+  <manifest>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch2" href="ch2.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch3" href="ch3.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="ch1"/><itemref idref="ch2"/><itemref idref="ch3"/></spine>
+</package>"#;
+    let ch1 = common::xhtml("One", "<p>First chapter</p>");
+    let ch3 = common::xhtml("Three", "<p>Third chapter</p>");
+    common::build_epub(opf, &[("OEBPS/ch1.xhtml", &ch1), ("OEBPS/ch3.xhtml", &ch3)])
+}
+
+async fn streamed_texts(epub: &mut LexEpub) -> Vec<Result<String>> {
+    let stream = epub.extract_chapters_stream().await.unwrap();
+    stream
+        .map(|chapter| chapter.map(|c| c.content))
+        .collect()
+        .await
+}
+
+#[test]
+fn test_lenient_mode_carries_on_past_missing_chapter_in_both_paths() {
+    futures::executor::block_on(async {
+        let mut epub = LexEpub::from_bytes(book_with_missing_chapter())
+            .await
+            .unwrap();
+        assert!(!epub.options().strict);
+
+        // Eager extraction skips the chapter; the stream reports it and
+        // carries on
+        let eager = epub.extract_text_only().await.unwrap();
+        let streamed = streamed_texts(&mut epub).await;
+
+        assert_eq!(eager.len(), 2);
+        assert!(eager[1].contains("Third chapter"));
+        assert_eq!(streamed.len(), 3);
+        assert_eq!(streamed[0].as_ref().unwrap(), &eager[0]);
+        match &streamed[1] {
+            Err(LexEpubError::ChapterFailed { href, .. }) => assert_eq!(href, "OEBPS/ch2.xhtml"),
+            other => panic!("expected ChapterFailed, got {:?}", other),
+        }
+        assert_eq!(streamed[2].as_ref().unwrap(), &eager[1]);
+    });
+}
+
+#[test]
+fn test_strict_mode_fails_on_missing_chapter_in_both_paths() {
+    futures::executor::block_on(async {
+        let mut epub = LexEpub::from_bytes(book_with_missing_chapter())
+            .await
+            .unwrap();
+        epub.set_options(LexEpubOptions::new().strict(true));
+
+        match epub.extract_text_only().await {
+            Err(LexEpubError::ChapterFailed { href, source }) => {
+                assert_eq!(href, "OEBPS/ch2.xhtml");
+                assert!(matches!(*source, LexEpubError::MissingFile(_)));
+            }
+            other => panic!("expected ChapterFailed, got {:?}", other),
+        }
+
+        let streamed = streamed_texts(&mut epub).await;
+        assert_eq!(streamed.len(), 2, "stream must stop after the failure");
+        assert!(streamed[0].as_ref().unwrap().contains("First chapter"));
+        match &streamed[1] {
+            Err(LexEpubError::ChapterFailed { href, .. }) => assert_eq!(href, "OEBPS/ch2.xhtml"),
+            other => panic!("expected ChapterFailed, got {:?}", other),
+        }
+    });
+}
+
+#[test]
+fn test_stream_reports_unresolved_spine_idref_in_place() {
+    futures::executor::block_on(async {
+        let opf = r#"<?xml version="1.0"?>
+<package version="3.0" xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Ghost</dc:title></metadata>
+  <manifest>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch3" href="ch3.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="ch1"/><itemref idref="ghost"/><itemref idref="ch3"/></spine>
+</package>"#;
+        let ch1 = common::xhtml("One", "<p>First chapter</p>");
+        let ch3 = common::xhtml("Three", "<p>Third chapter</p>");
+        let data = common::build_epub(opf, &[("OEBPS/ch1.xhtml", &ch1), ("OEBPS/ch3.xhtml", &ch3)]);
+
+        let mut epub = LexEpub::from_bytes(data.clone()).await.unwrap();
+        let streamed = streamed_texts(&mut epub).await;
+        assert_eq!(streamed.len(), 3);
+        match &streamed[1] {
+            Err(LexEpubError::MissingFile(message)) => {
+                assert_eq!(message, "Spine item 'ghost' is not in the manifest")
+            }
+            other => panic!("expected MissingFile, got {:?}", other),
+        }
+        assert!(streamed[2].as_ref().unwrap().contains("Third chapter"));
+
+        let mut epub = LexEpub::from_bytes(data).await.unwrap();
+        epub.set_options(LexEpubOptions::new().strict(true));
+        let streamed = streamed_texts(&mut epub).await;
+        assert_eq!(streamed.len(), 2, "stream must stop after the failure");
+        assert!(streamed[1].is_err());
+    });
+}